@@ -0,0 +1,377 @@
+//! Character devices, the drivers behind the nodes of devfs
+
+use alloc::{collections::VecDeque, string::String, vec::Vec};
+use core::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{process::WaitQueue, syscall::generated, vfs::Error};
+
+/// A device that is read and written through its driver.
+/// Devices with [`Device::input`] are streams, the others are read and written at an offset.
+pub trait Device: Send + Sync {
+    /// The name of the node in /dev
+    fn name(&self) -> &'static str;
+
+    /// Reads into `buffer` from `offset`, devices without contents ignore the offset
+    fn read(&self, _offset: usize, _buffer: &mut [u8]) -> Result<usize, Error> {
+        Ok(0)
+    }
+
+    fn write(&self, offset: usize, bytes: &[u8]) -> Result<usize, Error>;
+
+    /// Handles a request like [`generated::IOCTL_TTY_GET_WINDOW_SIZE`], `arg` is read and written by the caller
+    fn ioctl(&self, _request: u32, _arg: &mut [u8]) -> Result<(), Error> {
+        Err(Error::NotSupported)
+    }
+
+    /// The bytes that arrived at the device, reads take them instead of calling [`Device::read`]
+    fn input(&self) -> Option<&spin::Mutex<Input>> {
+        None
+    }
+
+    /// Number of bytes that can be read and written at offsets
+    fn size(&self) -> usize {
+        0
+    }
+}
+
+impl core::fmt::Debug for dyn Device {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Device({})", self.name())
+    }
+}
+
+/// Number of bytes an input buffers before further bytes are dropped
+pub const INPUT_CAPACITY: usize = 1024;
+
+/// The bytes that arrived at a device and were not read yet.
+/// Unlike an owned stream, everyone who has the device open reads from the same input.
+#[derive(Debug)]
+pub struct Input {
+    buffer: VecDeque<u8>,
+    /// threads waiting for input
+    pub readers: WaitQueue,
+}
+
+impl Input {
+    pub const fn new() -> Self {
+        Input {
+            buffer: VecDeque::new(),
+            readers: WaitQueue::new(),
+        }
+    }
+
+    /// Copies up to `len` bytes, `None` if nothing arrived yet.
+    /// They stay in the input until they are [consumed](Input::consume).
+    pub fn peek(&self, len: usize) -> Option<Vec<u8>> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        Some(self.buffer.iter().take(len).copied().collect())
+    }
+
+    /// Removes the first `len` bytes, once the reader received them
+    pub fn consume(&mut self, len: usize) {
+        self.buffer.drain(..len);
+    }
+}
+
+/// Appends `bytes` to the input of `device` and wakes its readers.
+/// The processes must not be borrowed.
+pub fn feed(device: &dyn Device, bytes: &[u8]) {
+    let Some(input) = device.input() else {
+        return;
+    };
+    let mut input = input.try_lock().expect("Failed to lock device input");
+
+    if input.buffer.len() + bytes.len() > INPUT_CAPACITY {
+        log::warn!(
+            "{} input overflow: dropping {} bytes",
+            device.name(),
+            bytes.len()
+        );
+        return;
+    }
+    input.buffer.extend(bytes);
+
+    let woken = input.readers.take();
+    drop(input);
+    crate::process::wake(woken);
+}
+
+/// Copies `value` into the argument of an ioctl, which has to have the size of `T`
+fn write_arg<T: Copy>(arg: &mut [u8], value: &T) -> Result<(), Error> {
+    if arg.len() != size_of::<T>() {
+        return Err(Error::InvalidInput);
+    }
+
+    let bytes =
+        unsafe { core::slice::from_raw_parts(core::ptr::from_ref(value).cast::<u8>(), arg.len()) };
+    arg.copy_from_slice(bytes);
+    Ok(())
+}
+
+/// Discards everything written to it, reading gives end of file
+pub struct Null;
+
+pub static NULL: Null = Null;
+
+impl Device for Null {
+    fn name(&self) -> &'static str {
+        "null"
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        Ok(bytes.len())
+    }
+}
+
+/// Reads as many zeros as requested, writes are discarded
+pub struct Zero;
+
+pub static ZERO: Zero = Zero;
+
+impl Device for Zero {
+    fn name(&self) -> &'static str {
+        "zero"
+    }
+
+    fn read(&self, _offset: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        buffer.fill(0);
+        Ok(buffer.len())
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        Ok(bytes.len())
+    }
+}
+
+/// Random bytes from RDRAND, or from a xorshift generator seeded with the time stamp counter on CPUs without it.
+/// Written bytes are mixed into the state of the generator.
+pub struct Random {
+    state: AtomicU64,
+}
+
+pub static RANDOM: Random = Random {
+    state: AtomicU64::new(0),
+};
+
+impl Random {
+    fn next(&self) -> u64 {
+        if let Some(value) = x86_64::instructions::random::RdRand::new()
+            .and_then(x86_64::instructions::random::RdRand::get_u64)
+        {
+            return value;
+        }
+
+        let mut x = self.state.load(Ordering::Relaxed);
+        if x == 0 {
+            x = unsafe { core::arch::x86_64::_rdtsc() } | 1;
+        }
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state.store(x, Ordering::Relaxed);
+        x
+    }
+}
+
+impl Device for Random {
+    fn name(&self) -> &'static str {
+        "random"
+    }
+
+    fn read(&self, _offset: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        for chunk in buffer.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next().to_le_bytes()[..chunk.len()]);
+        }
+        Ok(buffer.len())
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        for chunk in bytes.chunks(8) {
+            let mut value = [0; 8];
+            value[..chunk.len()].copy_from_slice(chunk);
+            self.state
+                .fetch_xor(u64::from_le_bytes(value), Ordering::Relaxed);
+        }
+        Ok(bytes.len())
+    }
+}
+
+/// Writes `bytes` to the terminal on the framebuffer, invalid UTF-8 is replaced
+fn write_terminal(bytes: &[u8]) {
+    crate::term::TERM
+        .writer()
+        .write_str(&String::from_utf8_lossy(bytes))
+        .expect("Failed to write to terminal");
+}
+
+fn window_size(request: u32, arg: &mut [u8]) -> Result<(), Error> {
+    match request {
+        generated::IOCTL_TTY_GET_WINDOW_SIZE => write_arg(
+            arg,
+            &generated::tty_window_size_t {
+                rows: crate::term::TERM.rows() as u32,
+                columns: crate::term::TERM.columns() as u32,
+            },
+        ),
+        _ => Err(Error::NotSupported),
+    }
+}
+
+/// The terminal on the framebuffer with the keyboard as its input
+pub struct Tty {
+    input: spin::Mutex<Input>,
+}
+
+pub static TTY: Tty = Tty {
+    input: spin::Mutex::new(Input::new()),
+};
+
+impl Device for Tty {
+    fn name(&self) -> &'static str {
+        "tty"
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        write_terminal(bytes);
+        Ok(bytes.len())
+    }
+
+    fn ioctl(&self, request: u32, arg: &mut [u8]) -> Result<(), Error> {
+        window_size(request, arg)
+    }
+
+    fn input(&self) -> Option<&spin::Mutex<Input>> {
+        Some(&self.input)
+    }
+}
+
+/// The console the kernel logs to, written to the terminal and the serial port.
+/// It shares its input with [`TTY`].
+pub struct Console;
+
+pub static CONSOLE: Console = Console;
+
+impl Device for Console {
+    fn name(&self) -> &'static str {
+        "console"
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        write_terminal(bytes);
+        if let Ok(com1) = super::serial::com1() {
+            write_serial(com1, bytes);
+        }
+        Ok(bytes.len())
+    }
+
+    fn ioctl(&self, request: u32, arg: &mut [u8]) -> Result<(), Error> {
+        window_size(request, arg)
+    }
+
+    fn input(&self) -> Option<&spin::Mutex<Input>> {
+        TTY.input()
+    }
+}
+
+fn write_serial(port: &super::serial::SerialPort, bytes: &[u8]) {
+    let mut writer = port.writer();
+    for &byte in bytes {
+        writer
+            .write_char(byte as char)
+            .expect("Failed to write to serial port");
+    }
+}
+
+/// The first serial port, only present in /dev if the port works
+pub struct SerialTty {
+    input: spin::Mutex<Input>,
+}
+
+pub static TTY_S0: SerialTty = SerialTty {
+    input: spin::Mutex::new(Input::new()),
+};
+
+impl Device for SerialTty {
+    fn name(&self) -> &'static str {
+        "ttyS0"
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        let port = super::serial::com1().map_err(|_| Error::NotSupported)?;
+        write_serial(port, bytes);
+        Ok(bytes.len())
+    }
+
+    fn input(&self) -> Option<&spin::Mutex<Input>> {
+        Some(&self.input)
+    }
+}
+
+/// The pixels of the framebuffer, 32 bits each, row by row
+pub struct Framebuffer;
+
+pub static FB0: Framebuffer = Framebuffer;
+
+impl Framebuffer {
+    /// Number of bytes from `offset` to the end of the pixels that fit into `len`
+    fn available(&self, offset: usize, len: usize) -> usize {
+        len.min(self.size().saturating_sub(offset))
+    }
+}
+
+impl Device for Framebuffer {
+    fn name(&self) -> &'static str {
+        "fb0"
+    }
+
+    fn read(&self, offset: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        let len = self.available(offset, buffer.len());
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                crate::term::TERM.ptr_pixels.cast::<u8>().add(offset),
+                buffer.as_mut_ptr(),
+                len,
+            );
+        }
+        Ok(len)
+    }
+
+    fn write(&self, offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        let len = self.available(offset, bytes.len());
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                crate::term::TERM.ptr_pixels.cast::<u8>().add(offset),
+                len,
+            );
+        }
+        Ok(len)
+    }
+
+    fn ioctl(&self, request: u32, arg: &mut [u8]) -> Result<(), Error> {
+        let term = &*crate::term::TERM;
+        match request {
+            generated::IOCTL_FRAMEBUFFER_GET_INFO => write_arg(
+                arg,
+                &generated::framebuffer_info_t {
+                    width: term.width_pixels as u32,
+                    height: term.height_pixels as u32,
+                    pitch: (term.width_pixels * 4) as u32,
+                    bits_per_pixel: 32,
+                },
+            ),
+            _ => Err(Error::NotSupported),
+        }
+    }
+
+    fn size(&self) -> usize {
+        let term = &*crate::term::TERM;
+        term.width_pixels * term.height_pixels * 4
+    }
+}
@@ -58,6 +58,11 @@ pub static mut TIMER0: Timer = Timer {
     ticks: 0,
 };
 
+/// The system timer, it is only changed by its interrupt handler after it was initialized
+pub fn timer0() -> &'static Timer {
+    unsafe { &*core::ptr::addr_of!(TIMER0) }
+}
+
 pub static mut TIMER1: Timer = Timer {
     channel: Channel::CH1,
     access_mode: AccessMode::LoHiByte,
@@ -175,4 +180,12 @@ impl Timer {
     pub fn ticks(&self) -> u64 {
         self.ticks
     }
+
+    pub fn ms_to_ticks(&self, ms: u64) -> u64 {
+        ms * u64::from(self.frequency) / 1000
+    }
+
+    pub fn ticks_to_ms(&self, ticks: u64) -> u64 {
+        ticks * 1000 / u64::from(self.frequency)
+    }
 }
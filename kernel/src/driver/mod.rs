@@ -1,3 +1,4 @@
+pub mod device;
 pub mod i8253;
 pub mod pci;
 pub mod rtc;
@@ -3,19 +3,39 @@ use alloc::vec::Vec;
 use self::class::ClassCode;
 mod class;
 
-fn pci_config_read_word(bus: u8, slot: u8, func: u8, offset: u8) -> u16 {
-    let address = 0x80000000
-        | ((bus as u32) << 16)
-        | ((slot as u32) << 11)
-        | ((func as u32) << 8)
-        | ((offset as u32) & 0xfc);
+fn pci_config_address(bus: u8, slot: u8, func: u8, offset: u8) -> u32 {
+    0x8000_0000
+        | (u32::from(bus) << 16)
+        | (u32::from(slot) << 11)
+        | (u32::from(func) << 8)
+        | (u32::from(offset) & 0xfc)
+}
 
+fn pci_config_read_dword(bus: u8, slot: u8, func: u8, offset: u8) -> u32 {
     unsafe {
-        x86_64::instructions::port::PortWrite::write_to_port(0xcf8, address);
+        x86_64::instructions::port::PortWrite::write_to_port(
+            0xcf8,
+            pci_config_address(bus, slot, func, offset),
+        );
         x86_64::instructions::port::PortRead::read_from_port(0xcfc)
     }
 }
 
+fn pci_config_write_dword(bus: u8, slot: u8, func: u8, offset: u8, value: u32) {
+    unsafe {
+        x86_64::instructions::port::PortWrite::write_to_port(
+            0xcf8,
+            pci_config_address(bus, slot, func, offset),
+        );
+        x86_64::instructions::port::PortWrite::write_to_port(0xcfc, value);
+    }
+}
+
+/// Reads the 16 bits at `offset`, for byte fields the upper bits are cut off by the caller
+fn pci_config_read_word(bus: u8, slot: u8, func: u8, offset: u8) -> u16 {
+    (pci_config_read_dword(bus, slot, func, offset) >> ((offset & 3) * 8)) as u16
+}
+
 pub fn scan() -> anyhow::Result<Vec<PCIDevice>> {
     Ok((0..=255)
         .flat_map(|bus| (0..=31).map(move |device| (bus, device)))
@@ -27,28 +47,147 @@ pub fn scan() -> anyhow::Result<Vec<PCIDevice>> {
         .collect::<Vec<PCIDevice>>())
 }
 
+static DEVICES: spin::Once<Vec<PCIDevice>> = spin::Once::new();
+
+/// Scans the buses once, later calls to [`devices`] return what was found
+pub fn init() {
+    DEVICES.call_once(|| {
+        let devices = scan().expect("Failed to scan PCI devices!");
+        log::info!("found {} PCI devices", devices.len());
+        devices
+    });
+}
+
+/// The devices found by [`init`], empty before it ran
+pub fn devices() -> &'static [PCIDevice] {
+    DEVICES.get().map_or(&[], Vec::as_slice)
+}
+
+/// Size of the configuration space of a function
+pub const CONFIG_SPACE_SIZE: usize = 256;
+
+/// A decoded base address register
+#[derive(Debug, Clone, Copy)]
+pub enum Bar {
+    Memory {
+        address: u64,
+        size: u64,
+        prefetchable: bool,
+        /// the register is combined with the next one to a 64 bit address
+        is_64bit: bool,
+    },
+    Io {
+        port: u32,
+        size: u32,
+    },
+}
+
+/// Decodes the first `count` base address registers, their size is found by writing ones and reading back the mask.
+/// Memory and I/O decoding is turned off while the registers are probed.
+fn read_bars(bus: u8, device: u8, function: u8, count: u8) -> Vec<(u8, Bar)> {
+    const COMMAND_DECODE: u32 = 0b11;
+
+    // the status register in the upper half is cleared by writing ones, so only the command is written back
+    let command = u32::from(pci_config_read_word(bus, device, function, 4));
+    pci_config_write_dword(bus, device, function, 4, command & !COMMAND_DECODE);
+
+    let probe = |index: u8| {
+        let offset = 16 + index * 4;
+        let original = pci_config_read_dword(bus, device, function, offset);
+        pci_config_write_dword(bus, device, function, offset, 0xffff_ffff);
+        let mask = pci_config_read_dword(bus, device, function, offset);
+        pci_config_write_dword(bus, device, function, offset, original);
+        (original, mask)
+    };
+
+    let mut bars = Vec::new();
+    let mut index = 0;
+    while index < count {
+        let (original, mask) = probe(index);
+
+        if original & 1 == 1 {
+            let mask = mask & 0xffff_fffc & 0xffff;
+            if mask != 0 {
+                bars.push((
+                    index,
+                    Bar::Io {
+                        port: original & 0xffff_fffc,
+                        size: (!mask & 0xffff) + 1,
+                    },
+                ));
+            }
+            index += 1;
+            continue;
+        }
+
+        let is_64bit = (original >> 1) & 0b11 == 0b10 && index + 1 < count;
+        let mut address = u64::from(original & 0xffff_fff0);
+        let mut mask = u64::from(mask & 0xffff_fff0);
+        if is_64bit {
+            let (upper, upper_mask) = probe(index + 1);
+            address |= u64::from(upper) << 32;
+            mask |= u64::from(upper_mask) << 32;
+        }
+
+        // registers that are not implemented ignore the write
+        if mask != 0 {
+            if !is_64bit {
+                mask |= 0xffff_ffff_0000_0000;
+            }
+            bars.push((
+                index,
+                Bar::Memory {
+                    address,
+                    size: !mask + 1,
+                    prefetchable: original & 0b1000 != 0,
+                    is_64bit,
+                },
+            ));
+        }
+        index += if is_64bit { 2 } else { 1 };
+    }
+
+    pci_config_write_dword(bus, device, function, 4, command);
+    bars
+}
+
 #[derive(Debug)]
 pub struct PCIDevice {
     pub bus: u8,
     pub device: u8,
     pub function: u8,
     pub header: PCIHeader,
+    /// the implemented base address registers with their index
+    pub bars: Vec<(u8, Bar)>,
 }
 
 impl PCIDevice {
     fn from_bus_device(bus: u8, device: u8, function: u8) -> anyhow::Result<Option<Self>> {
         let header = PCIHeader::from_bus_device(bus, device, function)?;
         if let Some(header) = header {
+            let bars = read_bars(bus, device, function, header.header_type.bar_count());
             Ok(Some(Self {
                 bus,
                 device,
                 function,
                 header,
+                bars,
             }))
         } else {
             Ok(None)
         }
     }
+
+    /// Reads the configuration space into `buffer` from `offset`, as far as it goes
+    pub fn read_config(&self, offset: usize, buffer: &mut [u8]) -> usize {
+        let len = buffer.len().min(CONFIG_SPACE_SIZE.saturating_sub(offset));
+        for (i, byte) in buffer[..len].iter_mut().enumerate() {
+            let offset = (offset + i) as u8;
+            *byte = (pci_config_read_dword(self.bus, self.device, self.function, offset)
+                >> ((offset & 3) * 8)) as u8;
+        }
+        len
+    }
 }
 
 #[derive(Debug)]
@@ -67,6 +206,28 @@ pub struct PCIHeader {
 }
 
 impl PCIHeader {
+    /// The interrupt line the function is routed to, `None` if it does not use an interrupt pin
+    pub fn interrupt_line(&self) -> Option<u8> {
+        let (line, pin) = match self.header_type {
+            HeaderType::Normal {
+                interrupt_line,
+                interrupt_pin,
+                ..
+            }
+            | HeaderType::Bridge {
+                interrupt_line,
+                interrupt_pin,
+                ..
+            }
+            | HeaderType::Cardbus {
+                interrupt_line,
+                interrupt_pin,
+                ..
+            } => (interrupt_line, interrupt_pin),
+        };
+        (pin != 0).then_some(line)
+    }
+
     fn from_bus_device(bus: u8, device: u8, function: u8) -> anyhow::Result<Option<Self>> {
         let vendor_id = pci_config_read_word(bus, device, function, 0);
         if vendor_id == 0xffff {
@@ -87,7 +248,7 @@ impl PCIHeader {
 
         let cache_line_size = pci_config_read_word(bus, device, function, 12) as u8;
         let latency_timer = pci_config_read_word(bus, device, function, 13) as u8;
-        let header_type = HeaderType::from_bus_device(bus, function, device)?;
+        let header_type = HeaderType::from_bus_device(bus, device, function)?;
         let bist = pci_config_read_word(bus, device, function, 15) as u8;
 
         Ok(Some(Self {
@@ -178,8 +339,17 @@ enum HeaderType {
 }
 
 impl HeaderType {
+    /// Number of base address registers in the header
+    fn bar_count(&self) -> u8 {
+        match self {
+            Self::Normal { .. } => 6,
+            Self::Bridge { .. } => 2,
+            Self::Cardbus { .. } => 0,
+        }
+    }
+
     fn from_bus_device(bus: u8, device: u8, function: u8) -> anyhow::Result<Self> {
-        let header_type = pci_config_read_word(bus, device, 0, 14) as u8;
+        let header_type = pci_config_read_word(bus, device, function, 14) as u8;
         match header_type & 0x7f {
             0 => {
                 let bar0 = pci_config_read_word(bus, device, function, 16) as u32;
@@ -1,3 +1,5 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
 use log::{debug, trace, warn};
 use pc_keyboard::{KeyboardLayout, ScancodeSet};
 use x86_64::{
@@ -17,6 +19,20 @@ use crate::{
 
 pub static mut IDT: InterruptDescriptorTable = InterruptDescriptorTable::new();
 
+/// How often each of the 16 IRQs of the PICs was raised
+static IRQ_COUNTS: [AtomicU64; 16] = [const { AtomicU64::new(0) }; 16];
+static SYSCALL_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// Returns how often each IRQ was raised and how many syscalls were made since boot
+pub fn interrupt_counts() -> ([u64; 16], u64) {
+    (
+        IRQ_COUNTS
+            .each_ref()
+            .map(|count| count.load(Ordering::Relaxed)),
+        SYSCALL_COUNT.load(Ordering::Relaxed),
+    )
+}
+
 pub fn load_idt() {
     unsafe {
         IDT.alignment_check.set_handler_fn(alignment_check_handler);
@@ -134,6 +150,7 @@ pub unsafe extern "C" fn syscall_handler(
 
     let pid = crate::process::store_state(registers, &stack_frame)
         .expect("syscall triggered but no current process");
+    SYSCALL_COUNT.fetch_add(1, Ordering::Relaxed);
 
     handle_syscall(pid);
 
@@ -155,12 +172,18 @@ extern "C" fn irq_handler(
     trace!("irq_handler begin: irq {irq:0x?}, stack_frame {stack_frame:0x?}, registers {registers:0x?}");
 
     let pid = crate::process::store_state(registers, &stack_frame);
+    let mut preempt = false;
+
+    if let Some(count) = IRQ_COUNTS.get(usize::from(irq)) {
+        count.fetch_add(1, Ordering::Relaxed);
+    }
 
     match irq {
-        0 => unsafe {
-            driver::i8253::TIMER0.tick();
+        0 => {
+            unsafe { (*core::ptr::addr_of_mut!(driver::i8253::TIMER0)).tick() };
             trace!("timer tick");
-        },
+            preempt = crate::process::timer_tick(pid);
+        }
         1 => unsafe {
             let scancode: u8 = PortRead::read_from_port(0x60);
             trace!("scancode: {scancode}");
@@ -203,38 +226,20 @@ extern "C" fn irq_handler(
                         },
                         pc_keyboard::DecodedKey::Unicode(char) => {
                             if MODIFIERS.lctrl {
-                                if let Some(_digit) = char.to_digit(10) {
+                                if key_event.state == pc_keyboard::KeyState::Down
+                                    && char.eq_ignore_ascii_case(&'c')
+                                {
+                                    interrupt_foreground(reads_keyboard);
+                                } else if let Some(_digit) = char.to_digit(10) {
                                     // switch to tty
                                 }
                             } else if key_event.state == pc_keyboard::KeyState::Down {
                                 if char.is_ascii() {
-                                    for process in
-                                        crate::process::PROCESSES.processes_mut().iter_mut()
-                                    {
-                                        let pid = process.pid();
-                                        for (_, fd) in process.file_descriptors_mut() {
-                                            if let process::FileDescriptor::OwnedStream {
-                                                buffer,
-                                                max_size,
-                                                stream_type,
-                                            } = fd
-                                            {
-                                                if *stream_type
-                                                    == crate::process::OwnedStreamType::Keyboard
-                                                {
-                                                    if buffer.len() < *max_size {
-                                                        buffer.push_back(char as u8);
-                                                    } else {
-                                                        warn!(
-                                                            "keyboard buffer overflow in process {pid}: buffer size {} exceeds max size {}",
-                                                            buffer.len(),
-                                                            max_size
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
+                                    crate::process::feed_streams(
+                                        crate::process::OwnedStreamType::Keyboard,
+                                        &[char as u8],
+                                    );
+                                    driver::device::feed(&driver::device::TTY, &[char as u8]);
                                 } else {
                                     warn!(
                                         "Non-ASCII character received from keyboard: '{}'",
@@ -253,37 +258,28 @@ extern "C" fn irq_handler(
         },
         4 => {
             // Serial port interrupt
+            // Ctrl+C arrives as ETX and is not part of the input
+            const ETX: u8 = 0x03;
+
             let mut buffer: [u8; 64] = [0; 64];
             let count = crate::driver::serial::com1()
                 .expect("Failed to get serial port")
                 .read_bytes(&mut buffer);
-            let bytes = &buffer[..count];
-
-            if count > 0 {
-                for process in crate::process::PROCESSES.processes_mut().iter_mut() {
-                    let pid = process.pid();
-                    for (_, fd) in process.file_descriptors_mut() {
-                        if let process::FileDescriptor::OwnedStream {
-                            buffer,
-                            max_size,
-                            stream_type,
-                        } = fd
-                        {
-                            if *stream_type == crate::process::OwnedStreamType::Serial {
-                                if buffer.len() + count <= *max_size {
-                                    buffer.extend(bytes);
-                                } else {
-                                    warn!(
-                                        "serial buffer overflow in process {pid}: buffer size {} exceeds max size {}",
-                                        buffer.len(),
-                                        max_size
-                                    );
-                                }
-                            }
-                        }
-                    }
+
+            let mut input: [u8; 64] = [0; 64];
+            let mut input_len = 0;
+            for &byte in &buffer[..count] {
+                if byte == ETX {
+                    interrupt_foreground(|fd| reads_device(fd, &driver::device::TTY_S0));
+                } else {
+                    input[input_len] = byte;
+                    input_len += 1;
                 }
             }
+
+            if input_len > 0 {
+                driver::device::feed(&driver::device::TTY_S0, &input[..input_len]);
+            }
         }
         12 => {
             let status = unsafe { <u8 as PortRead>::read_from_port(0x64) };
@@ -312,36 +308,13 @@ extern "C" fn irq_handler(
                     right_button_pressed: u8::from(right_button),
                 };
 
-                for process in crate::process::PROCESSES.processes_mut().iter_mut() {
-                    let pid = process.pid();
-
-                    for (_, fd) in process.file_descriptors_mut() {
-                        if let process::FileDescriptor::OwnedStream {
-                            buffer,
-                            max_size,
-                            stream_type,
-                        } = fd
-                        {
-                            if *stream_type == crate::process::OwnedStreamType::Mouse {
-                                if buffer.len() < *max_size {
-                                    let bytes = unsafe {
-                                        core::mem::transmute::<
-                                            crate::types::mouse_event_t,
-                                            [u8; size_of::<crate::types::mouse_event_t>()],
-                                        >(event)
-                                    };
-                                    buffer.extend(bytes);
-                                } else {
-                                    warn!(
-                                        "mouse buffer overflow in process {pid}: buffer size {} exceeds max size {}",
-                                        buffer.len(),
-                                        max_size
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+                let bytes = unsafe {
+                    core::mem::transmute::<
+                        crate::types::mouse_event_t,
+                        [u8; size_of::<crate::types::mouse_event_t>()],
+                    >(event)
+                };
+                crate::process::feed_streams(crate::process::OwnedStreamType::Mouse, &bytes);
             }
         }
         _ => {
@@ -354,7 +327,9 @@ extern "C" fn irq_handler(
     trace!("irq_handler end");
 
     match pid {
-        Some(pid) => crate::process::iret(pid),
+        // the interrupted thread may have been terminated or stopped by a signal
+        Some(tid) if !preempt && crate::syscall::deliver_signals(tid) => crate::process::iret(tid),
+        Some(_) => crate::process::schedule(),
         None => {
             unsafe {
                 crate::process::do_iret(
@@ -372,6 +347,36 @@ extern "C" fn irq_handler(
     }
 }
 
+/// Whether `fd` is a device that reads the same input as `device`
+fn reads_device(fd: &crate::process::FileDescriptor, device: &dyn driver::device::Device) -> bool {
+    match fd {
+        crate::process::FileDescriptor::Device { device: other, .. } => other
+            .input()
+            .zip(device.input())
+            .is_some_and(|(a, b)| core::ptr::eq(a, b)),
+        _ => false,
+    }
+}
+
+/// Whether `fd` gets what is typed on the keyboard, through the tty or as the raw keyboard stream
+fn reads_keyboard(fd: &crate::process::FileDescriptor) -> bool {
+    reads_device(fd, &driver::device::TTY)
+        || matches!(
+            fd,
+            crate::process::FileDescriptor::OwnedStream {
+                stream_type: crate::process::OwnedStreamType::Keyboard,
+                ..
+            }
+        )
+}
+
+/// Sends `SIGINT` to the process that reads from the stream Ctrl+C was typed into
+fn interrupt_foreground(reads_input: impl Fn(&crate::process::FileDescriptor) -> bool) {
+    if let Some(pid) = crate::process::foreground_process(reads_input) {
+        crate::syscall::send_signal(pid, crate::syscall::generated::SIGINT);
+    }
+}
+
 extern "x86-interrupt" fn alignment_check_handler(stack_frame: InterruptStackFrame, err: u64) {
     panic!(
         "EXCEPTION: ALIGNMENT CHECK {:#?}\n Error code: {}",
@@ -412,18 +417,17 @@ extern "x86-interrupt" fn general_protection_fault_handler(
 ) {
     if (stack_frame.code_segment.rpl() == x86_64::PrivilegeLevel::Ring3) && (err & 0x1 == 0) {
         // User mode general protection fault
-        let mut process = PROCESSES.current_mut().expect("No current process");
-
-        process.state = crate::process::State::Terminated(1);
+        let pid = PROCESSES
+            .current()
+            .expect("No current thread")
+            .pid()
+            .expect("kernel thread in user mode");
 
         log::warn!(
-            "user mode general protection fault in process {}: {:#x?}, error code: {}",
-            process.pid(),
-            stack_frame,
-            err
+            "user mode general protection fault in process {pid}: {stack_frame:#x?}, error code: {err}"
         );
 
-        drop(process);
+        crate::syscall::exit_process(pid, 1);
 
         process::schedule();
     } else {
@@ -463,12 +467,33 @@ extern "x86-interrupt" fn page_fault_handler(
 ) {
     let address = x86_64::registers::control::Cr2::read().expect("Failed to read CR2 register");
 
-    if err.contains(PageFaultErrorCode::USER_MODE) {
-        let mut process = PROCESSES.current_mut().expect("No current process");
+    // writes to shared pages after a fork, both from userspace and from syscalls
+    if err.contains(PageFaultErrorCode::PROTECTION_VIOLATION | PageFaultErrorCode::CAUSED_BY_WRITE)
+        && address.as_u64() < crate::kernel::paging::UPPER_HALF_START
+        && crate::kernel::paging::handle_copy_on_write_fault(address)
+    {
+        return;
+    }
 
-        process.state = crate::process::State::Terminated(1);
+    // first touch of a page in one of the regions of the active address space
+    if address.as_u64() < crate::kernel::paging::UPPER_HALF_START
+        && crate::process::memory::handle_page_fault(address, err)
+    {
+        return;
+    }
+
+    if err.contains(PageFaultErrorCode::USER_MODE) {
+        let pid = PROCESSES
+            .current()
+            .expect("No current thread")
+            .pid()
+            .expect("kernel thread in user mode");
+        let process = PROCESSES.process(pid);
 
         let mapping = process
+            .memory
+            .try_lock()
+            .expect("Failed to lock address space")
             .paging
             .page_table
             .translate(VirtAddr::new(address.as_u64()));
@@ -485,6 +510,8 @@ extern "x86-interrupt" fn page_fault_handler(
 
         drop(process);
 
+        crate::syscall::force_signal(pid, crate::syscall::generated::SIGSEGV);
+
         process::schedule();
     } else {
         unsafe {
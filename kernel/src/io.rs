@@ -2,6 +2,12 @@ pub trait Write {
     fn write(&mut self, bytes: &[u8]) -> Result<usize, WriterError>;
 }
 
+impl<T: Write + ?Sized> Write for &mut T {
+    fn write(&mut self, bytes: &[u8]) -> Result<usize, WriterError> {
+        (**self).write(bytes)
+    }
+}
+
 /// Ignorer is a writer that ignores the first `count` bytes written to it,
 /// then writes the rest to the underlying writer.
 pub struct Ignorer<T: Write> {
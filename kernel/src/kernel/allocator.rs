@@ -6,5 +6,12 @@ pub fn init_kernel_heap(heap_start: *mut u8, length: usize) {
     }
 }
 
+/// Returns the size of the kernel heap and how much of it is allocated, in bytes
+pub fn heap_usage() -> (usize, usize) {
+    // the lock is released before the caller allocates again
+    let heap = unsafe { (*core::ptr::addr_of!(ALLOCATOR)).lock() };
+    (heap.size(), heap.used())
+}
+
 #[global_allocator]
 pub static mut ALLOCATOR: LockedHeap = LockedHeap::empty();
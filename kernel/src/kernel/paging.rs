@@ -1,4 +1,4 @@
-use alloc::borrow::ToOwned as _;
+use alloc::{borrow::ToOwned as _, collections::BTreeMap};
 use x86_64::{
     registers::control::{Cr3, Cr3Flags},
     structures::paging::{
@@ -9,10 +9,7 @@ use x86_64::{
     PhysAddr, VirtAddr,
 };
 
-use crate::{
-    process::MappedPage,
-    stuff::memmap::{MemmapEntryType, SoosMemmap},
-};
+use crate::stuff::memmap::{MemmapEntryType, SoosMemmap};
 
 pub fn current_page_table() -> *mut PageTable {
     let (level_4_table_frame, _flags) = Cr3::read();
@@ -26,6 +23,10 @@ pub const KERNEL_FRAME_MAPPING_ADDRESS: u64 = 0xFFFF_8000_0000_0000;
 /// The split between kernel and userspace memory.
 pub const UPPER_HALF_START: u64 = 0xFFFF_7FFF_FFFF_FFFF;
 
+/// Marks a page that is shared read-only between address spaces after a fork.
+/// The first write to such a page gives the writer its own copy of the frame.
+pub const COPY_ON_WRITE: PageTableFlags = PageTableFlags::BIT_9;
+
 pub struct KernelPaging {
     page_table: OffsetPageTable<'static>,
     frame_allocator: KernelFrameAllocator,
@@ -63,6 +64,8 @@ impl x86_64::structures::paging::FrameDeallocator<Size4KiB> for UseKernelFrameAl
 
 pub struct KernelFrameAllocator {
     frame_map: &'static mut [(Option<u64>, [bool; 1 << 20]); 8],
+    /// reference counts of frames mapped more than once, keyed by start address
+    shared: BTreeMap<u64, usize>,
     pub memmap: SoosMemmap,
     skip: usize,
     allocated: usize,
@@ -97,12 +100,37 @@ impl KernelFrameAllocator {
 
         Self {
             frame_map,
+            shared: BTreeMap::new(),
             memmap: *memmap,
             skip: 0,
             allocated: 0,
         }
     }
 
+    /// Adds a reference to an already allocated frame.
+    /// The frame is only freed once every reference has been deallocated.
+    pub fn share_frame(&mut self, frame: PhysFrame<Size4KiB>) {
+        debug_assert!(self.is_used(frame), "sharing unused frame {frame:?}");
+
+        *self
+            .shared
+            .entry(frame.start_address().as_u64())
+            .or_insert(1) += 1;
+    }
+
+    /// Returns the number of references to the frame, 0 if it is free
+    pub fn frame_references(&self, frame: PhysFrame<Size4KiB>) -> usize {
+        match self.shared.get(&frame.start_address().as_u64()) {
+            Some(&count) => count,
+            None => usize::from(self.is_used(frame)),
+        }
+    }
+
+    /// Returns the number of frames that are referenced more than once
+    pub fn shared_frames(&self) -> usize {
+        self.shared.len()
+    }
+
     pub fn stats(&self) -> (usize, usize, usize) {
         let used = self
             .frame_map
@@ -287,6 +315,14 @@ unsafe impl FrameAllocator<Size4KiB> for KernelFrameAllocator {
 
 impl FrameDeallocator<Size4KiB> for KernelFrameAllocator {
     unsafe fn deallocate_frame(&mut self, frame: PhysFrame<Size4KiB>) {
+        if let Some(count) = self.shared.get_mut(&frame.start_address().as_u64()) {
+            *count -= 1;
+            if *count == 1 {
+                self.shared.remove(&frame.start_address().as_u64());
+            }
+            return;
+        }
+
         self.mark_frame(frame, false);
 
         self.allocated -= 1;
@@ -729,6 +765,15 @@ impl Drop for UserspacePaging<'_> {
 }
 
 impl UserspacePaging<'_> {
+    /// Returns whether this page table is currently loaded in CR3
+    pub fn is_active(&self) -> bool {
+        let (level_4_table_frame, _flags) = Cr3::read();
+
+        level_4_table_frame.start_address().as_u64()
+            == core::ptr::from_ref::<PageTable>(self.page_table.level_4_table()) as u64
+                - self.page_table.phys_offset().as_u64()
+    }
+
     pub fn load(&self) {
         let addr = core::ptr::from_ref(self.page_table.level_4_table()) as u64;
         let physical_address = self
@@ -749,10 +794,13 @@ impl UserspacePaging<'_> {
         }
     }
 
+    /// Creates a copy of this address space that shares all `pages` with it.
+    /// Writable pages that are not shared on purpose are mapped read-only in both page tables
+    /// and marked with [`COPY_ON_WRITE`], see [`handle_copy_on_write_fault`].
     pub fn fork(
-        &self,
+        &mut self,
         kernel_paging: &mut KernelPaging,
-        pages: &[MappedPage],
+        pages: &[(Page, PageTableFlags, bool)],
     ) -> UserspacePaging<'static> {
         kernel_paging.load();
 
@@ -776,84 +824,152 @@ impl UserspacePaging<'_> {
             .level_4_table()
             .clone_into(new_page_table.level_4_table_mut());
 
-        for &MappedPage { page, flags, .. } in pages {
-            let new_frame = kernel_paging
-                .frame_allocator
-                .allocate_frame()
-                .expect("Failed to allocate frame for cloned page table");
+        for &(page, flags, shared) in pages {
+            let frame = self
+                .page_table
+                .translate_page(page)
+                .expect("Failed to translate page");
+
+            let shared_flags = if flags.contains(PageTableFlags::WRITABLE) && !shared {
+                (flags - PageTableFlags::WRITABLE) | COPY_ON_WRITE
+            } else {
+                flags
+            };
 
             log::trace!(
-                "cloning page {:#x} to new frame {:#x}",
+                "sharing page {:#x} (frame {:#x}) with flags {shared_flags:?}",
                 page.start_address().as_u64(),
-                new_frame.start_address().as_u64()
+                frame.start_address().as_u64()
             );
 
             unsafe {
-                new_page_table
-                    .map_to(page, new_frame, flags, &mut kernel_paging.frame_allocator)
-                    .expect("Failed to map frame in cloned page table")
+                self.page_table
+                    .update_flags(page, shared_flags)
+                    .expect("Failed to write-protect page")
                     .flush();
-            };
+            }
 
-            // find free address to map the old and new frames
-            let temp_addr_src = VirtAddr::new_truncate(0xFFFF_9000_0000_0000);
-            let temp_addr_dst = temp_addr_src + 0x1000;
+            kernel_paging.frame_allocator.share_frame(frame);
 
-            let old_frame = self
-                .page_table
-                .translate_page(page)
-                .expect("Failed to translate page")
-                .start_address();
-
-            // map old and new frames to temporary addresses for copying
+            // the parent tables have to stay writable, otherwise breaking
+            // the copy-on-write mapping later would not make the page writable
             unsafe {
-                kernel_paging
-                    .page_table
-                    .map_to(
-                        Page::<Size4KiB>::containing_address(temp_addr_src),
-                        PhysFrame::containing_address(old_frame),
-                        PageTableFlags::PRESENT,
-                        &mut kernel_paging.frame_allocator,
-                    )
-                    .expect("Failed to map frame in cloned page table")
-                    .flush();
-
-                kernel_paging
-                    .page_table
-                    .map_to(
-                        Page::containing_address(temp_addr_dst),
-                        new_frame,
-                        PageTableFlags::PRESENT | PageTableFlags::WRITABLE,
+                new_page_table
+                    .map_to_with_table_flags(
+                        page,
+                        frame,
+                        shared_flags,
+                        PageTableFlags::PRESENT
+                            | PageTableFlags::WRITABLE
+                            | PageTableFlags::USER_ACCESSIBLE,
                         &mut kernel_paging.frame_allocator,
                     )
                     .expect("Failed to map frame in cloned page table")
                     .flush();
             };
+        }
 
-            unsafe {
-                for i in 0..0x1000 {
-                    let byte = temp_addr_src.as_mut_ptr::<u8>().add(i).read_volatile();
-                    temp_addr_dst.as_mut_ptr::<u8>().add(i).write_volatile(byte);
-                }
-            }
+        UserspacePaging {
+            page_table: new_page_table,
+        }
+    }
+}
 
-            // unmap the temporary pages
-            // dont deallocate the frames, the old one belongs to the parent process, the new one to the child
-            let (_old_frame, flush) = kernel_paging
-                .page_table
-                .unmap(Page::<Size4KiB>::containing_address(temp_addr_src))
-                .expect("Failed to unmap old frame in cloned page table");
-            flush.flush();
+/// Resolves a write fault on a [`COPY_ON_WRITE`] page in the active page table,
+/// see [`break_copy_on_write`].
+/// Returns `false` if the faulting page is not a copy-on-write page.
+pub fn handle_copy_on_write_fault(address: VirtAddr) -> bool {
+    let (level_4_table_frame, _flags) = Cr3::read();
+    let phys_offset = VirtAddr::new(KERNEL_FRAME_MAPPING_ADDRESS);
+    let mut page_table = unsafe {
+        OffsetPageTable::new(
+            &mut *(phys_offset + level_4_table_frame.start_address().as_u64()).as_mut_ptr(),
+            phys_offset,
+        )
+    };
 
-            let (_frame, flush) = kernel_paging
-                .page_table
-                .unmap(Page::<Size4KiB>::containing_address(temp_addr_dst))
-                .expect("Failed to unmap new frame in cloned page table");
-            flush.flush();
+    let mut kernel_paging = crate::kernel_paging();
+
+    break_copy_on_write(
+        &mut page_table,
+        &mut kernel_paging,
+        Page::containing_address(address),
+    )
+}
+
+/// Makes a [`COPY_ON_WRITE`] page of `page_table` writable.
+/// If the frame is still shared, the page gets a private copy of it,
+/// otherwise the page is simply made writable again.
+/// Returns `false` if the page is not a copy-on-write page.
+pub fn break_copy_on_write(
+    page_table: &mut OffsetPageTable,
+    kernel_paging: &mut KernelPaging,
+    page: Page,
+) -> bool {
+    let phys_offset = page_table.phys_offset();
+    let (frame, flags) = match page_table.translate(page.start_address()) {
+        TranslateResult::Mapped {
+            frame: MappedFrame::Size4KiB(frame),
+            flags,
+            ..
+        } if flags.contains(COPY_ON_WRITE) => (frame, flags),
+        _ => return false,
+    };
+
+    let writable_flags = (flags - COPY_ON_WRITE) | PageTableFlags::WRITABLE;
+
+    if kernel_paging.frame_allocator.frame_references(frame) > 1 {
+        let new_frame = kernel_paging
+            .frame_allocator
+            .allocate_frame()
+            .expect("Failed to allocate frame for copy-on-write page");
+
+        log::trace!(
+            "copy-on-write: copying page {:#x} from frame {:#x} to {:#x}",
+            page.start_address().as_u64(),
+            frame.start_address().as_u64(),
+            new_frame.start_address().as_u64()
+        );
+
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                (phys_offset + frame.start_address().as_u64()).as_ptr::<u8>(),
+                (phys_offset + new_frame.start_address().as_u64()).as_mut_ptr::<u8>(),
+                Size4KiB::SIZE as usize,
+            );
         }
 
-        UserspacePaging {
-            page_table: new_page_table,
+        let (old_frame, flush) = page_table
+            .unmap(page)
+            .expect("Failed to unmap copy-on-write page");
+        flush.flush();
+
+        unsafe {
+            page_table
+                .map_to(
+                    page,
+                    new_frame,
+                    writable_flags,
+                    &mut kernel_paging.frame_allocator,
+                )
+                .expect("Failed to map copied page")
+                .flush();
+
+            kernel_paging.frame_allocator.deallocate_frame(old_frame);
+        }
+    } else {
+        log::trace!(
+            "copy-on-write: page {:#x} is no longer shared, making it writable",
+            page.start_address().as_u64()
+        );
+
+        unsafe {
+            page_table
+                .update_flags(page, writable_flags)
+                .expect("Failed to update flags of copy-on-write page")
+                .flush();
         }
     }
+
+    true
 }
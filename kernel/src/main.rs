@@ -28,7 +28,9 @@ mod vfs;
 
 use core::arch::asm;
 
-use limine::request::{HhdmRequest, MemoryMapRequest, PagingModeRequest};
+use limine::request::{
+    ExecutableCmdlineRequest, HhdmRequest, MemoryMapRequest, ModuleRequest, PagingModeRequest,
+};
 use log::{debug, LevelFilter};
 
 use x86_64::{
@@ -58,8 +60,15 @@ static PAGING_MODE_REQUEST: PagingModeRequest =
 
 static HHDM_REQUEST: HhdmRequest = HhdmRequest::new();
 
-static FILE_SYSTEM: spin::Lazy<spin::Mutex<vfs::Directory>> =
-    spin::Lazy::new(|| spin::Mutex::new(vfs::Directory::new(&["home", "bin"])));
+static EXECUTABLE_CMDLINE_REQUEST: ExecutableCmdlineRequest = ExecutableCmdlineRequest::new();
+
+/// The initramfs with the programs, the first module in limine.conf
+static MODULE_REQUEST: ModuleRequest = ModuleRequest::new();
+
+/// The program started as the first process, `init=<path>` on the kernel command line replaces it
+const DEFAULT_INIT_PATH: &str = "/sbin/init";
+
+static FILE_SYSTEM: vfs::Vfs = vfs::Vfs::new();
 
 static KERNEL_MEMORY_START_ADDR: spin::Lazy<u64> = spin::Lazy::new(|| {
     extern "C" {
@@ -102,9 +111,11 @@ unsafe extern "C" fn _start() -> ! {
 #[no_mangle]
 unsafe extern "C" fn main() -> ! {
     // enable SSE, AVX, and x87 instructions
+    // write protect makes kernel writes to copy-on-write pages fault as well
     x86_64::registers::control::Cr0::update(|f| {
         f.remove(Cr0Flags::EMULATE_COPROCESSOR);
         f.insert(Cr0Flags::MONITOR_COPROCESSOR);
+        f.insert(Cr0Flags::WRITE_PROTECT);
     });
     x86_64::registers::control::Cr4::update(|f| {
         f.insert(x86_64::registers::control::Cr4Flags::OSFXSR);
@@ -193,7 +204,7 @@ unsafe extern "C" fn main() -> ! {
     }
 
     i8253::TIMER0.init(
-        10,
+        100,
         i8253::Channel::CH0,
         i8253::AccessMode::LoHiByte,
         i8253::OperatingMode::RateGenerator,
@@ -282,33 +293,69 @@ unsafe extern "C" fn main() -> ! {
         *KERNEL_MEMORY_END_ADDR
     );
 
-    {
-        vfs::root::init_fs(&mut FILE_SYSTEM.lock());
-    }
+    driver::pci::init();
 
-    let mut process1 = process::Process::user_from_elf(
-        ucs,
-        uds,
-        0x202,
-        include_bytes_aligned::include_bytes_aligned!(32, "../../build/userspace/bin/sosh"),
-    );
-    process1.redirect_stdout_to_term();
-    process1.redirect_keyboard_to_stdin();
-    process::PROCESSES.add_process(process1);
-
-    if driver::serial::com1().is_ok() {
-        let mut process2 = process::Process::user_from_elf(
-            ucs,
-            uds,
-            0x202,
-            include_bytes_aligned::include_bytes_aligned!(32, "../../build/userspace/bin/sosh"),
+    vfs::root::init_fs(&FILE_SYSTEM, initramfs(offset));
+
+    {
+        let init_path = init_path();
+        log::info!("starting init process '{init_path}'");
+
+        let contents = FILE_SYSTEM
+            .lookup(init_path)
+            .and_then(|node| vfs::read_all(&*node))
+            .unwrap_or_else(|e| {
+                panic!("init program '{init_path}' cannot be loaded ({e}), set another one with init=<path> on the kernel command line")
+            });
+
+        let (process, thread) =
+            process::Process::user_from_elf(ucs, uds, 0x202, &contents, &[init_path])
+                .unwrap_or_else(|e| {
+                    panic!("init program '{init_path}' cannot be started ({e}), set another one with init=<path> on the kernel command line")
+                });
+        assert_eq!(
+            process.pid(),
+            process::INIT_PID,
+            "init process must be the first process"
         );
-        process2.redirect_stdout_to_serial();
-        process2.redirect_serial_to_stdin();
-        process::PROCESSES.add_process(process2);
+        process::PROCESSES.add_process(process, thread);
     }
 
     log::info!("kernel initialization complete, starting scheduler");
 
     process::schedule();
 }
+
+/// The contents of the initramfs module, `hhdm_offset` is where the bootloader mapped the physical memory.
+/// The kernel page table maps the module at [`kernel::paging::KERNEL_FRAME_MAPPING_ADDRESS`] instead.
+/// Its memory is never freed, the unpacked files are copies.
+fn initramfs(hhdm_offset: u64) -> Option<&'static [u8]> {
+    let module = MODULE_REQUEST.get_response()?.modules().first()?;
+    log::debug!(
+        "initramfs module {:?} at {:#x}, {} bytes",
+        module.path(),
+        module.addr() as u64,
+        module.size()
+    );
+
+    let physical_address = module.addr() as u64 - hhdm_offset;
+    Some(unsafe {
+        core::slice::from_raw_parts(
+            (kernel::paging::KERNEL_FRAME_MAPPING_ADDRESS + physical_address) as *const u8,
+            module.size() as usize,
+        )
+    })
+}
+
+/// The path of the init program from the kernel command line
+fn init_path() -> &'static str {
+    EXECUTABLE_CMDLINE_REQUEST
+        .get_response()
+        .and_then(|response| response.cmdline().to_str().ok())
+        .and_then(|cmdline| {
+            cmdline
+                .split_whitespace()
+                .find_map(|arg| arg.strip_prefix("init="))
+        })
+        .unwrap_or(DEFAULT_INIT_PATH)
+}
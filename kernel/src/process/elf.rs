@@ -1,37 +1,54 @@
+use alloc::{sync::Arc, vec::Vec};
 use elf_rs::{Elf, ElfFile, ProgramHeaderFlags, ProgramType};
 use log::info;
 use x86_64::{
-    structures::paging::{FrameAllocator, Mapper, Page, PageSize, PageTableFlags, Size4KiB},
+    structures::paging::{PageSize, PageTableFlags, Size4KiB},
     VirtAddr,
 };
 
 use crate::{
-    kernel::paging::{KernelPaging, UserspacePaging},
-    process::MappedPage,
+    kernel::paging::KernelPaging,
+    process::memory::{AddressSpace, Backing, Region},
 };
 
+const STACK_ADDRESS: u64 = 0x0000_1000_0000_0000;
+const STACK_SIZE: u64 = 8 * 1024 * 1024;
+
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("not an ELF file ({0:?})")]
+    Malformed(elf_rs::Error),
+    #[error("not an x86_64 executable")]
+    NotExecutable,
+    #[error("the entry point {0:#x} is outside of user space")]
+    InvalidEntryPoint(u64),
+    #[error(
+        "the segment at {0:#x} is outside of the file or user space, or overlaps another segment"
+    )]
+    InvalidSegment(u64),
+}
+
+/// Sets up regions for all loadable segments and the stack of the ELF in `bytes`.
+/// Nothing is copied until the pages are touched, except for the arguments, environment and auxiliary vector on the stack.
+/// Returns the entry point and the initial stack pointer.
+/// The ELF is checked before anything is added to `memory`.
 pub fn load<T: AsRef<str>>(
-    process_paging: &mut UserspacePaging,
+    memory: &mut AddressSpace,
     kernel_paging: &mut KernelPaging,
     bytes: &[u8],
     args: &[T],
-) -> (VirtAddr, VirtAddr, alloc::vec::Vec<MappedPage>) {
-    let elf = Elf::from_bytes(bytes).expect("Failed to parse ELF!");
-    match elf.elf_header().elftype() {
-        elf_rs::ElfType::ET_EXEC => {}
-        e => panic!("Unsupported ELF type: {:?}", e),
-    }
+    env: &[T],
+) -> Result<(VirtAddr, VirtAddr), Error> {
+    let elf = Elf::from_bytes(bytes).map_err(Error::Malformed)?;
+    check(&elf)?;
 
-    let mut pages = alloc::vec::Vec::with_capacity(10);
+    let data: Arc<[u8]> = Arc::from(bytes);
+    let mut program_end = VirtAddr::zero();
 
-    // map pages
     for ph in elf
         .program_header_iter()
         .filter(|ph| ph.ph_type() == ProgramType::LOAD)
     {
-        let start_page = Page::containing_address(VirtAddr::new(ph.vaddr()));
-        let end_page = Page::containing_address(VirtAddr::new(ph.vaddr() + ph.memsz()));
-
         let mut flags = PageTableFlags::PRESENT | PageTableFlags::USER_ACCESSIBLE;
         if !ph.flags().contains(ProgramHeaderFlags::EXECUTE) {
             flags |= PageTableFlags::NO_EXECUTE;
@@ -40,203 +57,234 @@ pub fn load<T: AsRef<str>>(
             flags |= PageTableFlags::WRITABLE;
         }
 
-        info!(
-            "mapping code pages [{:#0x} - {:#0x}] with flags {:?}",
-            start_page.start_address(),
-            end_page.start_address(),
-            flags
-        );
-
-        // map page for copying program
-        for page in Page::range_inclusive(start_page, end_page) {
-            pages.push(MappedPage {
-                name: "elf",
-                page,
-                flags,
-            });
-
-            let frame = kernel_paging
-                .allocate_frame()
-                .expect("Failed to allocate frame!");
-
-            unsafe {
-                process_paging
-                    .page_table
-                    .map_to(
-                        page,
-                        frame,
-                        PageTableFlags::PRESENT | PageTableFlags::WRITABLE,
-                        &mut *kernel_paging,
-                    )
-                    .unwrap_or_else(|e| panic!("Failed to map page {:#?}: {:?}", page, e))
-                    .flush();
-            }
-        }
-
-        unsafe {
-            process_paging.load();
+        let vaddr = VirtAddr::new(ph.vaddr());
+        let start = vaddr.align_down(Size4KiB::SIZE);
+        let file_end = (vaddr + ph.filesz()).align_up(Size4KiB::SIZE);
+        let end = (vaddr + ph.memsz()).align_up(Size4KiB::SIZE);
 
-            // copy data
-            core::ptr::copy::<u8>(
-                bytes.as_ptr().add(ph.offset() as usize),
-                ph.vaddr() as *mut u8,
-                ph.filesz() as usize,
-            );
+        info!("mapping segment [{start:#0x} - {end:#0x}] with flags {flags:?}");
 
-            // zero out the rest of the page
-            let remaining_size = ph.memsz() as usize - ph.filesz() as usize;
-            core::ptr::write_bytes((ph.vaddr() + ph.filesz()) as *mut u8, 0, remaining_size);
+        program_end = program_end.max(end);
 
-            kernel_paging.load();
+        if start < file_end {
+            memory.add_region(Region {
+                start,
+                end: file_end,
+                flags,
+                name: "elf",
+                backing: Backing::File {
+                    data: Arc::clone(&data),
+                    vaddr,
+                    offset: ph.offset() as usize,
+                    size: ph.filesz() as usize,
+                },
+                shared: false,
+            });
         }
 
-        // remap the page to the correct flags
-        for page in Page::range_inclusive(start_page, end_page) {
-            unsafe {
-                let (frame, flush) = process_paging
-                    .page_table
-                    .unmap(page)
-                    .expect("Failed to unmap page!");
-                flush.flush();
-
-                process_paging
-                    .page_table
-                    .map_to(page, frame, flags, &mut *kernel_paging)
-                    .expect("Failed to remap page!")
-                    .flush();
-            }
+        // the part of the segment that is not backed by the file
+        if file_end < end {
+            memory.add_region(Region {
+                start: file_end,
+                end,
+                flags,
+                name: "bss",
+                backing: Backing::Anonymous,
+                shared: false,
+            });
         }
     }
 
-    // create stack
-    let stack_size_pages = 100;
-    let stack_address = VirtAddr::new(0x0000_1000_0000_0000);
-    let flags = PageTableFlags::PRESENT
-        | PageTableFlags::WRITABLE
-        | PageTableFlags::USER_ACCESSIBLE
-        | PageTableFlags::NO_EXECUTE;
-    info!(
-        "mapping stack pages [{:#0x} - {:#0x}] with flags {:?}",
-        stack_address,
-        stack_address + stack_size_pages * Size4KiB::SIZE,
-        flags
-    );
-    for i in 0..stack_size_pages {
-        let page = Page::containing_address(stack_address + i * Size4KiB::SIZE);
-        let frame = kernel_paging
-            .allocate_frame()
-            .expect("Failed to allocate frame!");
-
-        unsafe {
-            process_paging
-                .page_table
-                .map_to(page, frame, flags, &mut *kernel_paging)
-                .expect("Failed to map page!")
-                .flush();
-        }
+    memory.init_heap(program_end);
 
-        pages.push(MappedPage {
-            name: "stack",
-            page,
-            flags,
-        });
+    // create stack
+    let stack_address = VirtAddr::new(STACK_ADDRESS);
+    let stack_top = stack_address + STACK_SIZE;
+    memory.add_region(Region {
+        start: stack_address,
+        end: stack_top,
+        flags: PageTableFlags::PRESENT
+            | PageTableFlags::WRITABLE
+            | PageTableFlags::USER_ACCESSIBLE
+            | PageTableFlags::NO_EXECUTE,
+        name: "stack",
+        backing: Backing::Anonymous,
+        shared: false,
+    });
+
+    // prepare arguments on the stack, the structs are aligned to 16 bytes so they never cross a page
+
+    // random bytes for the program, e.g. to seed stack protectors
+    let random_ptr = stack_top - 16u64;
+    memory.write_bytes(kernel_paging, random_ptr, &random_bytes());
+
+    let argv_ptr = write_strings(memory, kernel_paging, random_ptr, args);
+    let envp_ptr = write_strings(memory, kernel_paging, argv_ptr, env);
+
+    let term = &crate::term::TERM;
+    let auxv = [
+        (crate::types::AT_PHDR, program_headers_address(&elf)),
+        (
+            crate::types::AT_PHENT,
+            u64::from(elf.elf_header().program_header_entry_size()),
+        ),
+        (
+            crate::types::AT_PHNUM,
+            u64::from(elf.elf_header().program_header_entry_num()),
+        ),
+        (crate::types::AT_PAGESZ, Size4KiB::SIZE),
+        (crate::types::AT_ENTRY, elf.entry_point()),
+        (crate::types::AT_RANDOM, random_ptr.as_u64()),
+        (crate::types::AT_FRAMEBUFFER_WIDTH, term.width_pixels as u64),
+        (
+            crate::types::AT_FRAMEBUFFER_HEIGHT,
+            term.height_pixels as u64,
+        ),
+        (crate::types::AT_NULL, 0),
+    ];
+
+    let auxv_ptr =
+        (envp_ptr - (auxv.len() * size_of::<crate::types::auxv_t>()) as u64).align_down(16u64);
+    log::debug!("auxiliary vector starts at {auxv_ptr:#x}");
+    for (i, (key, value)) in auxv.into_iter().enumerate() {
+        memory.write_value(
+            kernel_paging,
+            auxv_ptr + (i * size_of::<crate::types::auxv_t>()) as u64,
+            crate::types::auxv_t { key, value },
+        );
     }
 
-    let stack_top = stack_address + stack_size_pages * Size4KiB::SIZE;
-
-    // prepare arguments on the stack
+    // create entry struct, it is larger than 16 bytes so it is aligned to its size rounded up
+    let entry_struct_ptr = (auxv_ptr - size_of::<crate::types::entry_t>() as u64)
+        .align_down(size_of::<crate::types::entry_t>().next_power_of_two() as u64);
+    log::debug!("entry struct starts at {entry_struct_ptr:#x}");
+    memory.write_value(
+        kernel_paging,
+        entry_struct_ptr,
+        crate::types::entry_t {
+            argc: args.len() as u32,
+            argv: argv_ptr.as_mut_ptr::<crate::types::string_const_t>(),
+            envc: env.len() as u32,
+            envp: envp_ptr.as_mut_ptr::<crate::types::string_const_t>(),
+            auxv: auxv_ptr.as_mut_ptr::<crate::types::auxv_t>(),
+        },
+    );
 
-    let stack_top_frame = process_paging
-        .page_table
-        .translate_page(Page::<Size4KiB>::containing_address(stack_top - 1))
-        .expect("Failed to translate stack top page");
+    // create pointer to the entry struct
+    let entry_ptr = entry_struct_ptr - size_of::<u64>() as u64;
+    log::debug!("entry pointer is at {entry_ptr:#x}");
+    memory.write_value(kernel_paging, entry_ptr, entry_struct_ptr.as_u64());
 
-    log::debug!("preparing arguments on the stack at {stack_top:#x} (frame: {stack_top_frame:x?})");
+    Ok((VirtAddr::new(elf.entry_point()), entry_ptr))
+}
 
+/// Makes sure the ELF is an `x86_64` executable whose segments are inside the file and below the stack,
+/// and do not share pages with each other
+fn check(elf: &Elf) -> Result<(), Error> {
+    let Elf::Elf64(_) = elf else {
+        return Err(Error::NotExecutable);
+    };
+    if elf.elf_header().elftype() != elf_rs::ElfType::ET_EXEC
+        || elf.elf_header().machine() != elf_rs::ElfMachine::x86_64
     {
-        let l4_index = Page::<Size4KiB>::containing_address(stack_top - 1)
-            .start_address()
-            .p4_index();
-        let kernel_l4_ptr = &kernel_paging.page_table().level_4_table()[l4_index];
-        let process_l4_ptr = &process_paging.page_table.level_4_table()[l4_index];
-        log::log!(
-            log::Level::Debug,
-            "l4_index: {l4_index:?}, kernel L4 entry: {:#x} ({:x?}), process L4 entry: {:#x} ({:x?})",
-            kernel_l4_ptr as *const _ as u64,
-            kernel_l4_ptr,
-            process_l4_ptr as *const _ as u64,
-            process_l4_ptr
-        );
+        return Err(Error::NotExecutable);
     }
-
-    unsafe {
-        kernel_paging
-            .map_to(
-                Page::<Size4KiB>::containing_address(stack_top - 1),
-                stack_top_frame,
-                PageTableFlags::PRESENT | PageTableFlags::WRITABLE,
-                &mut crate::kernel::paging::UseKernelFrameAllocator,
-            )
-            .expect("failed to map stack top page")
-            .flush();
+    if elf.entry_point() >= STACK_ADDRESS {
+        return Err(Error::InvalidEntryPoint(elf.entry_point()));
     }
 
-    // copy all arg string contents to the stack
-    let str_area = stack_top - args.iter().map(|s| s.as_ref().len()).sum::<usize>() as u64;
-    log::debug!("arg string area starts at {str_area:#x}");
-    let mut offset = 0;
-    for arg in args {
-        let ptr = unsafe { str_area.as_mut_ptr::<u8>().add(offset) };
-        unsafe { core::ptr::copy_nonoverlapping(arg.as_ref().as_ptr(), ptr, arg.as_ref().len()) };
+    let mut segments = Vec::new();
+    for ph in elf
+        .program_header_iter()
+        .filter(|ph| ph.ph_type() == ProgramType::LOAD)
+    {
+        let invalid = Error::InvalidSegment(ph.vaddr());
+        let in_file = ph
+            .offset()
+            .checked_add(ph.filesz())
+            .is_some_and(|end| end <= elf.content().len() as u64);
+        let end = ph
+            .vaddr()
+            .checked_add(ph.memsz())
+            .filter(|&end| end <= STACK_ADDRESS)
+            .ok_or(invalid)?;
+        if !in_file || ph.filesz() > ph.memsz() {
+            return Err(invalid);
+        }
 
-        offset += arg.as_ref().len();
+        let start = ph.vaddr() & !(Size4KiB::SIZE - 1);
+        if start < end {
+            segments.push((start, end.next_multiple_of(Size4KiB::SIZE), invalid));
+        }
     }
 
-    // create string structs for each argument
-    let arg_str_ptr = (str_area - (args.len() * size_of::<crate::types::string_const_t>()) as u64)
-        .align_down(align_of::<crate::types::string_const_t>() as u64);
-    log::debug!("arg string structs starts at {arg_str_ptr:#x}");
-    let mut offset = 0;
-    for (i, arg) in args.iter().enumerate() {
-        let arg_ptr = unsafe {
-            arg_str_ptr
-                .as_mut_ptr::<crate::types::string_const_t>()
-                .add(i)
-        };
-        unsafe {
-            *arg_ptr = crate::types::string_const_t {
-                ptr: str_area.as_ptr::<i8>().add(offset),
-                len: arg.as_ref().len() as u32,
-            };
+    segments.sort_unstable_by_key(|&(start, _, _)| start);
+    for pair in segments.windows(2) {
+        if pair[0].1 > pair[1].0 {
+            return Err(pair[1].2);
         }
-        offset += arg.as_ref().len();
     }
 
-    // create entry struct
-    let entry_struct_ptr = (arg_str_ptr - size_of::<crate::types::entry_t>() as u64)
-        .align_down(align_of::<crate::types::entry_t>() as u64);
-    log::debug!("entry pointer starts at {entry_struct_ptr:#x}");
-    let entry_args_ptr = entry_struct_ptr.as_mut_ptr::<crate::types::entry_t>();
+    Ok(())
+}
 
-    unsafe {
-        *entry_args_ptr = crate::types::entry_t {
-            argc: args.len() as u32,
-            argv: arg_str_ptr.as_mut_ptr::<crate::types::string_const_t>(),
-        };
+/// Copies the contents of `strings` below `top` and creates string structs for them below the contents.
+/// Returns the address of the first string struct, which is the new top of the stack.
+fn write_strings<T: AsRef<str>>(
+    memory: &mut AddressSpace,
+    kernel_paging: &mut KernelPaging,
+    top: VirtAddr,
+    strings: &[T],
+) -> VirtAddr {
+    let str_area = top - strings.iter().map(|s| s.as_ref().len()).sum::<usize>() as u64;
+    log::debug!("string area starts at {str_area:#x}");
+    let mut offset = 0;
+    for string in strings {
+        memory.write_bytes(kernel_paging, str_area + offset, string.as_ref().as_bytes());
+        offset += string.as_ref().len() as u64;
     }
 
-    // create pointer to the entry struct
-    let entry_ptr = entry_struct_ptr - size_of::<u64>() as u64;
-    log::debug!("entry pointer is at {entry_ptr:#x}");
-    unsafe {
-        *(entry_ptr.as_mut_ptr::<u64>()) = entry_args_ptr as u64;
+    let str_ptr = (str_area - (strings.len() * size_of::<crate::types::string_const_t>()) as u64)
+        .align_down(16u64);
+    log::debug!("string structs start at {str_ptr:#x}");
+    let mut offset = 0;
+    for (i, string) in strings.iter().enumerate() {
+        memory.write_value(
+            kernel_paging,
+            str_ptr + (i * size_of::<crate::types::string_const_t>()) as u64,
+            crate::types::string_const_t {
+                ptr: (str_area + offset).as_ptr::<i8>(),
+                len: string.as_ref().len() as u32,
+            },
+        );
+        offset += string.as_ref().len() as u64;
     }
 
-    kernel_paging
-        .unmap(Page::<Size4KiB>::containing_address(stack_top - 1))
-        .expect("Failed to unmap stack top page")
-        .1
-        .flush();
+    str_ptr
+}
+
+/// The address of the program headers in the loaded image, which are part of the first segment for executables
+fn program_headers_address(elf: &Elf) -> u64 {
+    let offset = elf.elf_header().program_header_offset();
+    elf.program_header_iter()
+        .find(|ph| {
+            ph.ph_type() == ProgramType::LOAD
+                && (ph.offset()..ph.offset() + ph.filesz()).contains(&offset)
+        })
+        .map_or(0, |ph| ph.vaddr() + (offset - ph.offset()))
+}
 
-    (VirtAddr::new(elf.entry_point()), entry_ptr, pages)
+/// 16 bytes from RDRAND, or derived from the timestamp counter on CPUs without it
+fn random_bytes() -> [u8; 16] {
+    let mut bytes = [0; 16];
+    let rdrand = x86_64::instructions::random::RdRand::new();
+    for chunk in bytes.chunks_exact_mut(8) {
+        let value = rdrand
+            .and_then(x86_64::instructions::random::RdRand::get_u64)
+            .unwrap_or_else(|| {
+                unsafe { core::arch::x86_64::_rdtsc() }.wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            });
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+    bytes
 }
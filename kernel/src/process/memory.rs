@@ -0,0 +1,739 @@
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use x86_64::{
+    structures::{
+        idt::PageFaultErrorCode,
+        paging::{
+            mapper::{MapToError, MappedFrame, TranslateResult},
+            FrameAllocator as _, FrameDeallocator as _, Mapper as _, OffsetPageTable, Page,
+            PageSize as _, PageTable, PageTableFlags, PhysFrame, Size4KiB, Translate as _,
+        },
+    },
+    VirtAddr,
+};
+
+use crate::kernel::paging::{break_copy_on_write, KernelPaging, UserspacePaging, COPY_ON_WRITE};
+
+/// An access to user memory on behalf of a process that the process itself would not be allowed to make
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fault {
+    pub address: u64,
+}
+
+/// End of the lower half of the address space, which belongs to userspace
+pub const USER_SPACE_END: u64 = 0x0000_8000_0000_0000;
+
+/// Where the contents of a region come from when a page is first touched
+#[derive(Debug, Clone)]
+pub enum Backing {
+    /// zero-filled memory
+    Anonymous,
+    /// `data[offset..offset + size]` is mapped at `vaddr`, the rest of the region is zero-filled
+    File {
+        data: Arc<[u8]>,
+        vaddr: VirtAddr,
+        offset: usize,
+        size: usize,
+    },
+}
+
+/// A page-aligned range of virtual memory (VMA) that is populated on demand
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub start: VirtAddr,
+    pub end: VirtAddr,
+    pub flags: PageTableFlags,
+    pub name: &'static str,
+    pub backing: Backing,
+    /// populated pages are shared with forked children instead of being copied on write
+    pub shared: bool,
+}
+
+impl Region {
+    pub fn contains(&self, address: VirtAddr) -> bool {
+        self.start <= address && address < self.end
+    }
+
+    /// Returns whether an access described by the page fault error code is allowed
+    pub fn allows(&self, err: PageFaultErrorCode) -> bool {
+        !(err.contains(PageFaultErrorCode::USER_MODE)
+            && !self.flags.contains(PageTableFlags::USER_ACCESSIBLE)
+            || err.contains(PageFaultErrorCode::CAUSED_BY_WRITE)
+                && !self.flags.contains(PageTableFlags::WRITABLE)
+            || err.contains(PageFaultErrorCode::INSTRUCTION_FETCH)
+                && self.flags.contains(PageTableFlags::NO_EXECUTE))
+    }
+}
+
+/// The regions of a process, the page table only contains the pages of these
+/// regions that have been touched so far
+pub struct AddressSpace {
+    pub paging: UserspacePaging<'static>,
+    /// regions by their start address
+    regions: BTreeMap<VirtAddr, Region>,
+    /// start of the heap region after the program, see [`AddressSpace::set_brk`]
+    heap_start: VirtAddr,
+    /// end of the heap, the heap region ends at the next page boundary
+    brk: VirtAddr,
+}
+
+/// The address space whose page table was loaded last, used to resolve page faults
+/// that happen while the kernel accesses user memory during a syscall.
+static ACTIVE: spin::Mutex<Option<Arc<spin::Mutex<AddressSpace>>>> = spin::Mutex::new(None);
+
+/// Loads the page table of the address space and remembers it for the page fault handler
+pub fn activate(memory: &Arc<spin::Mutex<AddressSpace>>) {
+    memory
+        .try_lock()
+        .expect("Failed to lock address space")
+        .paging
+        .load();
+
+    // the previous address space may be dropped here, after its page table was unloaded
+    let previous = ACTIVE
+        .try_lock()
+        .expect("Failed to lock active address space")
+        .replace(Arc::clone(memory));
+    drop(previous);
+}
+
+/// Populates the page at `address` in the active address space on first touch.
+/// Returns `false` if the address is not part of any region or the access is not allowed.
+pub fn handle_page_fault(address: VirtAddr, err: PageFaultErrorCode) -> bool {
+    // present pages are never populated again, this is an access violation
+    if err.contains(PageFaultErrorCode::PROTECTION_VIOLATION) {
+        return false;
+    }
+
+    let Some(memory) = ACTIVE.try_lock().and_then(|active| active.clone()) else {
+        return false;
+    };
+    let mut memory = memory
+        .try_lock()
+        .expect("address space is locked during page fault");
+
+    if !memory.paging.is_active() {
+        return false;
+    }
+
+    match memory.region(address) {
+        Some(region) if region.allows(err) => {}
+        _ => return false,
+    }
+
+    let mut kernel_paging = crate::kernel_paging();
+    memory.populate(&mut kernel_paging, Page::containing_address(address));
+
+    true
+}
+
+/// Returns the pages in `start..end` that are mapped in `page_table`.
+/// Unused entries of the upper levels are skipped as a whole, so sparse regions are cheap to walk.
+fn present_pages(page_table: &OffsetPageTable, start: VirtAddr, end: VirtAddr) -> Vec<Page> {
+    let table_at = |entry: &x86_64::structures::paging::page_table::PageTableEntry| unsafe {
+        &*(page_table.phys_offset() + entry.addr().as_u64()).as_ptr::<PageTable>()
+    };
+    // the next address that is not covered by the same entry of a level spanning `size` bytes
+    let next = |address: u64, size: u64| (address & !(size - 1)) + size;
+
+    let mut pages = Vec::new();
+    let mut address = start.as_u64();
+    while address < end.as_u64() {
+        let virt = VirtAddr::new(address);
+
+        let l4_entry = &page_table.level_4_table()[virt.p4_index()];
+        if l4_entry.is_unused() {
+            address = next(address, 1 << 39);
+            continue;
+        }
+        let l3_entry = &table_at(l4_entry)[virt.p3_index()];
+        if l3_entry.is_unused() {
+            address = next(address, 1 << 30);
+            continue;
+        }
+        let l2_entry = &table_at(l3_entry)[virt.p2_index()];
+        if l2_entry.is_unused() {
+            address = next(address, 1 << 21);
+            continue;
+        }
+        if !table_at(l2_entry)[virt.p1_index()].is_unused() {
+            pages.push(Page::containing_address(virt));
+        }
+        address = next(address, Size4KiB::SIZE);
+    }
+
+    pages
+}
+
+impl AddressSpace {
+    pub fn new(paging: UserspacePaging<'static>) -> Self {
+        AddressSpace {
+            paging,
+            regions: BTreeMap::new(),
+            heap_start: VirtAddr::zero(),
+            brk: VirtAddr::zero(),
+        }
+    }
+
+    /// Places the (empty) heap at `start`, which has to be after all regions of the program
+    pub fn init_heap(&mut self, start: VirtAddr) {
+        self.heap_start = start.align_up(Size4KiB::SIZE);
+        self.brk = self.heap_start;
+    }
+
+    pub fn heap_start(&self) -> VirtAddr {
+        self.heap_start
+    }
+
+    pub fn brk(&self) -> VirtAddr {
+        self.brk
+    }
+
+    /// Grows or shrinks the heap so that it ends at `brk`.
+    /// Returns `false` if `brk` is below the start of the heap or the heap cannot grow that far.
+    pub fn set_brk(&mut self, kernel_paging: &mut KernelPaging, brk: VirtAddr) -> bool {
+        if brk < self.heap_start || brk.as_u64() > USER_SPACE_END {
+            return false;
+        }
+
+        let old_end = self.brk.align_up(Size4KiB::SIZE);
+        let new_end = brk.align_up(Size4KiB::SIZE);
+
+        if new_end > old_end {
+            if self.find_free(old_end.as_u64(), new_end - old_end) != Some(old_end) {
+                return false;
+            }
+
+            match self.regions.get_mut(&self.heap_start) {
+                Some(heap) if heap.name == "heap" && heap.end == old_end => heap.end = new_end,
+                _ => self.add_region(Region {
+                    start: old_end,
+                    end: new_end,
+                    flags: PageTableFlags::PRESENT
+                        | PageTableFlags::WRITABLE
+                        | PageTableFlags::USER_ACCESSIBLE
+                        | PageTableFlags::NO_EXECUTE,
+                    name: "heap",
+                    backing: Backing::Anonymous,
+                    shared: false,
+                }),
+            }
+        } else if new_end < old_end {
+            self.unmap_range(kernel_paging, new_end, old_end);
+        }
+
+        self.brk = brk;
+        true
+    }
+
+    /// Returns all regions, ordered by their start address
+    pub fn regions(&self) -> impl Iterator<Item = &Region> {
+        self.regions.values()
+    }
+
+    pub fn region(&self, address: VirtAddr) -> Option<&Region> {
+        self.regions
+            .range(..=address)
+            .next_back()
+            .map(|(_, r)| r)
+            .filter(|r| r.contains(address))
+    }
+
+    /// Returns the lowest page-aligned address at or above `from` where `size` bytes fit between the regions,
+    /// `None` if they do not fit below [`USER_SPACE_END`]
+    pub fn find_free(&self, from: u64, size: u64) -> Option<VirtAddr> {
+        let mut address = from.checked_next_multiple_of(Size4KiB::SIZE)?;
+        for region in self.regions.values() {
+            if region.end.as_u64() <= address {
+                continue;
+            }
+            if address.checked_add(size)? <= region.start.as_u64() {
+                break;
+            }
+            address = region.end.as_u64();
+        }
+
+        (address.checked_add(size)? <= USER_SPACE_END).then(|| VirtAddr::new(address))
+    }
+
+    pub fn add_region(&mut self, region: Region) {
+        assert!(
+            region.start.is_aligned(Size4KiB::SIZE) && region.end.is_aligned(Size4KiB::SIZE),
+            "region {:#x} - {:#x} is not page aligned",
+            region.start,
+            region.end
+        );
+        assert!(
+            self.regions
+                .range(..region.end)
+                .next_back()
+                .is_none_or(|(_, r)| r.end <= region.start),
+            "region {:#x} - {:#x} overlaps an existing region",
+            region.start,
+            region.end
+        );
+
+        log::debug!(
+            "adding region '{}' [{:#x} - {:#x}] with flags {:?}",
+            region.name,
+            region.start,
+            region.end,
+            region.flags
+        );
+
+        self.regions.insert(region.start, region);
+    }
+
+    /// Removes the region starting at `start` and frees all of its populated pages
+    pub fn remove_region(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        start: VirtAddr,
+    ) -> Option<Region> {
+        let region = self.regions.remove(&start)?;
+
+        for page in present_pages(&self.paging.page_table, region.start, region.end) {
+            self.unmap_page(kernel_paging, page);
+        }
+
+        Some(region)
+    }
+
+    /// Unmaps all pages and forgets all regions
+    pub fn clear(&mut self, kernel_paging: &mut KernelPaging) {
+        for (_, region) in core::mem::take(&mut self.regions) {
+            for page in present_pages(&self.paging.page_table, region.start, region.end) {
+                self.unmap_page(kernel_paging, page);
+            }
+        }
+    }
+
+    /// Splits the region containing `address` into two regions at `address`
+    fn split(&mut self, address: VirtAddr) {
+        let Some(region) = self.regions.values_mut().find(|r| r.contains(address)) else {
+            return;
+        };
+        if region.start == address {
+            return;
+        }
+
+        // file backings refer to absolute addresses, so both halves can keep them
+        let mut upper = region.clone();
+        upper.start = address;
+        region.end = address;
+
+        self.regions.insert(address, upper);
+    }
+
+    /// Unmaps all regions in `start..end`, splitting regions that are partially inside.
+    /// Returns `false` if nothing was mapped in that range.
+    pub fn unmap_range(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        start: VirtAddr,
+        end: VirtAddr,
+    ) -> bool {
+        self.split(start);
+        self.split(end);
+
+        let starts = self
+            .regions
+            .range(start..end)
+            .map(|(&start, _)| start)
+            .collect::<Vec<_>>();
+        for &start in &starts {
+            self.remove_region(kernel_paging, start);
+        }
+
+        !starts.is_empty()
+    }
+
+    /// Changes the flags of all regions in `start..end` and of their populated pages.
+    /// Returns `false` without changing anything if the range is not completely mapped.
+    pub fn protect(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        start: VirtAddr,
+        end: VirtAddr,
+        flags: PageTableFlags,
+    ) -> bool {
+        let mut covered = start;
+        while covered < end {
+            match self.region(covered) {
+                Some(region) => covered = region.end,
+                None => return false,
+            }
+        }
+
+        self.split(start);
+        self.split(end);
+
+        for region in self.regions.range_mut(start..end).map(|(_, r)| r) {
+            region.flags = flags;
+
+            for page in present_pages(&self.paging.page_table, region.start, region.end) {
+                let Ok(frame) = self.paging.page_table.translate_page(page) else {
+                    continue;
+                };
+
+                // pages still shared after a fork have to stay copy-on-write
+                let page_flags = if flags.contains(PageTableFlags::WRITABLE)
+                    && !region.shared
+                    && kernel_paging.frame_allocator().frame_references(frame) > 1
+                {
+                    (flags - PageTableFlags::WRITABLE) | COPY_ON_WRITE
+                } else {
+                    flags
+                };
+
+                unsafe {
+                    self.paging
+                        .page_table
+                        .update_flags(page, page_flags)
+                        .expect("Failed to update page flags")
+                        .flush();
+                }
+            }
+        }
+
+        true
+    }
+
+    /// Returns the pages that have been populated so far with the flags of their region
+    /// and whether they are shared on purpose
+    pub fn populated_pages(&self) -> Vec<(Page, PageTableFlags, bool)> {
+        self.regions
+            .values()
+            .flat_map(|r| {
+                present_pages(&self.paging.page_table, r.start, r.end)
+                    .into_iter()
+                    .map(move |page| (page, r.flags, r.shared))
+            })
+            .collect()
+    }
+
+    /// Unmaps `page` and frees its frame, if it has been populated
+    fn unmap_page(&mut self, kernel_paging: &mut KernelPaging, page: Page) {
+        if let Ok((frame, flush)) = self.paging.page_table.unmap(page) {
+            flush.flush();
+
+            unsafe {
+                kernel_paging.deallocate_frame(frame);
+            }
+        }
+    }
+
+    /// Maps a frame for `page` and fills it from the backing of its region.
+    /// Returns the frame the page is mapped to.
+    pub fn populate(&mut self, kernel_paging: &mut KernelPaging, page: Page) -> PhysFrame {
+        self.try_populate(kernel_paging, page)
+            .expect("Failed to allocate frame!")
+    }
+
+    /// Like [`AddressSpace::populate`], but returns `None` if there are no frames left for the page or its page tables
+    pub fn try_populate(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        page: Page,
+    ) -> Option<PhysFrame> {
+        if let Ok(frame) = self.paging.page_table.translate_page(page) {
+            return Some(frame);
+        }
+
+        let region = self
+            .region(page.start_address())
+            .unwrap_or_else(|| panic!("populating page {page:?} outside of any region"));
+
+        let frame = kernel_paging.allocate_frame()?;
+
+        log::trace!(
+            "populating page {:#x} of region '{}' with frame {:#x}",
+            page.start_address(),
+            region.name,
+            frame.start_address()
+        );
+
+        let contents = unsafe {
+            &mut *(kernel_paging.page_table().phys_offset() + frame.start_address().as_u64())
+                .as_mut_ptr::<[u8; Size4KiB::SIZE as usize]>()
+        };
+        contents.fill(0);
+
+        if let Backing::File {
+            data,
+            vaddr,
+            offset,
+            size,
+        } = &region.backing
+        {
+            let from = page.start_address().max(*vaddr);
+            let to = (page.start_address() + Size4KiB::SIZE).min(*vaddr + *size as u64);
+
+            if from < to {
+                let file_start = offset + (from - *vaddr) as usize;
+                let page_start = (from - page.start_address()) as usize;
+                let len = (to - from) as usize;
+                contents[page_start..page_start + len]
+                    .copy_from_slice(&data[file_start..file_start + len]);
+            }
+        }
+
+        let mapped = unsafe {
+            self.paging.page_table.map_to_with_table_flags(
+                page,
+                frame,
+                region.flags,
+                PageTableFlags::PRESENT
+                    | PageTableFlags::WRITABLE
+                    | PageTableFlags::USER_ACCESSIBLE,
+                &mut *kernel_paging,
+            )
+        };
+        match mapped {
+            Ok(flush) => flush.flush(),
+            Err(MapToError::FrameAllocationFailed) => {
+                unsafe {
+                    kernel_paging.deallocate_frame(frame);
+                }
+                return None;
+            }
+            Err(e) => panic!("Failed to map page: {e:?}"),
+        }
+
+        Some(frame)
+    }
+
+    /// Writes `bytes` to `address` through the kernel frame mapping, populating pages as needed.
+    /// This works regardless of which page table is active.
+    pub fn write_bytes(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        address: VirtAddr,
+        bytes: &[u8],
+    ) {
+        let mut written = 0;
+        while written < bytes.len() {
+            let address = address + written as u64;
+            let page = Page::<Size4KiB>::containing_address(address);
+            let frame = self.populate(kernel_paging, page);
+
+            let page_offset = (address - page.start_address()) as usize;
+            let len = (bytes.len() - written).min(Size4KiB::SIZE as usize - page_offset);
+
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    bytes[written..].as_ptr(),
+                    (kernel_paging.page_table().phys_offset()
+                        + frame.start_address().as_u64()
+                        + page_offset as u64)
+                        .as_mut_ptr::<u8>(),
+                    len,
+                );
+            }
+
+            written += len;
+        }
+    }
+
+    /// Writes `value` to `address`, which must not cross a page boundary
+    pub fn write_value<T: Copy>(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        address: VirtAddr,
+        value: T,
+    ) {
+        let page = Page::<Size4KiB>::containing_address(address);
+        assert!(
+            Page::<Size4KiB>::containing_address(address + (size_of::<T>() as u64 - 1)) == page,
+            "value at {address:#x} crosses a page boundary"
+        );
+
+        let frame = self.populate(kernel_paging, page);
+        unsafe {
+            (kernel_paging.page_table().phys_offset()
+                + frame.start_address().as_u64()
+                + (address - page.start_address()))
+            .as_mut_ptr::<T>()
+            .write_unaligned(value);
+        }
+    }
+
+    /// Checks that the process itself may read all `len` bytes at `address`, without populating any page
+    pub fn check_readable(&self, address: VirtAddr, len: usize) -> Result<(), Fault> {
+        if len == 0 {
+            return Ok(());
+        }
+
+        let end = address
+            .as_u64()
+            .checked_add(len as u64)
+            .filter(|&end| end <= USER_SPACE_END)
+            .ok_or(Fault {
+                address: address.as_u64(),
+            })?;
+
+        for page in Page::range_inclusive(
+            Page::<Size4KiB>::containing_address(address),
+            Page::containing_address(VirtAddr::new(end - 1)),
+        ) {
+            // pages outside of regions, like the framebuffer, are mapped up front
+            let readable = match self.region(page.start_address()) {
+                Some(region) => region.allows(PageFaultErrorCode::USER_MODE),
+                None => matches!(
+                    self.paging.page_table.translate(page.start_address()),
+                    TranslateResult::Mapped { flags, .. } if flags.contains(PageTableFlags::USER_ACCESSIBLE)
+                ),
+            };
+            if !readable {
+                return Err(Fault {
+                    address: page.start_address().as_u64().max(address.as_u64()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the frame of the user page containing `address` if the process may access it,
+    /// populating the page and breaking copy-on-write sharing as needed
+    fn user_frame(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        address: VirtAddr,
+        write: bool,
+    ) -> Result<PhysFrame, Fault> {
+        let fault = Fault {
+            address: address.as_u64(),
+        };
+
+        if address.as_u64() >= USER_SPACE_END {
+            return Err(fault);
+        }
+        let page = Page::containing_address(address);
+
+        if self.paging.page_table.translate_page(page).is_err() {
+            let access = if write {
+                PageFaultErrorCode::USER_MODE | PageFaultErrorCode::CAUSED_BY_WRITE
+            } else {
+                PageFaultErrorCode::USER_MODE
+            };
+            match self.region(address) {
+                Some(region) if region.allows(access) => {
+                    self.populate(kernel_paging, page);
+                }
+                _ => return Err(fault),
+            }
+        }
+
+        let TranslateResult::Mapped {
+            frame: MappedFrame::Size4KiB(frame),
+            flags,
+            ..
+        } = self.paging.page_table.translate(address)
+        else {
+            return Err(fault);
+        };
+
+        if !flags.contains(PageTableFlags::USER_ACCESSIBLE) {
+            return Err(fault);
+        }
+        if write && !flags.contains(PageTableFlags::WRITABLE) {
+            if !break_copy_on_write(&mut self.paging.page_table, kernel_paging, page) {
+                return Err(fault);
+            }
+
+            return self
+                .paging
+                .page_table
+                .translate_page(page)
+                .map_err(|_| fault);
+        }
+
+        Ok(frame)
+    }
+
+    /// Copies `buffer.len()` bytes at `address` in this address space into `buffer`.
+    /// Fails if the process itself could not read all of them.
+    pub fn copy_from_user(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        address: VirtAddr,
+        buffer: &mut [u8],
+    ) -> Result<(), Fault> {
+        let mut copied = 0;
+        while copied < buffer.len() {
+            let address = address + copied as u64;
+            let frame = self.user_frame(kernel_paging, address, false)?;
+
+            let page_offset = address.as_u64() % Size4KiB::SIZE;
+            let len = (buffer.len() - copied).min((Size4KiB::SIZE - page_offset) as usize);
+
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    (kernel_paging.page_table().phys_offset()
+                        + frame.start_address().as_u64()
+                        + page_offset)
+                        .as_ptr::<u8>(),
+                    buffer[copied..].as_mut_ptr(),
+                    len,
+                );
+            }
+
+            copied += len;
+        }
+
+        Ok(())
+    }
+
+    /// Copies `bytes` to `address` in this address space.
+    /// Fails if the process itself could not write all of them, in which case some may have been written.
+    pub fn copy_to_user(
+        &mut self,
+        kernel_paging: &mut KernelPaging,
+        address: VirtAddr,
+        bytes: &[u8],
+    ) -> Result<(), Fault> {
+        let mut copied = 0;
+        while copied < bytes.len() {
+            let address = address + copied as u64;
+            let frame = self.user_frame(kernel_paging, address, true)?;
+
+            let page_offset = address.as_u64() % Size4KiB::SIZE;
+            let len = (bytes.len() - copied).min((Size4KiB::SIZE - page_offset) as usize);
+
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    bytes[copied..].as_ptr(),
+                    (kernel_paging.page_table().phys_offset()
+                        + frame.start_address().as_u64()
+                        + page_offset)
+                        .as_mut_ptr::<u8>(),
+                    len,
+                );
+            }
+
+            copied += len;
+        }
+
+        Ok(())
+    }
+
+    pub fn fork(&mut self, kernel_paging: &mut KernelPaging) -> AddressSpace {
+        let pages = self.populated_pages();
+        let paging = self.paging.fork(kernel_paging, &pages);
+
+        AddressSpace {
+            paging,
+            regions: self.regions.clone(),
+            heap_start: self.heap_start,
+            brk: self.brk,
+        }
+    }
+}
+
+impl Drop for AddressSpace {
+    fn drop(&mut self) {
+        let mut kernel_paging = crate::kernel_paging();
+
+        self.clear(&mut kernel_paging);
+    }
+}
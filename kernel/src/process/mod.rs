@@ -1,12 +1,21 @@
 use core::{cell::RefCell, sync::atomic::AtomicU32};
 
-use alloc::{collections::vec_deque::VecDeque, vec::Vec};
+use alloc::{
+    collections::{btree_map::BTreeMap, vec_deque::VecDeque},
+    sync::Arc,
+};
 use anyhow::Context;
-use x86_64::structures::paging::{FrameDeallocator, Mapper};
-
-use crate::kernel::paging::UserspacePaging;
 
 mod elf;
+pub mod memory;
+pub mod pipe;
+pub mod scheduler;
+pub mod signal;
+pub mod thread;
+mod wait_queue;
+
+pub use thread::Thread;
+pub use wait_queue::WaitQueue;
 
 struct PidFactory {
     next_pid: AtomicU32,
@@ -24,46 +33,99 @@ impl PidFactory {
     }
 }
 
+/// Process and thread ids are taken from the same counter,
+/// the main thread of a process has the same id as the process
 static PID_FACTORY: PidFactory = PidFactory::new();
 
+/// Orphaned processes become children of this process
+pub const INIT_PID: u32 = 1;
+
+/// Exit status of a process that was killed because of a failing syscall
+pub const EXIT_BAD_SYSCALL: u32 = 128 + 31;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
     Ready,
     Sleeping(u64),
-    WaitingForStream(i32),
+    WaitingForStream {
+        fd: i32,
+        /// user address of the `syscall_read_t` to write the result to
+        arg: u64,
+    },
+    WaitingForRoom {
+        fd: i32,
+        /// user address of the `syscall_write_t` to write the result to
+        arg: u64,
+    },
     WaitingForChild {
         pid: u32,
-        arg: *mut crate::syscall::generated::syscall_waitpid_t,
+        /// user address of the `syscall_waitpid_t` to write the result to
+        arg: u64,
     },
+    WaitingForThread {
+        tid: u32,
+        /// user address of the `syscall_thread_join_t` to write the result to
+        arg: u64,
+    },
+    /// the process was stopped by a signal
+    Stopped,
+    /// a kernel thread that waits for work, whoever has work for it makes it ready
+    Idle,
     Terminated(u64),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct MappedPage {
-    pub name: &'static str,
-    pub page: x86_64::structures::paging::Page,
-    pub flags: x86_64::structures::paging::PageTableFlags,
-}
-
+/// The resources shared by the threads of a process
 pub struct Process {
     pid: u32,
-    pub state: State,
-    pub paging: UserspacePaging<'static>,
-    pub cs: x86_64::structures::gdt::SegmentSelector,
-    pub ds: x86_64::structures::gdt::SegmentSelector,
-    pub flags: u64,
-    pub rip: u64,
-    pub registers: crate::idt::GPRegisters,
-    pub xsave: xsave::XSave,
-    pub mapped_pages: Vec<MappedPage>,
-    file_descriptors: alloc::collections::BTreeMap<i32, FileDescriptor>,
+    /// the process that waits for this one, `None` if there is nobody to reap it
+    parent: Option<u32>,
+    /// set once the process exited, it stays a zombie until its parent reaps it
+    exit_status: Option<u32>,
+    pub memory: Arc<spin::Mutex<memory::AddressSpace>>,
+    file_descriptors: BTreeMap<i32, FileDescriptorEntry>,
+    /// absolute path of the working directory, relative paths are resolved against it
+    cwd: alloc::string::String,
+    /// the arguments the running program was started with
+    cmdline: alloc::vec::Vec<alloc::string::String>,
+    /// exit status of threads that exited but were not joined yet
+    exited_threads: BTreeMap<u32, u32>,
+    /// threads waiting for a child of the process to exit
+    pub child_waiters: WaitQueue,
+    pub signals: signal::Signals,
 }
 
+/// An open file, shared by all file descriptors that were duplicated from the same one
+/// or inherited by `fork`, including its offset and the data buffered in it
+pub type OpenFile = Arc<spin::Mutex<FileDescriptor>>;
+
+/// An entry of the file descriptor table of a process
 #[derive(Debug, Clone)]
+struct FileDescriptorEntry {
+    file: OpenFile,
+    /// the file descriptor is closed by `execve`
+    close_on_exec: bool,
+}
+
+impl FileDescriptorEntry {
+    fn new(fd: FileDescriptor) -> Self {
+        FileDescriptorEntry {
+            file: Arc::new(spin::Mutex::new(fd)),
+            close_on_exec: false,
+        }
+    }
+}
+
+#[derive(Debug)]
 pub enum FileDescriptor {
+    /// a file of the VFS that is read and written at an offset
     Regular {
         path: alloc::string::String,
+        inode: Arc<dyn crate::vfs::Inode>,
         offset: usize,
+        readable: bool,
+        writable: bool,
+        /// every write goes to the end of the file
+        append: bool,
     },
     ForeignStream {
         stream_type: ForeignStreamType,
@@ -72,18 +134,23 @@ pub enum FileDescriptor {
         buffer: VecDeque<u8>,
         max_size: usize,
         stream_type: OwnedStreamType,
+        /// threads waiting for data in the buffer
+        readers: WaitQueue,
+    },
+    Pipe {
+        end: pipe::PipeEnd,
+    },
+    /// a device of devfs, devices with input are streams, the others are read and written at an offset
+    Device {
+        device: &'static dyn crate::driver::device::Device,
+        offset: usize,
     },
-    Terminal,
-    Serial,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OwnedStreamType {
-    Stdin,
-    Stdout,
     Keyboard,
     Mouse,
-    Serial,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -93,7 +160,8 @@ pub enum ForeignStreamType {
 
 pub struct Processes {
     processes: RefCell<VecDeque<Process>>,
-    current_pid: AtomicU32,
+    threads: RefCell<BTreeMap<u32, Thread>>,
+    current_tid: AtomicU32,
 }
 
 impl Processes {
@@ -145,22 +213,6 @@ impl Processes {
             .map_or_else(|| panic!("process {pid} not found"), f)
     }
 
-    #[track_caller]
-    pub fn with_process_mut<F, R>(&self, pid: u32, f: F) -> R
-    where
-        F: FnOnce(&mut Process) -> R,
-    {
-        let mut processes = self
-            .processes
-            .try_borrow_mut()
-            .with_context(|| core::panic::Location::caller())
-            .expect("Failed to borrow processes");
-        processes
-            .iter_mut()
-            .find(|p| p.pid == pid)
-            .map_or_else(|| panic!("process {pid} not found"), f)
-    }
-
     #[track_caller]
     pub fn processes(&self) -> core::cell::Ref<'_, VecDeque<Process>> {
         self.processes
@@ -178,27 +230,72 @@ impl Processes {
     }
 
     #[track_caller]
-    pub fn current(&self) -> Option<core::cell::Ref<'_, Process>> {
-        let pid = self.current_pid.load(core::sync::atomic::Ordering::Relaxed);
+    pub fn thread(&self, tid: u32) -> impl core::ops::Deref<Target = Thread> + '_ {
+        core::cell::Ref::map(self.threads(), |threads| {
+            threads
+                .get(&tid)
+                .unwrap_or_else(|| panic!("thread {tid} not found"))
+        })
+    }
 
-        core::cell::Ref::filter_map(self.processes(), |processes| {
-            processes.iter().find(|p| p.pid == pid)
+    #[track_caller]
+    pub fn thread_mut(&self, tid: u32) -> impl core::ops::DerefMut<Target = Thread> + '_ {
+        core::cell::RefMut::map(self.threads_mut(), |threads| {
+            threads
+                .get_mut(&tid)
+                .unwrap_or_else(|| panic!("thread {tid} not found"))
         })
-        .ok()
     }
 
     #[track_caller]
-    pub fn current_mut(&self) -> Option<core::cell::RefMut<'_, Process>> {
-        let pid = self.current_pid.load(core::sync::atomic::Ordering::Relaxed);
+    pub fn threads(&self) -> core::cell::Ref<'_, BTreeMap<u32, Thread>> {
+        self.threads
+            .try_borrow()
+            .with_context(|| core::panic::Location::caller())
+            .expect("Failed to borrow threads")
+    }
 
-        core::cell::RefMut::filter_map(self.processes_mut(), |processes| {
-            processes.iter_mut().find(|p| p.pid == pid)
-        })
-        .ok()
+    #[track_caller]
+    pub fn threads_mut(&self) -> core::cell::RefMut<'_, BTreeMap<u32, Thread>> {
+        self.threads
+            .try_borrow_mut()
+            .with_context(|| core::panic::Location::caller())
+            .expect("Failed to borrow threads")
+    }
+
+    /// Ids of the threads of process `pid` that have not terminated
+    #[track_caller]
+    pub fn threads_of(&self, pid: u32) -> alloc::vec::Vec<u32> {
+        self.threads()
+            .values()
+            .filter(|t| t.pid() == Some(pid) && !matches!(t.state(), State::Terminated(_)))
+            .map(Thread::tid)
+            .collect()
+    }
+
+    /// The thread that was interrupted
+    #[track_caller]
+    pub fn current(&self) -> Option<core::cell::Ref<'_, Thread>> {
+        let tid = self.current_tid.load(core::sync::atomic::Ordering::Relaxed);
+
+        core::cell::Ref::filter_map(self.threads(), |threads| threads.get(&tid)).ok()
+    }
+
+    #[track_caller]
+    pub fn current_mut(&self) -> Option<core::cell::RefMut<'_, Thread>> {
+        let tid = self.current_tid.load(core::sync::atomic::Ordering::Relaxed);
+
+        core::cell::RefMut::filter_map(self.threads_mut(), |threads| threads.get_mut(&tid)).ok()
     }
 
-    pub fn add_process(&self, process: Process) {
+    pub fn add_process(&self, process: Process, main_thread: Thread) {
         self.processes.borrow_mut().push_back(process);
+        self.add_thread(main_thread);
+    }
+
+    pub fn add_thread(&self, thread: Thread) {
+        scheduler::scheduler().add(thread.tid(), thread.priority(), thread.state());
+        self.threads.borrow_mut().insert(thread.tid(), thread);
     }
 }
 unsafe impl Sync for Processes {}
@@ -207,29 +304,30 @@ pub fn store_state(
     registers: crate::idt::GPRegisters,
     stack_frame: &x86_64::structures::idt::InterruptStackFrame,
 ) -> Option<u32> {
-    let mut process = PROCESSES.current_mut()?;
+    let mut thread = PROCESSES.current_mut()?;
 
-    process.flags = stack_frame.cpu_flags.bits();
-    process.rip = stack_frame.instruction_pointer.as_u64();
-    process.registers = crate::idt::GPRegisters {
+    thread.flags = stack_frame.cpu_flags.bits();
+    thread.rip = stack_frame.instruction_pointer.as_u64();
+    thread.registers = crate::idt::GPRegisters {
         rsp: stack_frame.stack_pointer.as_u64(),
         ..registers
     };
-    process.xsave.save();
+    thread.xsave.save();
 
-    let pid = PROCESSES
-        .current_pid
+    let tid = PROCESSES
+        .current_tid
         .load(core::sync::atomic::Ordering::Relaxed);
     PROCESSES
-        .current_pid
+        .current_tid
         .store(0, core::sync::atomic::Ordering::Relaxed);
 
-    Some(pid)
+    Some(tid)
 }
 
 pub static PROCESSES: Processes = Processes {
     processes: RefCell::new(VecDeque::new()),
-    current_pid: AtomicU32::new(0),
+    threads: RefCell::new(BTreeMap::new()),
+    current_tid: AtomicU32::new(0),
 };
 
 impl Process {
@@ -238,75 +336,78 @@ impl Process {
         ds: x86_64::structures::gdt::SegmentSelector,
         flags: u64,
         elf: &[u8],
-    ) -> Self {
+        args: &[&str],
+    ) -> Result<(Self, Thread), elf::Error> {
         let pid = PID_FACTORY.next_pid();
 
         log::debug!("loading process with pid {pid}");
 
         let mut kernel_paging = crate::kernel_paging();
 
-        let mut userspace_paging = kernel_paging.make_userspace_paging();
+        let mut memory = memory::AddressSpace::new(kernel_paging.make_userspace_paging());
 
-        let (userspace_address, userspace_stack, mapped_pages) =
-            elf::load::<&str>(&mut userspace_paging, &mut kernel_paging, elf, &[]);
+        let (userspace_address, userspace_stack) =
+            elf::load(&mut memory, &mut kernel_paging, elf, args, &[])?;
 
         log::debug!("elf for pid {pid} loaded at address {userspace_address:#x}, stack at {userspace_stack:#x}");
 
-        let mut file_descriptors = alloc::collections::BTreeMap::new();
-        file_descriptors.insert(
-            0,
-            FileDescriptor::OwnedStream {
-                buffer: alloc::collections::vec_deque::VecDeque::new(),
-                max_size: 1024,
-                stream_type: OwnedStreamType::Stdin,
-            },
-        );
-        file_descriptors.insert(
-            1,
-            FileDescriptor::OwnedStream {
-                buffer: alloc::collections::vec_deque::VecDeque::new(),
-                max_size: 1024,
-                stream_type: OwnedStreamType::Stdout,
-            },
-        );
-
-        Process {
-            pid: PID_FACTORY.next_pid(),
-            state: State::Ready,
-            paging: userspace_paging,
+        // the standard input, output and error share one open file of the console
+        let console = crate::FILE_SYSTEM
+            .lookup("/dev/console")
+            .ok()
+            .and_then(|node| node.open())
+            .expect("/dev/console cannot be opened");
+        let console = FileDescriptorEntry::new(console);
+        let file_descriptors = (0..=2).map(|fd| (fd, console.clone())).collect();
+
+        let process = Process {
+            pid,
+            parent: None,
+            exit_status: None,
+            memory: Arc::new(spin::Mutex::new(memory)),
+            file_descriptors,
+            cwd: alloc::string::String::from("/"),
+            cmdline: args.iter().map(|&arg| arg.into()).collect(),
+            exited_threads: BTreeMap::new(),
+            child_waiters: WaitQueue::new(),
+            signals: signal::Signals::default(),
+        };
+        let thread = Thread::user(
+            pid,
+            pid,
             cs,
             ds,
             flags,
-            rip: userspace_address.as_u64(),
-            registers: crate::idt::GPRegisters {
-                rsp: userspace_stack.as_u64(),
-                ..Default::default()
-            },
-            xsave: xsave::XSave::default(),
-            mapped_pages,
-            file_descriptors,
-        }
+            userspace_address.as_u64(),
+            userspace_stack.as_u64(),
+        );
+
+        Ok((process, thread))
     }
 
-    pub fn execve<T: AsRef<str>>(&mut self, elf: &[u8], args: &[T]) {
+    /// Replaces the address space with the program in `elf` started with `args` and the environment `env`,
+    /// and resets `thread` to its entry point.
+    /// The other threads of the process have to be terminated by the caller once it succeeded,
+    /// if the program cannot be loaded the process is left as it is.
+    pub fn execve<T: AsRef<str>>(
+        &mut self,
+        thread: &mut Thread,
+        elf: &[u8],
+        args: &[T],
+        env: &[T],
+    ) -> Result<(), elf::Error> {
         log::debug!("execve for pid {}", self.pid);
 
         let mut kernel_paging = crate::kernel_paging();
+        let mut memory = memory::AddressSpace::new(kernel_paging.make_userspace_paging());
 
-        for page in &self.mapped_pages {
-            let (frame, flush) = self
-                .paging
-                .page_table
-                .unmap(page.page)
-                .expect("Failed to unmap page");
-            flush.flush();
-            unsafe {
-                kernel_paging.deallocate_frame(frame);
-            }
-        }
+        let (userspace_address, userspace_stack) =
+            elf::load(&mut memory, &mut kernel_paging, elf, args, env)?;
+        drop(kernel_paging);
 
-        let (userspace_address, userspace_stack, mapped_pages) =
-            elf::load(&mut self.paging, &mut kernel_paging, elf, args);
+        // the old address space is freed once it is no longer active
+        self.memory = Arc::new(spin::Mutex::new(memory));
+        self.cmdline = args.iter().map(|arg| arg.as_ref().into()).collect();
 
         log::debug!(
             "elf for pid {} loaded at address {:#x}, stack at {:#x}",
@@ -315,223 +416,308 @@ impl Process {
             userspace_stack.as_u64()
         );
 
-        self.rip = userspace_address.as_u64();
-        self.registers = crate::idt::GPRegisters {
+        thread.rip = userspace_address.as_u64();
+        thread.registers = crate::idt::GPRegisters {
             rsp: userspace_stack.as_u64(),
             ..Default::default()
         };
-        self.mapped_pages = mapped_pages;
+        self.exited_threads.clear();
+        self.file_descriptors
+            .retain(|_, entry| !entry.close_on_exec);
+        self.signals.execve();
+        Ok(())
     }
 
     pub fn pid(&self) -> u32 {
         self.pid
     }
 
-    fn update_state(pid: u32) {
-        let mut process = PROCESSES.process_mut(pid);
+    pub fn parent(&self) -> Option<u32> {
+        self.parent
+    }
 
-        match process.state {
-            State::Sleeping(target) => {
-                let ticks = unsafe { crate::i8253::TIMER0.ticks() };
-                if ticks >= target {
-                    process.state = State::Ready;
-                }
-            }
-            State::WaitingForStream(fd) => {
-                let file_descriptor = process
-                    .file_descriptors
-                    .get(&fd)
-                    .expect("File descriptor not found");
-
-                match file_descriptor {
-                    FileDescriptor::OwnedStream { buffer, .. } => {
-                        if !buffer.is_empty() {
-                            drop(process);
-                            crate::syscall::handle_syscall(pid);
-                            PROCESSES.process_mut(pid).state = State::Ready;
-                        }
-                    }
-                    FileDescriptor::Regular { .. } => {
-                        panic!("cannot wait for regular file descriptor")
-                    }
-                    FileDescriptor::ForeignStream { stream_type } => match stream_type {
-                        &ForeignStreamType::Process {
-                            pid: other_pid,
-                            file_descriptor,
-                        } => {
-                            drop(process);
-                            let other_process = PROCESSES.process(other_pid);
-                            let fd = other_process
-                                .file_descriptor(file_descriptor)
-                                .expect("File descriptor not found");
-                            match fd {
-                                FileDescriptor::OwnedStream { buffer, .. } => {
-                                    if !buffer.is_empty() {
-                                        drop(other_process);
-                                        crate::syscall::handle_syscall(pid);
-                                        PROCESSES.process_mut(pid).state = State::Ready;
-                                    }
-                                }
-                                e => panic!("cannot wait for foreign file descriptor: {:?}", e,),
-                            }
-                        }
-                    },
-                    FileDescriptor::Terminal => todo!(),
-                    FileDescriptor::Serial => todo!(),
-                }
-            }
-            _ => {}
-        }
+    pub fn set_parent(&mut self, parent: Option<u32>) {
+        self.parent = parent;
     }
 
-    pub fn load_paging(&self) {
-        self.paging.load();
+    pub fn cwd(&self) -> &str {
+        &self.cwd
     }
 
-    pub fn fork(&self) -> Process {
-        let mut kernel_paging = crate::kernel_paging();
+    /// Sets the working directory, `cwd` has to be an absolute path without `.` and `..`
+    pub fn set_cwd(&mut self, cwd: alloc::string::String) {
+        self.cwd = cwd;
+    }
 
-        let forked_paging = self.paging.fork(&mut kernel_paging, &self.mapped_pages);
-
-        Process {
-            pid: PID_FACTORY.next_pid(),
-            state: self.state,
-            paging: forked_paging,
-            cs: self.cs,
-            ds: self.ds,
-            flags: self.flags,
-            rip: self.rip,
-            registers: self.registers,
-            xsave: self.xsave,
-            mapped_pages: self.mapped_pages.clone(),
-            file_descriptors: self.file_descriptors.clone(),
-        }
+    /// The arguments the running program was started with
+    pub fn cmdline(&self) -> &[alloc::string::String] {
+        &self.cmdline
     }
 
-    pub fn new_file_descriptor(&mut self, fd: FileDescriptor) -> i32 {
-        let file_descriptor = self.file_descriptors.keys().max().map_or(0, |max| max + 1);
-        self.file_descriptors.insert(file_descriptor, fd);
-        file_descriptor
+    /// Resolves `path` against the working directory of the process
+    pub fn absolute_path(&self, path: &str) -> alloc::string::String {
+        crate::vfs::absolute_path(&self.cwd, path)
     }
 
-    pub fn close_file_descriptor(&mut self, fd: i32) -> Option<FileDescriptor> {
-        self.file_descriptors.remove(&fd)
+    pub fn exit_status(&self) -> Option<u32> {
+        self.exit_status
     }
 
-    pub fn file_descriptor(&self, fd: i32) -> Option<&FileDescriptor> {
-        self.file_descriptors.get(&fd)
+    /// Turns the process into a zombie with `status` and returns its open files, which have to be closed
+    pub fn exited(&mut self, status: u32) -> alloc::vec::Vec<OpenFile> {
+        self.exit_status = Some(status);
+        core::mem::take(&mut self.file_descriptors)
+            .into_values()
+            .map(|entry| entry.file)
+            .collect()
     }
 
-    pub fn file_descriptor_mut(&mut self, fd: i32) -> Option<&mut FileDescriptor> {
-        self.file_descriptors.get_mut(&fd)
+    /// Records the exit status of a thread until it is joined
+    pub fn thread_exited(&mut self, tid: u32, status: u32) {
+        self.exited_threads.insert(tid, status);
     }
 
-    pub fn file_descriptors(&self) -> impl Iterator<Item = (&i32, &FileDescriptor)> {
-        self.file_descriptors.iter()
+    /// Returns the exit status of a thread that exited and was not joined yet
+    pub fn join_thread(&mut self, tid: u32) -> Option<u32> {
+        self.exited_threads.remove(&tid)
     }
 
-    pub fn file_descriptors_mut(&mut self) -> impl Iterator<Item = (&i32, &mut FileDescriptor)> {
-        self.file_descriptors.iter_mut()
+    pub fn load_paging(&self) {
+        memory::activate(&self.memory);
     }
 
-    pub fn redirect_stdout_to_term(&mut self) {
-        *self
-            .file_descriptor_mut(1)
-            .expect("File descriptor 1 not found") = FileDescriptor::Terminal;
+    /// Creates a new process with a copy of the address space and `thread` as its only thread
+    pub fn fork(&mut self, thread: &Thread) -> (Process, Thread) {
+        let mut kernel_paging = crate::kernel_paging();
+
+        let forked_memory = self
+            .memory
+            .try_lock()
+            .expect("Failed to lock address space")
+            .fork(&mut kernel_paging);
+
+        let pid = PID_FACTORY.next_pid();
+
+        let process = Process {
+            pid,
+            parent: Some(self.pid),
+            exit_status: None,
+            memory: Arc::new(spin::Mutex::new(forked_memory)),
+            file_descriptors: self.file_descriptors.clone(),
+            cwd: self.cwd.clone(),
+            cmdline: self.cmdline.clone(),
+            exited_threads: BTreeMap::new(),
+            child_waiters: WaitQueue::new(),
+            signals: self.signals.fork(),
+        };
+
+        (process, thread.fork(pid))
     }
 
-    pub fn redirect_stdout_to_serial(&mut self) {
-        *self
-            .file_descriptor_mut(1)
-            .expect("File descriptor 1 not found") = FileDescriptor::Serial;
+    /// Opens `fd` at the lowest free file descriptor
+    pub fn new_file_descriptor(&mut self, fd: FileDescriptor, close_on_exec: bool) -> i32 {
+        let file_descriptor = self.lowest_free_file_descriptor();
+        self.file_descriptors.insert(
+            file_descriptor,
+            FileDescriptorEntry {
+                close_on_exec,
+                ..FileDescriptorEntry::new(fd)
+            },
+        );
+        file_descriptor
     }
 
-    pub fn redirect_keyboard_to_stdin(&mut self) {
-        *self
-            .file_descriptor_mut(0)
-            .expect("File descriptor 0 not found") = FileDescriptor::OwnedStream {
-            buffer: alloc::collections::vec_deque::VecDeque::new(),
-            max_size: 1024,
-            stream_type: OwnedStreamType::Keyboard,
-        };
+    fn lowest_free_file_descriptor(&self) -> i32 {
+        (0..i32::MAX)
+            .find(|fd| !self.file_descriptors.contains_key(fd))
+            .expect("No free file descriptor")
     }
 
-    pub fn redirect_serial_to_stdin(&mut self) {
-        *self
-            .file_descriptor_mut(0)
-            .expect("File descriptor 0 not found") = FileDescriptor::OwnedStream {
-            buffer: alloc::collections::vec_deque::VecDeque::new(),
-            max_size: 1024,
-            stream_type: OwnedStreamType::Serial,
-        };
+    /// Makes `new_fd`, or the lowest free file descriptor if it is `None`, refer to the open file of `fd`.
+    /// Returns the new file descriptor and the open file it referred to before, or `None` if `fd` is not open.
+    pub fn duplicate_file_descriptor(
+        &mut self,
+        fd: i32,
+        new_fd: Option<i32>,
+        close_on_exec: bool,
+    ) -> Option<(i32, Option<OpenFile>)> {
+        let file = Arc::clone(&self.file_descriptors.get(&fd)?.file);
+
+        // duplicating a file descriptor onto itself leaves it unchanged
+        if new_fd == Some(fd) {
+            return Some((fd, None));
+        }
+
+        let new_fd = new_fd.unwrap_or_else(|| self.lowest_free_file_descriptor());
+        let replaced = self.file_descriptors.insert(
+            new_fd,
+            FileDescriptorEntry {
+                file,
+                close_on_exec,
+            },
+        );
+
+        Some((new_fd, replaced.map(|entry| entry.file)))
     }
-}
 
-impl Drop for Process {
-    fn drop(&mut self) {
-        let mut kernel_paging = crate::kernel_paging();
+    pub fn close_file_descriptor(&mut self, fd: i32) -> Option<OpenFile> {
+        self.file_descriptors.remove(&fd).map(|entry| entry.file)
+    }
 
-        for &MappedPage { page, .. } in &self.mapped_pages {
-            let (frame, flush) = self
-                .paging
-                .page_table
-                .unmap(page)
-                .expect("Failed to unmap page");
+    pub fn file_descriptor(&self, fd: i32) -> Option<OpenFile> {
+        self.file_descriptors
+            .get(&fd)
+            .map(|entry| Arc::clone(&entry.file))
+    }
 
-            flush.flush();
+    /// The open file descriptors in ascending order
+    pub fn file_descriptors(&self) -> impl Iterator<Item = i32> + '_ {
+        self.file_descriptors.keys().copied()
+    }
+}
 
-            unsafe {
-                kernel_paging.deallocate_frame(frame);
+/// Appends `bytes` to the owned streams of type `stream_type` of all processes and wakes their readers.
+/// Streams without room for all of the bytes drop them.
+pub fn feed_streams(stream_type: OwnedStreamType, bytes: &[u8]) {
+    let mut woken = alloc::vec::Vec::new();
+    let mut fed = alloc::collections::BTreeSet::new();
+
+    for process in PROCESSES.processes().iter() {
+        let pid = process.pid;
+        for entry in process.file_descriptors.values() {
+            // an open file shared by several file descriptors is only fed once
+            if !fed.insert(Arc::as_ptr(&entry.file)) {
+                continue;
             }
+
+            if let FileDescriptor::OwnedStream {
+                buffer,
+                max_size,
+                stream_type: fd_stream_type,
+                readers,
+            } = &mut *entry
+                .file
+                .try_lock()
+                .expect("Failed to lock file descriptor")
+            {
+                if *fd_stream_type != stream_type {
+                    continue;
+                }
+
+                if buffer.len() + bytes.len() <= *max_size {
+                    buffer.extend(bytes);
+                    woken.extend(readers.take());
+                } else {
+                    log::warn!(
+                        "{stream_type:?} buffer overflow in process {pid}: buffer size {} exceeds max size {}",
+                        buffer.len(),
+                        max_size
+                    );
+                }
+            }
+        }
+    }
+
+    wake(woken);
+}
+
+/// The newest process with a file open for which `reads_input` is true, signals typed into the input go to it
+pub fn foreground_process(reads_input: impl Fn(&FileDescriptor) -> bool) -> Option<u32> {
+    PROCESSES
+        .processes()
+        .iter()
+        .rev()
+        .find(|p| {
+            p.file_descriptors.values().any(|entry| {
+                reads_input(
+                    &entry
+                        .file
+                        .try_lock()
+                        .expect("Failed to lock file descriptor"),
+                )
+            })
+        })
+        .map(Process::pid)
+}
+
+/// Resumes the blocked syscalls of the threads taken from a [`WaitQueue`].
+/// The processes and threads must not be borrowed.
+pub fn wake(tids: impl IntoIterator<Item = u32>) {
+    for tid in tids {
+        let state = PROCESSES.threads().get(&tid).map(Thread::state);
+
+        match state {
+            Some(State::WaitingForStream { .. }) => crate::syscall::resume_read(tid),
+            Some(State::WaitingForRoom { .. }) => crate::syscall::resume_write(tid),
+            Some(State::WaitingForChild { .. }) => crate::syscall::resume_waitpid(tid),
+            // the thread was terminated, or it is woken by whoever it waits for
+            _ => log::trace!("not waking thread {tid} in state {state:?}"),
         }
     }
 }
 
+/// Accounts a timer tick to the interrupted thread `tid` and wakes sleeping threads.
+/// Returns whether the interrupted thread has to give up the CPU.
+pub fn timer_tick(tid: Option<u32>) -> bool {
+    let now = crate::i8253::timer0().ticks();
+
+    if let Some(tid) = tid {
+        PROCESSES.thread_mut(tid).stats.cpu_ticks += 1;
+    }
+
+    let woken = scheduler::scheduler().expired_sleepers(now);
+    for tid in woken {
+        PROCESSES.thread_mut(tid).set_state(State::Ready);
+    }
+
+    scheduler::scheduler().should_preempt(now)
+}
+
 pub fn schedule() -> ! {
     loop {
         x86_64::instructions::interrupts::disable();
         log::trace!("scheduling...");
 
-        let mut processes = PROCESSES.processes_mut();
+        let mut threads = PROCESSES.threads_mut();
 
-        processes.retain(|p| !matches!(p.state, State::Terminated(_)));
+        threads.retain(|_, t| !matches!(t.state(), State::Terminated(_)));
 
-        if processes.is_empty() {
-            log::warn!("no processes left to schedule, halting...");
+        if threads.is_empty() {
+            log::warn!("no threads left to schedule, halting...");
             x86_64::instructions::interrupts::disable();
             x86_64::instructions::hlt();
         }
 
-        let len = processes.len();
-        drop(processes);
+        drop(threads);
 
-        for i in 0..len {
-            let pid = PROCESSES.processes().get(i).expect("Process not found").pid;
-            Process::update_state(pid);
-        }
-
-        let mut processes = PROCESSES.processes_mut();
+        // zombies stay until their parent reaps them, nobody waits for those without a parent
+        PROCESSES
+            .processes_mut()
+            .retain(|p| p.exit_status.is_none() || p.parent.is_some());
+        wait_queue::wake_deferred_waker();
 
-        // rotate through processes until we find one that is ready
-        for _ in 0..processes.len() {
-            let process = processes.front_mut().expect("No processes left!");
+        let now = crate::i8253::timer0().ticks();
+        let decision = scheduler::scheduler().pick(now);
 
-            if process.state == State::Ready {
-                log::trace!("scheduling {}", process.pid);
-                let pid = process.pid;
+        if let Some(decision) = decision {
+            if let Some(tid) = decision.preempted {
+                PROCESSES.thread_mut(tid).stats.preempted += 1;
+            }
 
-                processes.rotate_left(1);
-                drop(processes);
+            if decision.switched {
+                log::trace!("switching to {}", decision.tid);
+                PROCESSES.thread_mut(decision.tid).stats.scheduled += 1;
+            }
 
-                iret(pid)
-            } else {
-                processes.rotate_left(1);
+            // the thread may have been terminated or stopped by a signal
+            if crate::syscall::deliver_signals(decision.tid) {
+                iret(decision.tid)
             }
-        }
 
-        drop(processes);
+            continue;
+        }
 
-        log::trace!("no ready processes found, sleeping...");
+        log::trace!("no ready threads found, sleeping...");
 
         x86_64::instructions::interrupts::enable();
         x86_64::instructions::hlt();
@@ -548,26 +734,30 @@ extern "C" {
     ) -> !;
 }
 
-pub fn iret(pid: u32) -> ! {
+pub fn iret(tid: u32) -> ! {
     x86_64::instructions::interrupts::disable();
 
     PROCESSES
-        .current_pid
-        .store(pid, core::sync::atomic::Ordering::Relaxed);
+        .current_tid
+        .store(tid, core::sync::atomic::Ordering::Relaxed);
 
-    let process = PROCESSES.process(pid);
+    let thread = PROCESSES.thread(tid);
 
-    let flags = process.flags;
-    let rip = process.rip;
-    let registers = process.registers;
-    let cs = process.cs;
-    let ds = process.ds;
+    let flags = thread.flags;
+    let rip = thread.rip;
+    let registers = thread.registers;
+    let cs = thread.cs;
+    let ds = thread.ds;
 
-    process.xsave.load();
+    thread.xsave.load();
 
-    process.load_paging();
-
-    drop(process);
+    // kernel threads run in whatever address space is active
+    if let Some(pid) = thread.pid() {
+        drop(thread);
+        PROCESSES.process(pid).load_paging();
+    } else {
+        drop(thread);
+    }
 
     unsafe {
         do_iret(
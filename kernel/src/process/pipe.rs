@@ -0,0 +1,138 @@
+use alloc::{collections::VecDeque, sync::Arc, vec::Vec};
+
+use super::WaitQueue;
+
+/// Number of bytes a pipe buffers before writers have to wait
+pub const PIPE_CAPACITY: usize = 4096;
+
+/// A buffer owned by the kernel with separate ends for reading and writing.
+/// It lives as long as one of its ends is open, independent of the processes using it.
+#[derive(Debug)]
+pub struct Pipe {
+    buffer: VecDeque<u8>,
+    read_ends: usize,
+    write_ends: usize,
+    /// threads waiting for data, or for the last write end to be closed
+    pub readers: WaitQueue,
+    /// threads waiting for room in the buffer, or for the last read end to be closed
+    pub writers: WaitQueue,
+}
+
+/// Writing to a pipe whose read ends are all closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrokenPipe;
+
+/// Creates a pipe and returns its read and write end
+pub fn create() -> (PipeEnd, PipeEnd) {
+    let pipe = Arc::new(spin::Mutex::new(Pipe {
+        buffer: VecDeque::with_capacity(PIPE_CAPACITY),
+        read_ends: 1,
+        write_ends: 1,
+        readers: WaitQueue::new(),
+        writers: WaitQueue::new(),
+    }));
+
+    (
+        PipeEnd {
+            pipe: Arc::clone(&pipe),
+            side: PipeSide::Read,
+        },
+        PipeEnd {
+            pipe,
+            side: PipeSide::Write,
+        },
+    )
+}
+
+impl Pipe {
+    /// Copies up to `len` bytes from the buffer, they stay in it until they are [consumed](Pipe::consume).
+    /// Returns `None` if the buffer is empty but can still be written to, an empty buffer means end of file.
+    pub fn peek(&self, len: usize) -> Option<Vec<u8>> {
+        if self.buffer.is_empty() && self.write_ends > 0 {
+            return None;
+        }
+
+        Some(self.buffer.iter().take(len).copied().collect())
+    }
+
+    /// Removes the first `len` bytes, once the reader received them
+    pub fn consume(&mut self, len: usize) {
+        self.buffer.drain(..len);
+    }
+
+    /// Number of bytes that can be written without waiting
+    pub fn room(&self) -> Result<usize, BrokenPipe> {
+        if self.read_ends == 0 {
+            return Err(BrokenPipe);
+        }
+
+        Ok(PIPE_CAPACITY.saturating_sub(self.buffer.len()))
+    }
+
+    /// Appends bytes that fit into the [`Pipe::room`]
+    pub fn write(&mut self, bytes: &[u8]) {
+        debug_assert!(self.buffer.len() + bytes.len() <= PIPE_CAPACITY);
+        self.buffer.extend(bytes);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeSide {
+    Read,
+    Write,
+}
+
+/// An open end of a [`Pipe`], copies of it keep the end open until all of them are dropped
+#[derive(Debug)]
+pub struct PipeEnd {
+    pipe: Arc<spin::Mutex<Pipe>>,
+    side: PipeSide,
+}
+
+impl PipeEnd {
+    pub fn side(&self) -> PipeSide {
+        self.side
+    }
+
+    pub fn pipe(&self) -> Arc<spin::Mutex<Pipe>> {
+        Arc::clone(&self.pipe)
+    }
+}
+
+impl Clone for PipeEnd {
+    fn clone(&self) -> Self {
+        let mut pipe = self.pipe.try_lock().expect("Failed to lock pipe");
+        match self.side {
+            PipeSide::Read => pipe.read_ends += 1,
+            PipeSide::Write => pipe.write_ends += 1,
+        }
+        drop(pipe);
+
+        PipeEnd {
+            pipe: Arc::clone(&self.pipe),
+            side: self.side,
+        }
+    }
+}
+
+/// Closing the last end of a side wakes the threads waiting on the other side,
+/// readers see the end of file and writers a broken pipe
+impl Drop for PipeEnd {
+    fn drop(&mut self) {
+        let mut pipe = self.pipe.try_lock().expect("Failed to lock pipe");
+        match self.side {
+            PipeSide::Read => {
+                pipe.read_ends -= 1;
+                if pipe.read_ends == 0 {
+                    pipe.writers.wake_deferred();
+                }
+            }
+            PipeSide::Write => {
+                pipe.write_ends -= 1;
+                if pipe.write_ends == 0 {
+                    pipe.readers.wake_deferred();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use alloc::collections::{BTreeSet, VecDeque};
+
+use super::State;
+
+/// Number of priority levels, level 0 is the highest
+pub const PRIORITY_LEVELS: usize = 8;
+/// Number of timer ticks a thread may run before it is preempted
+pub const TIME_SLICE_TICKS: u64 = 10;
+
+pub const NICE_MIN: i8 = -20;
+pub const NICE_MAX: i8 = 19;
+
+/// Maps a nice value to a priority level, lower nice values get higher priorities
+pub fn priority(nice: i8) -> usize {
+    let nice = nice.clamp(NICE_MIN, NICE_MAX);
+    usize::from(nice.abs_diff(NICE_MIN)) * PRIORITY_LEVELS / (NICE_MAX - NICE_MIN + 1) as usize
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SchedulingStats {
+    /// timer ticks spent running
+    pub cpu_ticks: u64,
+    /// how often the thread was switched to
+    pub scheduled: u64,
+    /// how often the thread was switched away from because its time slice ran out
+    pub preempted: u64,
+    /// how often the thread blocked in a syscall
+    pub blocked: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Running {
+    tid: u32,
+    priority: usize,
+    slice_end: u64,
+}
+
+/// Result of [`Scheduler::pick`]
+#[derive(Debug, Clone, Copy)]
+pub struct Decision {
+    pub tid: u32,
+    /// whether `tid` was not running before
+    pub switched: bool,
+    /// the thread that was still ready but had to give up the CPU
+    pub preempted: Option<u32>,
+}
+
+/// Keeps ready threads in one round-robin queue per priority level.
+/// Sleeping threads are ordered by the tick they wake up at, other blocked threads
+/// are in the [`super::WaitQueue`] of what they wait for and only come back once they are woken.
+pub struct Scheduler {
+    run_queues: [VecDeque<u32>; PRIORITY_LEVELS],
+    sleeping: BTreeSet<(u64, u32)>,
+    running: Option<Running>,
+}
+
+pub static SCHEDULER: spin::Mutex<Scheduler> = spin::Mutex::new(Scheduler::new());
+
+pub fn scheduler() -> spin::MutexGuard<'static, Scheduler> {
+    SCHEDULER.try_lock().expect("Failed to lock scheduler")
+}
+
+impl Scheduler {
+    const fn new() -> Self {
+        Scheduler {
+            run_queues: [const { VecDeque::new() }; PRIORITY_LEVELS],
+            sleeping: BTreeSet::new(),
+            running: None,
+        }
+    }
+
+    fn remove_ready(&mut self, tid: u32) {
+        for queue in &mut self.run_queues {
+            queue.retain(|&p| p != tid);
+        }
+        if self.running.is_some_and(|running| running.tid == tid) {
+            self.running = None;
+        }
+    }
+
+    fn insert(&mut self, tid: u32, priority: usize, state: State) {
+        match state {
+            State::Ready => self.run_queues[priority].push_back(tid),
+            State::Sleeping(target) => {
+                self.sleeping.insert((target, tid));
+            }
+            State::WaitingForStream { .. }
+            | State::WaitingForRoom { .. }
+            | State::WaitingForChild { .. }
+            | State::WaitingForThread { .. }
+            | State::Stopped
+            | State::Idle
+            | State::Terminated(_) => {}
+        }
+    }
+
+    fn remove(&mut self, tid: u32, state: State) {
+        match state {
+            State::Ready => self.remove_ready(tid),
+            State::Sleeping(target) => {
+                self.sleeping.remove(&(target, tid));
+            }
+            State::WaitingForStream { .. }
+            | State::WaitingForRoom { .. }
+            | State::WaitingForChild { .. }
+            | State::WaitingForThread { .. }
+            | State::Stopped
+            | State::Idle
+            | State::Terminated(_) => {}
+        }
+    }
+
+    /// Starts scheduling a new thread
+    pub fn add(&mut self, tid: u32, priority: usize, state: State) {
+        self.insert(tid, priority, state);
+    }
+
+    /// Moves `tid` between the queues when its state changes from `old` to `new`
+    pub fn transition(&mut self, tid: u32, priority: usize, old: State, new: State) {
+        // a ready thread keeps its place in the queue or the CPU
+        if old == State::Ready && new == State::Ready {
+            return;
+        }
+
+        self.remove(tid, old);
+        self.insert(tid, priority, new);
+    }
+
+    /// Returns the sleeping threads whose wake up tick has been reached,
+    /// they still have to be set to [`State::Ready`]
+    pub fn expired_sleepers(&self, now: u64) -> alloc::vec::Vec<u32> {
+        self.sleeping
+            .iter()
+            .take_while(|(target, _)| *target <= now)
+            .map(|&(_, tid)| tid)
+            .collect()
+    }
+
+    /// The thread holding the CPU, if any
+    pub fn running(&self) -> Option<u32> {
+        self.running.map(|running| running.tid)
+    }
+
+    /// Whether the running thread has to give up the CPU at tick `now`
+    pub fn should_preempt(&self, now: u64) -> bool {
+        self.running.is_some_and(|running| {
+            now >= running.slice_end
+                || self.run_queues[..running.priority]
+                    .iter()
+                    .any(|queue| !queue.is_empty())
+        })
+    }
+
+    /// Chooses the thread to run next.
+    /// The running thread keeps the CPU until its time slice is used up or a thread
+    /// with a higher priority becomes ready, then the highest priority queue is served round-robin.
+    /// Threads in lower priority queues only run when all higher queues are empty.
+    pub fn pick(&mut self, now: u64) -> Option<Decision> {
+        let previous = self.running();
+        let mut preempted = None;
+
+        if let Some(running) = self.running {
+            if !self.should_preempt(now) {
+                return Some(Decision {
+                    tid: running.tid,
+                    switched: false,
+                    preempted: None,
+                });
+            }
+
+            self.running = None;
+            self.run_queues[running.priority].push_back(running.tid);
+            preempted = Some(running.tid);
+        }
+
+        let (priority, tid) = self
+            .run_queues
+            .iter_mut()
+            .enumerate()
+            .find_map(|(priority, queue)| Some((priority, queue.pop_front()?)))?;
+
+        self.running = Some(Running {
+            tid,
+            priority,
+            slice_end: now + TIME_SLICE_TICKS,
+        });
+
+        // the preempted thread got the CPU again because nothing else is ready
+        let preempted = preempted.filter(|&p| p != tid);
+
+        Some(Decision {
+            tid,
+            switched: previous != Some(tid),
+            preempted,
+        })
+    }
+}
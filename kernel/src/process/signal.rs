@@ -0,0 +1,191 @@
+use crate::syscall::generated::{self, signal_mask_t, signal_t};
+
+/// What happens to a process when it receives a signal it does not handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DefaultAction {
+    Terminate,
+    Ignore,
+    Stop,
+    /// continues a stopped process, otherwise the signal is ignored
+    Continue,
+}
+
+pub fn default_action(signal: signal_t) -> DefaultAction {
+    match signal {
+        generated::SIGCHLD => DefaultAction::Ignore,
+        generated::SIGSTOP | generated::SIGTSTP => DefaultAction::Stop,
+        generated::SIGCONT => DefaultAction::Continue,
+        _ => DefaultAction::Terminate,
+    }
+}
+
+/// Exit status of a process that was terminated by `signal`
+pub fn exit_status(signal: signal_t) -> u32 {
+    128 + signal
+}
+
+pub fn is_valid(signal: signal_t) -> bool {
+    (1..=generated::SIGNAL_MAX).contains(&signal)
+}
+
+/// Signals whose action cannot be changed and that cannot be blocked
+pub fn is_fixed(signal: signal_t) -> bool {
+    matches!(signal, generated::SIGKILL | generated::SIGSTOP)
+}
+
+/// The mask with only `signal` set
+pub fn mask(signal: signal_t) -> signal_mask_t {
+    1 << signal
+}
+
+/// Signals that can be blocked
+const BLOCKABLE: signal_mask_t = !(1 | (1 << generated::SIGKILL) | (1 << generated::SIGSTOP));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Default,
+    Ignore,
+    Handler {
+        handler: u64,
+        /// signals blocked in addition to the signal while the handler runs
+        mask: signal_mask_t,
+        /// where the handler returns to
+        restorer: u64,
+    },
+}
+
+impl Action {
+    pub fn from_handler(handler: u64, mask: signal_mask_t, restorer: u64) -> Self {
+        match handler {
+            generated::SIGNAL_HANDLER_DEFAULT => Action::Default,
+            generated::SIGNAL_HANDLER_IGNORE => Action::Ignore,
+            handler => Action::Handler {
+                handler,
+                mask,
+                restorer,
+            },
+        }
+    }
+
+    pub fn handler(self) -> u64 {
+        match self {
+            Action::Default => generated::SIGNAL_HANDLER_DEFAULT,
+            Action::Ignore => generated::SIGNAL_HANDLER_IGNORE,
+            Action::Handler { handler, .. } => handler,
+        }
+    }
+
+    /// Whether receiving `signal` with this action has no effect
+    fn ignores(self, signal: signal_t) -> bool {
+        match self {
+            Action::Ignore => true,
+            Action::Default => matches!(
+                default_action(signal),
+                DefaultAction::Ignore | DefaultAction::Continue
+            ),
+            Action::Handler { .. } => false,
+        }
+    }
+}
+
+/// The signal state of a process, shared by its threads
+#[derive(Debug, Clone)]
+pub struct Signals {
+    pending: signal_mask_t,
+    blocked: signal_mask_t,
+    actions: [Action; generated::SIGNAL_MAX as usize + 1],
+    /// the process was stopped by a signal and its threads do not run until it receives `SIGCONT`
+    pub stopped: bool,
+}
+
+impl Default for Signals {
+    fn default() -> Self {
+        Signals {
+            pending: 0,
+            blocked: 0,
+            actions: [Action::Default; generated::SIGNAL_MAX as usize + 1],
+            stopped: false,
+        }
+    }
+}
+
+impl Signals {
+    /// The state of a forked process, which inherits the actions and the blocked signals but none of the pending ones
+    pub fn fork(&self) -> Self {
+        Signals {
+            pending: 0,
+            stopped: false,
+            ..self.clone()
+        }
+    }
+
+    /// Handlers are not part of the new program, ignored signals stay ignored
+    pub fn execve(&mut self) {
+        for action in &mut self.actions {
+            if let Action::Handler { .. } = action {
+                *action = Action::Default;
+            }
+        }
+    }
+
+    pub fn action(&self, signal: signal_t) -> Action {
+        self.actions[signal as usize]
+    }
+
+    /// Replaces the action for `signal` and returns the previous one.
+    /// Ignoring a signal discards it if it is pending.
+    pub fn set_action(&mut self, signal: signal_t, action: Action) -> Action {
+        if action.ignores(signal) {
+            self.pending &= !mask(signal);
+        }
+        core::mem::replace(&mut self.actions[signal as usize], action)
+    }
+
+    pub fn blocked(&self) -> signal_mask_t {
+        self.blocked
+    }
+
+    /// Sets the blocked signals, `SIGKILL` and `SIGSTOP` are never blocked
+    pub fn set_blocked(&mut self, blocked: signal_mask_t) {
+        self.blocked = blocked & BLOCKABLE;
+    }
+
+    /// Whether `signal` would terminate or stop the process, because it is left to its default action
+    pub fn is_fatal(&self, signal: signal_t) -> bool {
+        self.action(signal) == Action::Default
+            && matches!(
+                default_action(signal),
+                DefaultAction::Terminate | DefaultAction::Stop
+            )
+    }
+
+    /// Marks `signal` as pending unless it would be ignored.
+    /// Returns whether the process has to be interrupted to deliver it.
+    pub fn raise(&mut self, signal: signal_t) -> bool {
+        if self.action(signal).ignores(signal) {
+            return false;
+        }
+
+        self.pending |= mask(signal);
+        self.blocked & mask(signal) == 0
+    }
+
+    /// Makes sure `signal` is acted on with its default action, for faults that cannot be continued
+    pub fn force(&mut self, signal: signal_t) {
+        self.actions[signal as usize] = Action::Default;
+        self.blocked &= !mask(signal);
+        self.pending |= mask(signal);
+    }
+
+    /// Takes the lowest pending signal that is not blocked
+    pub fn take_deliverable(&mut self) -> Option<signal_t> {
+        let deliverable = self.pending & !self.blocked;
+        if deliverable == 0 {
+            return None;
+        }
+
+        let signal = deliverable.trailing_zeros();
+        self.pending &= !mask(signal);
+        Some(signal)
+    }
+}
@@ -0,0 +1,225 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use x86_64::instructions::segmentation::Segment;
+
+use super::{scheduler, State, WaitQueue, PID_FACTORY, PROCESSES};
+
+/// Size of the stack of a kernel thread
+const KERNEL_THREAD_STACK_SIZE: usize = 64 * 1024;
+
+/// A thread of execution with its own registers, user threads share the address space
+/// and file descriptors of their process.
+pub struct Thread {
+    tid: u32,
+    /// the process the thread belongs to, `None` for kernel threads
+    pid: Option<u32>,
+    state: State,
+    /// lower values give the thread a higher priority, between [`scheduler::NICE_MIN`] and [`scheduler::NICE_MAX`]
+    nice: i8,
+    pub stats: scheduler::SchedulingStats,
+    pub cs: x86_64::structures::gdt::SegmentSelector,
+    pub ds: x86_64::structures::gdt::SegmentSelector,
+    pub flags: u64,
+    pub rip: u64,
+    pub registers: crate::idt::GPRegisters,
+    pub xsave: xsave::XSave,
+    /// floating point state of the code interrupted by signal handlers, restored when they return
+    pub signal_xsave: Vec<xsave::XSave>,
+    /// the stack of a kernel thread, user threads have their stack in the address space
+    kernel_stack: Option<Box<[u8]>>,
+    /// threads waiting to join this thread
+    pub joiners: WaitQueue,
+}
+
+impl Thread {
+    pub fn user(
+        tid: u32,
+        pid: u32,
+        cs: x86_64::structures::gdt::SegmentSelector,
+        ds: x86_64::structures::gdt::SegmentSelector,
+        flags: u64,
+        rip: u64,
+        rsp: u64,
+    ) -> Self {
+        Thread {
+            tid,
+            pid: Some(pid),
+            state: State::Ready,
+            nice: 0,
+            stats: scheduler::SchedulingStats::default(),
+            cs,
+            ds,
+            flags,
+            rip,
+            registers: crate::idt::GPRegisters {
+                rsp,
+                ..Default::default()
+            },
+            xsave: xsave::XSave::default(),
+            signal_xsave: Vec::new(),
+            kernel_stack: None,
+            joiners: WaitQueue::new(),
+        }
+    }
+
+    /// Creates a thread that runs `entry` in ring 0 with interrupts enabled.
+    /// Kernel threads can be preempted at any time, so they have to disable interrupts
+    /// while they access state shared with interrupt handlers or the scheduler.
+    pub fn kernel(entry: fn()) -> Self {
+        let stack = vec![0u8; KERNEL_THREAD_STACK_SIZE].into_boxed_slice();
+        // aligned as if `kernel_thread_entry` was called
+        let rsp = ((stack.as_ptr() as u64 + stack.len() as u64) & !0xF) - 8;
+
+        Thread {
+            tid: PID_FACTORY.next_pid(),
+            pid: None,
+            state: State::Ready,
+            nice: 0,
+            stats: scheduler::SchedulingStats::default(),
+            cs: x86_64::instructions::segmentation::CS::get_reg(),
+            ds: x86_64::instructions::segmentation::SS::get_reg(),
+            flags: 0x202,
+            rip: kernel_thread_entry as extern "C" fn(usize) -> ! as usize as u64,
+            registers: crate::idt::GPRegisters {
+                rsp,
+                rdi: entry as usize as u64,
+                ..Default::default()
+            },
+            xsave: xsave::XSave::default(),
+            signal_xsave: Vec::new(),
+            kernel_stack: Some(stack),
+            joiners: WaitQueue::new(),
+        }
+    }
+
+    /// Creates a copy of the thread for the forked process `pid`, its main thread has the same id
+    pub fn fork(&self, pid: u32) -> Self {
+        Thread {
+            tid: pid,
+            pid: Some(pid),
+            state: self.state,
+            nice: self.nice,
+            stats: scheduler::SchedulingStats::default(),
+            cs: self.cs,
+            ds: self.ds,
+            flags: self.flags,
+            rip: self.rip,
+            registers: self.registers,
+            xsave: self.xsave,
+            signal_xsave: self.signal_xsave.clone(),
+            kernel_stack: None,
+            joiners: WaitQueue::new(),
+        }
+    }
+
+    /// Creates a new thread in the same process that starts at `rip` with the stack at `rsp`
+    pub fn sibling(&self, rip: u64, rsp: u64) -> Self {
+        Thread {
+            tid: PID_FACTORY.next_pid(),
+            pid: self.pid,
+            state: State::Ready,
+            nice: self.nice,
+            stats: scheduler::SchedulingStats::default(),
+            cs: self.cs,
+            ds: self.ds,
+            flags: self.flags,
+            rip,
+            registers: crate::idt::GPRegisters {
+                rsp,
+                ..Default::default()
+            },
+            xsave: xsave::XSave::default(),
+            signal_xsave: Vec::new(),
+            kernel_stack: None,
+            joiners: WaitQueue::new(),
+        }
+    }
+
+    pub fn tid(&self) -> u32 {
+        self.tid
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.pid
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Changes the state and moves the thread to the matching scheduler queue.
+    /// A terminated thread stays terminated.
+    pub fn set_state(&mut self, state: State) {
+        if matches!(self.state, State::Terminated(_)) {
+            return;
+        }
+
+        if self.state == State::Ready && !matches!(state, State::Ready | State::Terminated(_)) {
+            self.stats.blocked += 1;
+        }
+
+        scheduler::scheduler().transition(self.tid, self.priority(), self.state, state);
+        self.state = state;
+    }
+
+    pub fn nice(&self) -> i8 {
+        self.nice
+    }
+
+    /// Sets the nice value, the new priority applies the next time the thread is queued
+    pub fn set_nice(&mut self, nice: i8) {
+        self.nice = nice.clamp(scheduler::NICE_MIN, scheduler::NICE_MAX);
+    }
+
+    pub fn priority(&self) -> usize {
+        scheduler::priority(self.nice)
+    }
+}
+
+/// Starts a kernel thread running `entry`, it terminates when `entry` returns
+pub fn spawn_kernel_thread(entry: fn()) -> u32 {
+    let thread = Thread::kernel(entry);
+    let tid = thread.tid;
+
+    log::debug!("spawning kernel thread {tid}");
+
+    PROCESSES.add_thread(thread);
+
+    tid
+}
+
+/// Calls `entry`, which is passed as an address because `fn()` is not FFI-safe
+extern "C" fn kernel_thread_entry(entry: usize) -> ! {
+    let entry = unsafe { core::mem::transmute::<usize, fn()>(entry) };
+    entry();
+    exit_kernel_thread()
+}
+
+extern "C" fn schedule_on_kernel_stack(tid: u32) -> ! {
+    // the exited thread no longer runs on its stack
+    drop(PROCESSES.thread_mut(tid).kernel_stack.take());
+    super::schedule()
+}
+
+fn exit_kernel_thread() -> ! {
+    x86_64::instructions::interrupts::disable();
+
+    let tid = PROCESSES
+        .current_tid
+        .swap(0, core::sync::atomic::Ordering::Relaxed);
+    PROCESSES.thread_mut(tid).set_state(State::Terminated(0));
+
+    log::debug!("kernel thread {tid} exited");
+
+    // the stack of the thread is freed, so the scheduler has to run on the kernel stack
+    unsafe {
+        core::arch::asm!(
+            "mov rsp, {stack}",
+            "call {schedule}",
+            stack = in(reg) crate::KERNEL_STACK_POINTER(),
+            schedule = sym schedule_on_kernel_stack,
+            in("edi") tid,
+            options(noreturn)
+        );
+    }
+}
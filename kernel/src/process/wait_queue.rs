@@ -0,0 +1,105 @@
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use alloc::{collections::VecDeque, vec::Vec};
+
+use super::{State, PROCESSES};
+
+/// Threads that are blocked until an event happens, like data arriving in a stream or a child exiting.
+/// The queue only holds thread ids, the state of a thread records what it is waiting for.
+/// Threads that stopped waiting in the meantime, e.g. because they were terminated, are skipped when they are woken.
+#[derive(Debug, Default)]
+pub struct WaitQueue {
+    waiters: VecDeque<u32>,
+}
+
+/// Waiters are not inherited, the copy of a queue is empty
+impl Clone for WaitQueue {
+    fn clone(&self) -> Self {
+        WaitQueue::new()
+    }
+}
+
+impl WaitQueue {
+    pub const fn new() -> Self {
+        WaitQueue {
+            waiters: VecDeque::new(),
+        }
+    }
+
+    /// Adds `tid` to the queue, the caller has to block the thread
+    pub fn push(&mut self, tid: u32) {
+        if !self.waiters.contains(&tid) {
+            self.waiters.push_back(tid);
+        }
+    }
+
+    /// Removes all waiters in the order they started waiting.
+    /// They have to be woken once the queue is no longer borrowed, each of them is woken exactly once.
+    pub fn take(&mut self) -> Vec<u32> {
+        self.waiters.drain(..).collect()
+    }
+
+    /// Removes all waiters and leaves waking them to a kernel thread,
+    /// for places that may run while the processes are borrowed, like dropping a file descriptor
+    pub fn wake_deferred(&mut self) {
+        DEFERRED
+            .try_lock()
+            .expect("Failed to lock deferred waiters")
+            .extend(self.waiters.drain(..));
+    }
+}
+
+/// Waiters removed by [`WaitQueue::wake_deferred`] that still have to be woken
+static DEFERRED: spin::Mutex<Vec<u32>> = spin::Mutex::new(Vec::new());
+
+/// The kernel thread that wakes the deferred waiters, 0 until they are woken for the first time
+static WAKER: AtomicU32 = AtomicU32::new(0);
+
+/// Makes the kernel thread that wakes the waiters of [`WaitQueue::wake_deferred`] ready if there are any,
+/// it is spawned the first time. Called by the scheduler, which must not resume other syscalls itself.
+pub fn wake_deferred_waker() {
+    if DEFERRED
+        .try_lock()
+        .expect("Failed to lock deferred waiters")
+        .is_empty()
+    {
+        return;
+    }
+
+    match WAKER.load(Ordering::Relaxed) {
+        0 => {
+            let tid = super::thread::spawn_kernel_thread(deferred_waker);
+            WAKER.store(tid, Ordering::Relaxed);
+        }
+        tid => {
+            let mut waker = PROCESSES.thread_mut(tid);
+            if waker.state() == State::Idle {
+                waker.set_state(State::Ready);
+            }
+        }
+    }
+}
+
+/// Entry of the kernel thread that wakes the deferred waiters, it is idle while there are none
+fn deferred_waker() {
+    loop {
+        // resuming syscalls borrows the processes, which interrupt handlers do as well
+        x86_64::instructions::interrupts::disable();
+
+        let waiters = core::mem::take(
+            &mut *DEFERRED
+                .try_lock()
+                .expect("Failed to lock deferred waiters"),
+        );
+
+        if waiters.is_empty() {
+            let tid = WAKER.load(Ordering::Relaxed);
+            PROCESSES.thread_mut(tid).set_state(State::Idle);
+            // the next interrupt switches to another thread, this one continues here once it is ready again
+            x86_64::instructions::interrupts::enable_and_hlt();
+            continue;
+        }
+
+        super::wake(waiters);
+    }
+}
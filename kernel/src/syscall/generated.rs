@@ -218,18 +218,47 @@ const _: () = {
 };
 pub type fd_t = i32;
 pub const FD_STDIN: fd_t = 0;
+pub type auxv_key_t = u64;
+pub const AT_NULL: auxv_key_t = 0;
+pub const AT_PHDR: auxv_key_t = 3;
+pub const AT_PHENT: auxv_key_t = 4;
+pub const AT_PHNUM: auxv_key_t = 5;
+pub const AT_PAGESZ: auxv_key_t = 6;
+pub const AT_ENTRY: auxv_key_t = 9;
+pub const AT_RANDOM: auxv_key_t = 25;
+pub const AT_FRAMEBUFFER_WIDTH: auxv_key_t = 0x1000;
+pub const AT_FRAMEBUFFER_HEIGHT: auxv_key_t = 0x1001;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct auxv_t {
+    pub key: auxv_key_t,
+    pub value: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of auxv_t"][::core::mem::size_of::<auxv_t>() - 16usize];
+    ["Alignment of auxv_t"][::core::mem::align_of::<auxv_t>() - 8usize];
+    ["Offset of field: auxv_t::key"][::core::mem::offset_of!(auxv_t, key) - 0usize];
+    ["Offset of field: auxv_t::value"][::core::mem::offset_of!(auxv_t, value) - 8usize];
+};
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct entry_t {
     pub argc: u32,
     pub argv: *mut string_const_t,
+    pub envc: u32,
+    pub envp: *mut string_const_t,
+    pub auxv: *mut auxv_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of entry_t"][::core::mem::size_of::<entry_t>() - 16usize];
+    ["Size of entry_t"][::core::mem::size_of::<entry_t>() - 40usize];
     ["Alignment of entry_t"][::core::mem::align_of::<entry_t>() - 8usize];
     ["Offset of field: entry_t::argc"][::core::mem::offset_of!(entry_t, argc) - 0usize];
     ["Offset of field: entry_t::argv"][::core::mem::offset_of!(entry_t, argv) - 8usize];
+    ["Offset of field: entry_t::envc"][::core::mem::offset_of!(entry_t, envc) - 16usize];
+    ["Offset of field: entry_t::envp"][::core::mem::offset_of!(entry_t, envp) - 24usize];
+    ["Offset of field: entry_t::auxv"][::core::mem::offset_of!(entry_t, auxv) - 32usize];
 };
 pub const syscall_id_t_SYSCALL_PRINT: syscall_id_t = 0;
 pub const syscall_id_t_SYSCALL_SLEEP: syscall_id_t = 1;
@@ -245,7 +274,45 @@ pub const syscall_id_t_SYSCALL_EXECVE: syscall_id_t = 10;
 pub const syscall_id_t_SYSCALL_MAP_FRAMEBUFFER: syscall_id_t = 11;
 pub const syscall_id_t_SYSCALL_WRITE: syscall_id_t = 12;
 pub const syscall_id_t_SYSCALL_WAITPID: syscall_id_t = 13;
+pub const syscall_id_t_SYSCALL_MPROTECT: syscall_id_t = 14;
+pub const syscall_id_t_SYSCALL_BRK: syscall_id_t = 15;
+pub const syscall_id_t_SYSCALL_NICE: syscall_id_t = 16;
+pub const syscall_id_t_SYSCALL_THREAD_CREATE: syscall_id_t = 17;
+pub const syscall_id_t_SYSCALL_THREAD_EXIT: syscall_id_t = 18;
+pub const syscall_id_t_SYSCALL_THREAD_JOIN: syscall_id_t = 19;
+pub const syscall_id_t_SYSCALL_PIPE: syscall_id_t = 20;
+pub const syscall_id_t_SYSCALL_DUP: syscall_id_t = 21;
+pub const syscall_id_t_SYSCALL_DUP2: syscall_id_t = 22;
+pub const syscall_id_t_SYSCALL_KILL: syscall_id_t = 23;
+pub const syscall_id_t_SYSCALL_SIGACTION: syscall_id_t = 24;
+pub const syscall_id_t_SYSCALL_SIGPROCMASK: syscall_id_t = 25;
+pub const syscall_id_t_SYSCALL_SIGRETURN: syscall_id_t = 26;
+pub const syscall_id_t_SYSCALL_GETPID: syscall_id_t = 27;
+pub const syscall_id_t_SYSCALL_GETPPID: syscall_id_t = 28;
+pub const syscall_id_t_SYSCALL_CHDIR: syscall_id_t = 29;
+pub const syscall_id_t_SYSCALL_GETCWD: syscall_id_t = 30;
+pub const syscall_id_t_SYSCALL_MOUNT: syscall_id_t = 31;
+pub const syscall_id_t_SYSCALL_UMOUNT: syscall_id_t = 32;
+pub const syscall_id_t_SYSCALL_UNLINK: syscall_id_t = 33;
+pub const syscall_id_t_SYSCALL_MKDIR: syscall_id_t = 34;
+pub const syscall_id_t_SYSCALL_RMDIR: syscall_id_t = 35;
+pub const syscall_id_t_SYSCALL_RENAME: syscall_id_t = 36;
+pub const syscall_id_t_SYSCALL_STAT: syscall_id_t = 37;
+pub const syscall_id_t_SYSCALL_FSTAT: syscall_id_t = 38;
+pub const syscall_id_t_SYSCALL_LSEEK: syscall_id_t = 39;
+pub const syscall_id_t_SYSCALL_PREAD: syscall_id_t = 40;
+pub const syscall_id_t_SYSCALL_PWRITE: syscall_id_t = 41;
+pub const syscall_id_t_SYSCALL_IOCTL: syscall_id_t = 42;
+pub const syscall_id_t_SYSCALL_GETDENTS: syscall_id_t = 43;
 pub type syscall_id_t = ::core::ffi::c_uint;
+pub type syscall_error_t = u32;
+pub const SYSCALL_ERROR_NONE: syscall_error_t = 0;
+pub const SYSCALL_ERROR_FAULT: syscall_error_t = 1;
+pub const SYSCALL_ERROR_UNKNOWN_SYSCALL: syscall_error_t = 2;
+pub const SYSCALL_ERROR_NOT_SUPPORTED: syscall_error_t = 3;
+pub const SYSCALL_ERROR_INTERRUPTED: syscall_error_t = 4;
+pub const SYSCALL_ERROR_TOO_LARGE: syscall_error_t = 5;
+pub const SYSCALL_ERROR_NO_MEMORY: syscall_error_t = 6;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_print_t {
@@ -388,6 +455,7 @@ const _: () = {
         [::core::mem::offset_of!(syscall_read_t, return_value) - 24usize];
 };
 pub type pid_t = u32;
+pub const SYSCALL_FD_FLAG_CLOSE_ON_EXEC: u32 = 1;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_fork_return_t {
@@ -413,9 +481,19 @@ const _: () = {
     ["Offset of field: syscall_fork_t::return_value"]
         [::core::mem::offset_of!(syscall_fork_t, return_value) - 0usize];
 };
+pub type syscall_open_mode_t = u32;
+pub const SYSCALL_OPEN_MODE_READ: syscall_open_mode_t = 1;
+pub const SYSCALL_OPEN_MODE_WRITE: syscall_open_mode_t = 2;
+pub const SYSCALL_OPEN_MODE_READ_WRITE: syscall_open_mode_t = 3;
+pub const SYSCALL_OPEN_MODE_CREATE: syscall_open_mode_t = 4;
+pub const SYSCALL_OPEN_MODE_TRUNCATE: syscall_open_mode_t = 8;
+pub const SYSCALL_OPEN_MODE_APPEND: syscall_open_mode_t = 16;
 pub type syscall_open_error_t = u32;
 pub const SYSCALL_OPEN_ERROR_NONE: syscall_open_error_t = 0;
 pub const SYSCALL_OPEN_ERROR_NOT_FOUND: syscall_open_error_t = 1;
+pub const SYSCALL_OPEN_ERROR_IS_A_DIRECTORY: syscall_open_error_t = 2;
+pub const SYSCALL_OPEN_ERROR_NOT_A_DIRECTORY: syscall_open_error_t = 3;
+pub const SYSCALL_OPEN_ERROR_NOT_SUPPORTED: syscall_open_error_t = 4;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_open_return_t {
@@ -436,16 +514,22 @@ const _: () = {
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_open_t {
     pub path: string_const_t,
+    pub flags: u32,
+    pub mode: syscall_open_mode_t,
     pub return_value: syscall_open_return_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of syscall_open_t"][::core::mem::size_of::<syscall_open_t>() - 24usize];
+    ["Size of syscall_open_t"][::core::mem::size_of::<syscall_open_t>() - 32usize];
     ["Alignment of syscall_open_t"][::core::mem::align_of::<syscall_open_t>() - 8usize];
     ["Offset of field: syscall_open_t::path"]
         [::core::mem::offset_of!(syscall_open_t, path) - 0usize];
+    ["Offset of field: syscall_open_t::flags"]
+        [::core::mem::offset_of!(syscall_open_t, flags) - 16usize];
+    ["Offset of field: syscall_open_t::mode"]
+        [::core::mem::offset_of!(syscall_open_t, mode) - 20usize];
     ["Offset of field: syscall_open_t::return_value"]
-        [::core::mem::offset_of!(syscall_open_t, return_value) - 16usize];
+        [::core::mem::offset_of!(syscall_open_t, return_value) - 24usize];
 };
 pub type syscall_close_error_t = u32;
 pub const SYSCALL_CLOSE_ERROR_NONE: syscall_close_error_t = 0;
@@ -477,8 +561,19 @@ const _: () = {
     ["Offset of field: syscall_close_t::return_value"]
         [::core::mem::offset_of!(syscall_close_t, return_value) - 4usize];
 };
+pub const SYSCALL_MMAP_PROT_NONE: u32 = 0;
+pub const SYSCALL_MMAP_PROT_READ: u32 = 1;
+pub const SYSCALL_MMAP_PROT_WRITE: u32 = 2;
+pub const SYSCALL_MMAP_PROT_EXEC: u32 = 4;
+pub const SYSCALL_MMAP_FLAG_FIXED: u32 = 1;
+pub const SYSCALL_MMAP_FLAG_ANONYMOUS: u32 = 2;
+pub const SYSCALL_MMAP_FLAG_SHARED: u32 = 4;
 pub type syscall_mmap_error_t = u32;
 pub const SYSCALL_MMAP_ERROR_NONE: syscall_mmap_error_t = 0;
+pub const SYSCALL_MMAP_ERROR_INVALID_SIZE: syscall_mmap_error_t = 1;
+pub const SYSCALL_MMAP_ERROR_INVALID_ADDR: syscall_mmap_error_t = 2;
+pub const SYSCALL_MMAP_ERROR_INVALID_FLAGS: syscall_mmap_error_t = 3;
+pub const SYSCALL_MMAP_ERROR_NO_MEMORY: syscall_mmap_error_t = 4;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_mmap_return_t {
@@ -498,21 +593,31 @@ const _: () = {
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_mmap_t {
-    pub size: u32,
+    pub addr: *mut ::core::ffi::c_void,
+    pub size: u64,
+    pub prot: u32,
+    pub flags: u32,
     pub return_value: syscall_mmap_return_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of syscall_mmap_t"][::core::mem::size_of::<syscall_mmap_t>() - 24usize];
+    ["Size of syscall_mmap_t"][::core::mem::size_of::<syscall_mmap_t>() - 40usize];
     ["Alignment of syscall_mmap_t"][::core::mem::align_of::<syscall_mmap_t>() - 8usize];
+    ["Offset of field: syscall_mmap_t::addr"]
+        [::core::mem::offset_of!(syscall_mmap_t, addr) - 0usize];
     ["Offset of field: syscall_mmap_t::size"]
-        [::core::mem::offset_of!(syscall_mmap_t, size) - 0usize];
+        [::core::mem::offset_of!(syscall_mmap_t, size) - 8usize];
+    ["Offset of field: syscall_mmap_t::prot"]
+        [::core::mem::offset_of!(syscall_mmap_t, prot) - 16usize];
+    ["Offset of field: syscall_mmap_t::flags"]
+        [::core::mem::offset_of!(syscall_mmap_t, flags) - 20usize];
     ["Offset of field: syscall_mmap_t::return_value"]
-        [::core::mem::offset_of!(syscall_mmap_t, return_value) - 8usize];
+        [::core::mem::offset_of!(syscall_mmap_t, return_value) - 24usize];
 };
 pub type syscall_munmap_error_t = u32;
 pub const SYSCALL_MUNMAP_ERROR_NONE: syscall_munmap_error_t = 0;
 pub const SYSCALL_MUNMAP_ERROR_INVALID_ADDR: syscall_munmap_error_t = 1;
+pub const SYSCALL_MUNMAP_ERROR_INVALID_SIZE: syscall_munmap_error_t = 2;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_munmap_return_t {
@@ -530,23 +635,225 @@ const _: () = {
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_munmap_t {
     pub addr: *mut ::core::ffi::c_void,
-    pub size: u32,
+    pub size: u64,
     pub return_value: syscall_munmap_return_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of syscall_munmap_t"][::core::mem::size_of::<syscall_munmap_t>() - 16usize];
+    ["Size of syscall_munmap_t"][::core::mem::size_of::<syscall_munmap_t>() - 24usize];
     ["Alignment of syscall_munmap_t"][::core::mem::align_of::<syscall_munmap_t>() - 8usize];
     ["Offset of field: syscall_munmap_t::addr"]
         [::core::mem::offset_of!(syscall_munmap_t, addr) - 0usize];
     ["Offset of field: syscall_munmap_t::size"]
         [::core::mem::offset_of!(syscall_munmap_t, size) - 8usize];
     ["Offset of field: syscall_munmap_t::return_value"]
-        [::core::mem::offset_of!(syscall_munmap_t, return_value) - 12usize];
+        [::core::mem::offset_of!(syscall_munmap_t, return_value) - 16usize];
+};
+pub type syscall_mprotect_error_t = u32;
+pub const SYSCALL_MPROTECT_ERROR_NONE: syscall_mprotect_error_t = 0;
+pub const SYSCALL_MPROTECT_ERROR_INVALID_ADDR: syscall_mprotect_error_t = 1;
+pub const SYSCALL_MPROTECT_ERROR_INVALID_SIZE: syscall_mprotect_error_t = 2;
+pub const SYSCALL_MPROTECT_ERROR_NOT_MAPPED: syscall_mprotect_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_mprotect_return_t {
+    pub error: syscall_mprotect_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_mprotect_return_t"]
+        [::core::mem::size_of::<syscall_mprotect_return_t>() - 4usize];
+    ["Alignment of syscall_mprotect_return_t"]
+        [::core::mem::align_of::<syscall_mprotect_return_t>() - 4usize];
+    ["Offset of field: syscall_mprotect_return_t::error"]
+        [::core::mem::offset_of!(syscall_mprotect_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_mprotect_t {
+    pub addr: *mut ::core::ffi::c_void,
+    pub size: u64,
+    pub prot: u32,
+    pub return_value: syscall_mprotect_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_mprotect_t"][::core::mem::size_of::<syscall_mprotect_t>() - 24usize];
+    ["Alignment of syscall_mprotect_t"][::core::mem::align_of::<syscall_mprotect_t>() - 8usize];
+    ["Offset of field: syscall_mprotect_t::addr"]
+        [::core::mem::offset_of!(syscall_mprotect_t, addr) - 0usize];
+    ["Offset of field: syscall_mprotect_t::size"]
+        [::core::mem::offset_of!(syscall_mprotect_t, size) - 8usize];
+    ["Offset of field: syscall_mprotect_t::prot"]
+        [::core::mem::offset_of!(syscall_mprotect_t, prot) - 16usize];
+    ["Offset of field: syscall_mprotect_t::return_value"]
+        [::core::mem::offset_of!(syscall_mprotect_t, return_value) - 20usize];
+};
+pub type syscall_brk_error_t = u32;
+pub const SYSCALL_BRK_ERROR_NONE: syscall_brk_error_t = 0;
+pub const SYSCALL_BRK_ERROR_INVALID_ADDR: syscall_brk_error_t = 1;
+pub const SYSCALL_BRK_ERROR_NO_MEMORY: syscall_brk_error_t = 2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_brk_return_t {
+    pub addr: *mut ::core::ffi::c_void,
+    pub error: syscall_brk_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_brk_return_t"][::core::mem::size_of::<syscall_brk_return_t>() - 16usize];
+    ["Alignment of syscall_brk_return_t"][::core::mem::align_of::<syscall_brk_return_t>() - 8usize];
+    ["Offset of field: syscall_brk_return_t::addr"]
+        [::core::mem::offset_of!(syscall_brk_return_t, addr) - 0usize];
+    ["Offset of field: syscall_brk_return_t::error"]
+        [::core::mem::offset_of!(syscall_brk_return_t, error) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_brk_t {
+    pub addr: *mut ::core::ffi::c_void,
+    pub return_value: syscall_brk_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_brk_t"][::core::mem::size_of::<syscall_brk_t>() - 24usize];
+    ["Alignment of syscall_brk_t"][::core::mem::align_of::<syscall_brk_t>() - 8usize];
+    ["Offset of field: syscall_brk_t::addr"][::core::mem::offset_of!(syscall_brk_t, addr) - 0usize];
+    ["Offset of field: syscall_brk_t::return_value"]
+        [::core::mem::offset_of!(syscall_brk_t, return_value) - 8usize];
+};
+pub type syscall_nice_error_t = u32;
+pub const SYSCALL_NICE_ERROR_NONE: syscall_nice_error_t = 0;
+pub const SYSCALL_NICE_ERROR_PERMISSION_DENIED: syscall_nice_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_nice_return_t {
+    pub nice: i32,
+    pub error: syscall_nice_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_nice_return_t"][::core::mem::size_of::<syscall_nice_return_t>() - 8usize];
+    ["Alignment of syscall_nice_return_t"]
+        [::core::mem::align_of::<syscall_nice_return_t>() - 4usize];
+    ["Offset of field: syscall_nice_return_t::nice"]
+        [::core::mem::offset_of!(syscall_nice_return_t, nice) - 0usize];
+    ["Offset of field: syscall_nice_return_t::error"]
+        [::core::mem::offset_of!(syscall_nice_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_nice_t {
+    pub increment: i32,
+    pub return_value: syscall_nice_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_nice_t"][::core::mem::size_of::<syscall_nice_t>() - 12usize];
+    ["Alignment of syscall_nice_t"][::core::mem::align_of::<syscall_nice_t>() - 4usize];
+    ["Offset of field: syscall_nice_t::increment"]
+        [::core::mem::offset_of!(syscall_nice_t, increment) - 0usize];
+    ["Offset of field: syscall_nice_t::return_value"]
+        [::core::mem::offset_of!(syscall_nice_t, return_value) - 4usize];
+};
+pub type tid_t = u32;
+pub type syscall_thread_create_error_t = u32;
+pub const SYSCALL_THREAD_CREATE_ERROR_NONE: syscall_thread_create_error_t = 0;
+pub const SYSCALL_THREAD_CREATE_ERROR_INVALID_ADDR: syscall_thread_create_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_thread_create_return_t {
+    pub tid: tid_t,
+    pub error: syscall_thread_create_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_thread_create_return_t"]
+        [::core::mem::size_of::<syscall_thread_create_return_t>() - 8usize];
+    ["Alignment of syscall_thread_create_return_t"]
+        [::core::mem::align_of::<syscall_thread_create_return_t>() - 4usize];
+    ["Offset of field: syscall_thread_create_return_t::tid"]
+        [::core::mem::offset_of!(syscall_thread_create_return_t, tid) - 0usize];
+    ["Offset of field: syscall_thread_create_return_t::error"]
+        [::core::mem::offset_of!(syscall_thread_create_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_thread_create_t {
+    pub entry: *mut ::core::ffi::c_void,
+    pub stack: *mut ::core::ffi::c_void,
+    pub arg: *mut ::core::ffi::c_void,
+    pub return_value: syscall_thread_create_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_thread_create_t"]
+        [::core::mem::size_of::<syscall_thread_create_t>() - 32usize];
+    ["Alignment of syscall_thread_create_t"]
+        [::core::mem::align_of::<syscall_thread_create_t>() - 8usize];
+    ["Offset of field: syscall_thread_create_t::entry"]
+        [::core::mem::offset_of!(syscall_thread_create_t, entry) - 0usize];
+    ["Offset of field: syscall_thread_create_t::stack"]
+        [::core::mem::offset_of!(syscall_thread_create_t, stack) - 8usize];
+    ["Offset of field: syscall_thread_create_t::arg"]
+        [::core::mem::offset_of!(syscall_thread_create_t, arg) - 16usize];
+    ["Offset of field: syscall_thread_create_t::return_value"]
+        [::core::mem::offset_of!(syscall_thread_create_t, return_value) - 24usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_thread_exit_t {
+    pub status: u32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_thread_exit_t"][::core::mem::size_of::<syscall_thread_exit_t>() - 4usize];
+    ["Alignment of syscall_thread_exit_t"]
+        [::core::mem::align_of::<syscall_thread_exit_t>() - 4usize];
+    ["Offset of field: syscall_thread_exit_t::status"]
+        [::core::mem::offset_of!(syscall_thread_exit_t, status) - 0usize];
+};
+pub type syscall_thread_join_error_t = u32;
+pub const SYSCALL_THREAD_JOIN_ERROR_NONE: syscall_thread_join_error_t = 0;
+pub const SYSCALL_THREAD_JOIN_ERROR_NOT_FOUND: syscall_thread_join_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_thread_join_return_t {
+    pub status: u32,
+    pub error: syscall_thread_join_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_thread_join_return_t"]
+        [::core::mem::size_of::<syscall_thread_join_return_t>() - 8usize];
+    ["Alignment of syscall_thread_join_return_t"]
+        [::core::mem::align_of::<syscall_thread_join_return_t>() - 4usize];
+    ["Offset of field: syscall_thread_join_return_t::status"]
+        [::core::mem::offset_of!(syscall_thread_join_return_t, status) - 0usize];
+    ["Offset of field: syscall_thread_join_return_t::error"]
+        [::core::mem::offset_of!(syscall_thread_join_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_thread_join_t {
+    pub tid: tid_t,
+    pub return_value: syscall_thread_join_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_thread_join_t"][::core::mem::size_of::<syscall_thread_join_t>() - 12usize];
+    ["Alignment of syscall_thread_join_t"]
+        [::core::mem::align_of::<syscall_thread_join_t>() - 4usize];
+    ["Offset of field: syscall_thread_join_t::tid"]
+        [::core::mem::offset_of!(syscall_thread_join_t, tid) - 0usize];
+    ["Offset of field: syscall_thread_join_t::return_value"]
+        [::core::mem::offset_of!(syscall_thread_join_t, return_value) - 4usize];
 };
 pub type syscall_execve_error_t = u32;
 pub const SYSCALL_EXECVE_ERROR_NONE: syscall_execve_error_t = 0;
 pub const SYSCALL_EXECVE_ERROR_NOT_FOUND: syscall_execve_error_t = 1;
+pub const SYSCALL_EXECVE_ERROR_INVALID_EXECUTABLE: syscall_execve_error_t = 2;
+pub const SYSCALL_EXECVE_ERROR_ARGUMENTS_TOO_LONG: syscall_execve_error_t = 3;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_execve_return_t {
@@ -624,6 +931,10 @@ const _: () = {
 pub type syscall_write_error_t = u32;
 pub const SYSCALL_WRITE_ERROR_NONE: syscall_write_error_t = 0;
 pub const SYSCALL_WRITE_ERROR_INVALID_FD: syscall_write_error_t = 1;
+pub const SYSCALL_WRITE_ERROR_BROKEN_PIPE: syscall_write_error_t = 2;
+pub const SYSCALL_WRITE_ERROR_INVALID_VALUE: syscall_write_error_t = 3;
+pub const SYSCALL_WRITE_ERROR_FILE_TOO_LARGE: syscall_write_error_t = 4;
+pub const SYSCALL_WRITE_ERROR_NO_SPACE: syscall_write_error_t = 5;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_write_return_t {
@@ -663,35 +974,1065 @@ const _: () = {
 pub type syscall_waitpid_error_t = u32;
 pub const SYSCALL_WAITPID_ERROR_NONE: syscall_waitpid_error_t = 0;
 pub const SYSCALL_WAITPID_ERROR_INVALID_PID: syscall_waitpid_error_t = 1;
+pub const SYSCALL_WAITPID_ANY_CHILD: pid_t = 0;
+pub const SYSCALL_WAITPID_OPTION_NON_BLOCKING: u32 = 1;
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_waitpid_return_t {
+    pub pid: pid_t,
     pub status: u32,
     pub error: syscall_waitpid_error_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
     ["Size of syscall_waitpid_return_t"]
-        [::core::mem::size_of::<syscall_waitpid_return_t>() - 8usize];
+        [::core::mem::size_of::<syscall_waitpid_return_t>() - 12usize];
     ["Alignment of syscall_waitpid_return_t"]
         [::core::mem::align_of::<syscall_waitpid_return_t>() - 4usize];
+    ["Offset of field: syscall_waitpid_return_t::pid"]
+        [::core::mem::offset_of!(syscall_waitpid_return_t, pid) - 0usize];
     ["Offset of field: syscall_waitpid_return_t::status"]
-        [::core::mem::offset_of!(syscall_waitpid_return_t, status) - 0usize];
+        [::core::mem::offset_of!(syscall_waitpid_return_t, status) - 4usize];
     ["Offset of field: syscall_waitpid_return_t::error"]
-        [::core::mem::offset_of!(syscall_waitpid_return_t, error) - 4usize];
+        [::core::mem::offset_of!(syscall_waitpid_return_t, error) - 8usize];
 };
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct syscall_waitpid_t {
     pub pid: pid_t,
+    pub options: u32,
     pub return_value: syscall_waitpid_return_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of syscall_waitpid_t"][::core::mem::size_of::<syscall_waitpid_t>() - 12usize];
+    ["Size of syscall_waitpid_t"][::core::mem::size_of::<syscall_waitpid_t>() - 20usize];
     ["Alignment of syscall_waitpid_t"][::core::mem::align_of::<syscall_waitpid_t>() - 4usize];
     ["Offset of field: syscall_waitpid_t::pid"]
         [::core::mem::offset_of!(syscall_waitpid_t, pid) - 0usize];
+    ["Offset of field: syscall_waitpid_t::options"]
+        [::core::mem::offset_of!(syscall_waitpid_t, options) - 4usize];
     ["Offset of field: syscall_waitpid_t::return_value"]
-        [::core::mem::offset_of!(syscall_waitpid_t, return_value) - 4usize];
+        [::core::mem::offset_of!(syscall_waitpid_t, return_value) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_pipe_return_t {
+    pub read_fd: fd_t,
+    pub write_fd: fd_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_pipe_return_t"][::core::mem::size_of::<syscall_pipe_return_t>() - 8usize];
+    ["Alignment of syscall_pipe_return_t"]
+        [::core::mem::align_of::<syscall_pipe_return_t>() - 4usize];
+    ["Offset of field: syscall_pipe_return_t::read_fd"]
+        [::core::mem::offset_of!(syscall_pipe_return_t, read_fd) - 0usize];
+    ["Offset of field: syscall_pipe_return_t::write_fd"]
+        [::core::mem::offset_of!(syscall_pipe_return_t, write_fd) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_pipe_t {
+    pub flags: u32,
+    pub return_value: syscall_pipe_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_pipe_t"][::core::mem::size_of::<syscall_pipe_t>() - 12usize];
+    ["Alignment of syscall_pipe_t"][::core::mem::align_of::<syscall_pipe_t>() - 4usize];
+    ["Offset of field: syscall_pipe_t::flags"]
+        [::core::mem::offset_of!(syscall_pipe_t, flags) - 0usize];
+    ["Offset of field: syscall_pipe_t::return_value"]
+        [::core::mem::offset_of!(syscall_pipe_t, return_value) - 4usize];
+};
+pub type syscall_dup_error_t = u32;
+pub const SYSCALL_DUP_ERROR_NONE: syscall_dup_error_t = 0;
+pub const SYSCALL_DUP_ERROR_INVALID_FD: syscall_dup_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_dup_return_t {
+    pub fd: fd_t,
+    pub error: syscall_dup_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_dup_return_t"][::core::mem::size_of::<syscall_dup_return_t>() - 8usize];
+    ["Alignment of syscall_dup_return_t"][::core::mem::align_of::<syscall_dup_return_t>() - 4usize];
+    ["Offset of field: syscall_dup_return_t::fd"]
+        [::core::mem::offset_of!(syscall_dup_return_t, fd) - 0usize];
+    ["Offset of field: syscall_dup_return_t::error"]
+        [::core::mem::offset_of!(syscall_dup_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_dup_t {
+    pub fd: fd_t,
+    pub flags: u32,
+    pub return_value: syscall_dup_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_dup_t"][::core::mem::size_of::<syscall_dup_t>() - 16usize];
+    ["Alignment of syscall_dup_t"][::core::mem::align_of::<syscall_dup_t>() - 4usize];
+    ["Offset of field: syscall_dup_t::fd"][::core::mem::offset_of!(syscall_dup_t, fd) - 0usize];
+    ["Offset of field: syscall_dup_t::flags"]
+        [::core::mem::offset_of!(syscall_dup_t, flags) - 4usize];
+    ["Offset of field: syscall_dup_t::return_value"]
+        [::core::mem::offset_of!(syscall_dup_t, return_value) - 8usize];
+};
+pub type syscall_dup2_error_t = u32;
+pub const SYSCALL_DUP2_ERROR_NONE: syscall_dup2_error_t = 0;
+pub const SYSCALL_DUP2_ERROR_INVALID_FD: syscall_dup2_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_dup2_return_t {
+    pub fd: fd_t,
+    pub error: syscall_dup2_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_dup2_return_t"][::core::mem::size_of::<syscall_dup2_return_t>() - 8usize];
+    ["Alignment of syscall_dup2_return_t"]
+        [::core::mem::align_of::<syscall_dup2_return_t>() - 4usize];
+    ["Offset of field: syscall_dup2_return_t::fd"]
+        [::core::mem::offset_of!(syscall_dup2_return_t, fd) - 0usize];
+    ["Offset of field: syscall_dup2_return_t::error"]
+        [::core::mem::offset_of!(syscall_dup2_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_dup2_t {
+    pub fd: fd_t,
+    pub new_fd: fd_t,
+    pub flags: u32,
+    pub return_value: syscall_dup2_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_dup2_t"][::core::mem::size_of::<syscall_dup2_t>() - 20usize];
+    ["Alignment of syscall_dup2_t"][::core::mem::align_of::<syscall_dup2_t>() - 4usize];
+    ["Offset of field: syscall_dup2_t::fd"][::core::mem::offset_of!(syscall_dup2_t, fd) - 0usize];
+    ["Offset of field: syscall_dup2_t::new_fd"]
+        [::core::mem::offset_of!(syscall_dup2_t, new_fd) - 4usize];
+    ["Offset of field: syscall_dup2_t::flags"]
+        [::core::mem::offset_of!(syscall_dup2_t, flags) - 8usize];
+    ["Offset of field: syscall_dup2_t::return_value"]
+        [::core::mem::offset_of!(syscall_dup2_t, return_value) - 12usize];
+};
+pub type signal_t = u32;
+pub type signal_mask_t = u32;
+pub const SIGHUP: signal_t = 1;
+pub const SIGINT: signal_t = 2;
+pub const SIGQUIT: signal_t = 3;
+pub const SIGILL: signal_t = 4;
+pub const SIGABRT: signal_t = 6;
+pub const SIGFPE: signal_t = 8;
+pub const SIGKILL: signal_t = 9;
+pub const SIGUSR1: signal_t = 10;
+pub const SIGSEGV: signal_t = 11;
+pub const SIGUSR2: signal_t = 12;
+pub const SIGPIPE: signal_t = 13;
+pub const SIGALRM: signal_t = 14;
+pub const SIGTERM: signal_t = 15;
+pub const SIGCHLD: signal_t = 17;
+pub const SIGCONT: signal_t = 18;
+pub const SIGSTOP: signal_t = 19;
+pub const SIGTSTP: signal_t = 20;
+pub const SIGNAL_MAX: signal_t = 31;
+pub type syscall_kill_error_t = u32;
+pub const SYSCALL_KILL_ERROR_NONE: syscall_kill_error_t = 0;
+pub const SYSCALL_KILL_ERROR_INVALID_PID: syscall_kill_error_t = 1;
+pub const SYSCALL_KILL_ERROR_INVALID_SIGNAL: syscall_kill_error_t = 2;
+pub const SYSCALL_KILL_ERROR_PERMISSION_DENIED: syscall_kill_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_kill_return_t {
+    pub error: syscall_kill_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_kill_return_t"][::core::mem::size_of::<syscall_kill_return_t>() - 4usize];
+    ["Alignment of syscall_kill_return_t"]
+        [::core::mem::align_of::<syscall_kill_return_t>() - 4usize];
+    ["Offset of field: syscall_kill_return_t::error"]
+        [::core::mem::offset_of!(syscall_kill_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_kill_t {
+    pub pid: pid_t,
+    pub signal: signal_t,
+    pub return_value: syscall_kill_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_kill_t"][::core::mem::size_of::<syscall_kill_t>() - 12usize];
+    ["Alignment of syscall_kill_t"][::core::mem::align_of::<syscall_kill_t>() - 4usize];
+    ["Offset of field: syscall_kill_t::pid"][::core::mem::offset_of!(syscall_kill_t, pid) - 0usize];
+    ["Offset of field: syscall_kill_t::signal"]
+        [::core::mem::offset_of!(syscall_kill_t, signal) - 4usize];
+    ["Offset of field: syscall_kill_t::return_value"]
+        [::core::mem::offset_of!(syscall_kill_t, return_value) - 8usize];
+};
+pub const SIGNAL_HANDLER_DEFAULT: u64 = 0;
+pub const SIGNAL_HANDLER_IGNORE: u64 = 1;
+pub type syscall_sigaction_error_t = u32;
+pub const SYSCALL_SIGACTION_ERROR_NONE: syscall_sigaction_error_t = 0;
+pub const SYSCALL_SIGACTION_ERROR_INVALID_SIGNAL: syscall_sigaction_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_sigaction_return_t {
+    pub old_handler: u64,
+    pub error: syscall_sigaction_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_sigaction_return_t"]
+        [::core::mem::size_of::<syscall_sigaction_return_t>() - 16usize];
+    ["Alignment of syscall_sigaction_return_t"]
+        [::core::mem::align_of::<syscall_sigaction_return_t>() - 8usize];
+    ["Offset of field: syscall_sigaction_return_t::old_handler"]
+        [::core::mem::offset_of!(syscall_sigaction_return_t, old_handler) - 0usize];
+    ["Offset of field: syscall_sigaction_return_t::error"]
+        [::core::mem::offset_of!(syscall_sigaction_return_t, error) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_sigaction_t {
+    pub signal: signal_t,
+    pub handler: u64,
+    pub mask: signal_mask_t,
+    pub restorer: u64,
+    pub return_value: syscall_sigaction_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_sigaction_t"][::core::mem::size_of::<syscall_sigaction_t>() - 48usize];
+    ["Alignment of syscall_sigaction_t"][::core::mem::align_of::<syscall_sigaction_t>() - 8usize];
+    ["Offset of field: syscall_sigaction_t::signal"]
+        [::core::mem::offset_of!(syscall_sigaction_t, signal) - 0usize];
+    ["Offset of field: syscall_sigaction_t::handler"]
+        [::core::mem::offset_of!(syscall_sigaction_t, handler) - 8usize];
+    ["Offset of field: syscall_sigaction_t::mask"]
+        [::core::mem::offset_of!(syscall_sigaction_t, mask) - 16usize];
+    ["Offset of field: syscall_sigaction_t::restorer"]
+        [::core::mem::offset_of!(syscall_sigaction_t, restorer) - 24usize];
+    ["Offset of field: syscall_sigaction_t::return_value"]
+        [::core::mem::offset_of!(syscall_sigaction_t, return_value) - 32usize];
+};
+pub type syscall_sigprocmask_how_t = u32;
+pub const SYSCALL_SIGPROCMASK_BLOCK: syscall_sigprocmask_how_t = 0;
+pub const SYSCALL_SIGPROCMASK_UNBLOCK: syscall_sigprocmask_how_t = 1;
+pub const SYSCALL_SIGPROCMASK_SET: syscall_sigprocmask_how_t = 2;
+pub type syscall_sigprocmask_error_t = u32;
+pub const SYSCALL_SIGPROCMASK_ERROR_NONE: syscall_sigprocmask_error_t = 0;
+pub const SYSCALL_SIGPROCMASK_ERROR_INVALID_HOW: syscall_sigprocmask_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_sigprocmask_return_t {
+    pub old_mask: signal_mask_t,
+    pub error: syscall_sigprocmask_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_sigprocmask_return_t"]
+        [::core::mem::size_of::<syscall_sigprocmask_return_t>() - 8usize];
+    ["Alignment of syscall_sigprocmask_return_t"]
+        [::core::mem::align_of::<syscall_sigprocmask_return_t>() - 4usize];
+    ["Offset of field: syscall_sigprocmask_return_t::old_mask"]
+        [::core::mem::offset_of!(syscall_sigprocmask_return_t, old_mask) - 0usize];
+    ["Offset of field: syscall_sigprocmask_return_t::error"]
+        [::core::mem::offset_of!(syscall_sigprocmask_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_sigprocmask_t {
+    pub how: syscall_sigprocmask_how_t,
+    pub mask: signal_mask_t,
+    pub return_value: syscall_sigprocmask_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_sigprocmask_t"][::core::mem::size_of::<syscall_sigprocmask_t>() - 16usize];
+    ["Alignment of syscall_sigprocmask_t"]
+        [::core::mem::align_of::<syscall_sigprocmask_t>() - 4usize];
+    ["Offset of field: syscall_sigprocmask_t::how"]
+        [::core::mem::offset_of!(syscall_sigprocmask_t, how) - 0usize];
+    ["Offset of field: syscall_sigprocmask_t::mask"]
+        [::core::mem::offset_of!(syscall_sigprocmask_t, mask) - 4usize];
+    ["Offset of field: syscall_sigprocmask_t::return_value"]
+        [::core::mem::offset_of!(syscall_sigprocmask_t, return_value) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct signal_frame_t {
+    pub rax: u64,
+    pub rbx: u64,
+    pub rcx: u64,
+    pub rdx: u64,
+    pub rsi: u64,
+    pub rdi: u64,
+    pub rsp: u64,
+    pub rbp: u64,
+    pub r8: u64,
+    pub r9: u64,
+    pub r10: u64,
+    pub r11: u64,
+    pub r12: u64,
+    pub r13: u64,
+    pub r14: u64,
+    pub r15: u64,
+    pub rip: u64,
+    pub flags: u64,
+    pub blocked: signal_mask_t,
+    pub signal: signal_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of signal_frame_t"][::core::mem::size_of::<signal_frame_t>() - 152usize];
+    ["Alignment of signal_frame_t"][::core::mem::align_of::<signal_frame_t>() - 8usize];
+    ["Offset of field: signal_frame_t::rax"][::core::mem::offset_of!(signal_frame_t, rax) - 0usize];
+    ["Offset of field: signal_frame_t::rbx"][::core::mem::offset_of!(signal_frame_t, rbx) - 8usize];
+    ["Offset of field: signal_frame_t::rcx"]
+        [::core::mem::offset_of!(signal_frame_t, rcx) - 16usize];
+    ["Offset of field: signal_frame_t::rdx"]
+        [::core::mem::offset_of!(signal_frame_t, rdx) - 24usize];
+    ["Offset of field: signal_frame_t::rsi"]
+        [::core::mem::offset_of!(signal_frame_t, rsi) - 32usize];
+    ["Offset of field: signal_frame_t::rdi"]
+        [::core::mem::offset_of!(signal_frame_t, rdi) - 40usize];
+    ["Offset of field: signal_frame_t::rsp"]
+        [::core::mem::offset_of!(signal_frame_t, rsp) - 48usize];
+    ["Offset of field: signal_frame_t::rbp"]
+        [::core::mem::offset_of!(signal_frame_t, rbp) - 56usize];
+    ["Offset of field: signal_frame_t::r8"][::core::mem::offset_of!(signal_frame_t, r8) - 64usize];
+    ["Offset of field: signal_frame_t::r9"][::core::mem::offset_of!(signal_frame_t, r9) - 72usize];
+    ["Offset of field: signal_frame_t::r10"]
+        [::core::mem::offset_of!(signal_frame_t, r10) - 80usize];
+    ["Offset of field: signal_frame_t::r11"]
+        [::core::mem::offset_of!(signal_frame_t, r11) - 88usize];
+    ["Offset of field: signal_frame_t::r12"]
+        [::core::mem::offset_of!(signal_frame_t, r12) - 96usize];
+    ["Offset of field: signal_frame_t::r13"]
+        [::core::mem::offset_of!(signal_frame_t, r13) - 104usize];
+    ["Offset of field: signal_frame_t::r14"]
+        [::core::mem::offset_of!(signal_frame_t, r14) - 112usize];
+    ["Offset of field: signal_frame_t::r15"]
+        [::core::mem::offset_of!(signal_frame_t, r15) - 120usize];
+    ["Offset of field: signal_frame_t::rip"]
+        [::core::mem::offset_of!(signal_frame_t, rip) - 128usize];
+    ["Offset of field: signal_frame_t::flags"]
+        [::core::mem::offset_of!(signal_frame_t, flags) - 136usize];
+    ["Offset of field: signal_frame_t::blocked"]
+        [::core::mem::offset_of!(signal_frame_t, blocked) - 144usize];
+    ["Offset of field: signal_frame_t::signal"]
+        [::core::mem::offset_of!(signal_frame_t, signal) - 148usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getpid_return_t {
+    pub pid: pid_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getpid_return_t"][::core::mem::size_of::<syscall_getpid_return_t>() - 4usize];
+    ["Alignment of syscall_getpid_return_t"]
+        [::core::mem::align_of::<syscall_getpid_return_t>() - 4usize];
+    ["Offset of field: syscall_getpid_return_t::pid"]
+        [::core::mem::offset_of!(syscall_getpid_return_t, pid) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getpid_t {
+    pub return_value: syscall_getpid_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getpid_t"][::core::mem::size_of::<syscall_getpid_t>() - 4usize];
+    ["Alignment of syscall_getpid_t"][::core::mem::align_of::<syscall_getpid_t>() - 4usize];
+    ["Offset of field: syscall_getpid_t::return_value"]
+        [::core::mem::offset_of!(syscall_getpid_t, return_value) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getppid_return_t {
+    pub pid: pid_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getppid_return_t"]
+        [::core::mem::size_of::<syscall_getppid_return_t>() - 4usize];
+    ["Alignment of syscall_getppid_return_t"]
+        [::core::mem::align_of::<syscall_getppid_return_t>() - 4usize];
+    ["Offset of field: syscall_getppid_return_t::pid"]
+        [::core::mem::offset_of!(syscall_getppid_return_t, pid) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getppid_t {
+    pub return_value: syscall_getppid_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getppid_t"][::core::mem::size_of::<syscall_getppid_t>() - 4usize];
+    ["Alignment of syscall_getppid_t"][::core::mem::align_of::<syscall_getppid_t>() - 4usize];
+    ["Offset of field: syscall_getppid_t::return_value"]
+        [::core::mem::offset_of!(syscall_getppid_t, return_value) - 0usize];
+};
+pub type syscall_chdir_error_t = u32;
+pub const SYSCALL_CHDIR_ERROR_NONE: syscall_chdir_error_t = 0;
+pub const SYSCALL_CHDIR_ERROR_NOT_FOUND: syscall_chdir_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_chdir_return_t {
+    pub error: syscall_chdir_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_chdir_return_t"][::core::mem::size_of::<syscall_chdir_return_t>() - 4usize];
+    ["Alignment of syscall_chdir_return_t"]
+        [::core::mem::align_of::<syscall_chdir_return_t>() - 4usize];
+    ["Offset of field: syscall_chdir_return_t::error"]
+        [::core::mem::offset_of!(syscall_chdir_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_chdir_t {
+    pub path: string_const_t,
+    pub return_value: syscall_chdir_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_chdir_t"][::core::mem::size_of::<syscall_chdir_t>() - 24usize];
+    ["Alignment of syscall_chdir_t"][::core::mem::align_of::<syscall_chdir_t>() - 8usize];
+    ["Offset of field: syscall_chdir_t::path"]
+        [::core::mem::offset_of!(syscall_chdir_t, path) - 0usize];
+    ["Offset of field: syscall_chdir_t::return_value"]
+        [::core::mem::offset_of!(syscall_chdir_t, return_value) - 16usize];
+};
+pub type syscall_getcwd_error_t = u32;
+pub const SYSCALL_GETCWD_ERROR_NONE: syscall_getcwd_error_t = 0;
+pub const SYSCALL_GETCWD_ERROR_BUFFER_TOO_SMALL: syscall_getcwd_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getcwd_return_t {
+    pub len: u32,
+    pub error: syscall_getcwd_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getcwd_return_t"][::core::mem::size_of::<syscall_getcwd_return_t>() - 8usize];
+    ["Alignment of syscall_getcwd_return_t"]
+        [::core::mem::align_of::<syscall_getcwd_return_t>() - 4usize];
+    ["Offset of field: syscall_getcwd_return_t::len"]
+        [::core::mem::offset_of!(syscall_getcwd_return_t, len) - 0usize];
+    ["Offset of field: syscall_getcwd_return_t::error"]
+        [::core::mem::offset_of!(syscall_getcwd_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getcwd_t {
+    pub buf: *mut ::core::ffi::c_char,
+    pub len: u32,
+    pub return_value: syscall_getcwd_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getcwd_t"][::core::mem::size_of::<syscall_getcwd_t>() - 24usize];
+    ["Alignment of syscall_getcwd_t"][::core::mem::align_of::<syscall_getcwd_t>() - 8usize];
+    ["Offset of field: syscall_getcwd_t::buf"]
+        [::core::mem::offset_of!(syscall_getcwd_t, buf) - 0usize];
+    ["Offset of field: syscall_getcwd_t::len"]
+        [::core::mem::offset_of!(syscall_getcwd_t, len) - 8usize];
+    ["Offset of field: syscall_getcwd_t::return_value"]
+        [::core::mem::offset_of!(syscall_getcwd_t, return_value) - 12usize];
+};
+pub type syscall_mount_error_t = u32;
+pub const SYSCALL_MOUNT_ERROR_NONE: syscall_mount_error_t = 0;
+pub const SYSCALL_MOUNT_ERROR_NOT_FOUND: syscall_mount_error_t = 1;
+pub const SYSCALL_MOUNT_ERROR_NOT_A_DIRECTORY: syscall_mount_error_t = 2;
+pub const SYSCALL_MOUNT_ERROR_UNKNOWN_FILE_SYSTEM: syscall_mount_error_t = 3;
+pub const SYSCALL_MOUNT_ERROR_BUSY: syscall_mount_error_t = 4;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_mount_return_t {
+    pub error: syscall_mount_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_mount_return_t"][::core::mem::size_of::<syscall_mount_return_t>() - 4usize];
+    ["Alignment of syscall_mount_return_t"]
+        [::core::mem::align_of::<syscall_mount_return_t>() - 4usize];
+    ["Offset of field: syscall_mount_return_t::error"]
+        [::core::mem::offset_of!(syscall_mount_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_mount_t {
+    pub fs_type: string_const_t,
+    pub target: string_const_t,
+    pub return_value: syscall_mount_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_mount_t"][::core::mem::size_of::<syscall_mount_t>() - 40usize];
+    ["Alignment of syscall_mount_t"][::core::mem::align_of::<syscall_mount_t>() - 8usize];
+    ["Offset of field: syscall_mount_t::fs_type"]
+        [::core::mem::offset_of!(syscall_mount_t, fs_type) - 0usize];
+    ["Offset of field: syscall_mount_t::target"]
+        [::core::mem::offset_of!(syscall_mount_t, target) - 16usize];
+    ["Offset of field: syscall_mount_t::return_value"]
+        [::core::mem::offset_of!(syscall_mount_t, return_value) - 32usize];
+};
+pub type syscall_umount_error_t = u32;
+pub const SYSCALL_UMOUNT_ERROR_NONE: syscall_umount_error_t = 0;
+pub const SYSCALL_UMOUNT_ERROR_NOT_MOUNTED: syscall_umount_error_t = 1;
+pub const SYSCALL_UMOUNT_ERROR_BUSY: syscall_umount_error_t = 2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_umount_return_t {
+    pub error: syscall_umount_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_umount_return_t"][::core::mem::size_of::<syscall_umount_return_t>() - 4usize];
+    ["Alignment of syscall_umount_return_t"]
+        [::core::mem::align_of::<syscall_umount_return_t>() - 4usize];
+    ["Offset of field: syscall_umount_return_t::error"]
+        [::core::mem::offset_of!(syscall_umount_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_umount_t {
+    pub target: string_const_t,
+    pub return_value: syscall_umount_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_umount_t"][::core::mem::size_of::<syscall_umount_t>() - 24usize];
+    ["Alignment of syscall_umount_t"][::core::mem::align_of::<syscall_umount_t>() - 8usize];
+    ["Offset of field: syscall_umount_t::target"]
+        [::core::mem::offset_of!(syscall_umount_t, target) - 0usize];
+    ["Offset of field: syscall_umount_t::return_value"]
+        [::core::mem::offset_of!(syscall_umount_t, return_value) - 16usize];
+};
+pub type syscall_unlink_error_t = u32;
+pub const SYSCALL_UNLINK_ERROR_NONE: syscall_unlink_error_t = 0;
+pub const SYSCALL_UNLINK_ERROR_NOT_FOUND: syscall_unlink_error_t = 1;
+pub const SYSCALL_UNLINK_ERROR_IS_A_DIRECTORY: syscall_unlink_error_t = 2;
+pub const SYSCALL_UNLINK_ERROR_NOT_SUPPORTED: syscall_unlink_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_unlink_return_t {
+    pub error: syscall_unlink_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_unlink_return_t"][::core::mem::size_of::<syscall_unlink_return_t>() - 4usize];
+    ["Alignment of syscall_unlink_return_t"]
+        [::core::mem::align_of::<syscall_unlink_return_t>() - 4usize];
+    ["Offset of field: syscall_unlink_return_t::error"]
+        [::core::mem::offset_of!(syscall_unlink_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_unlink_t {
+    pub path: string_const_t,
+    pub return_value: syscall_unlink_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_unlink_t"][::core::mem::size_of::<syscall_unlink_t>() - 24usize];
+    ["Alignment of syscall_unlink_t"][::core::mem::align_of::<syscall_unlink_t>() - 8usize];
+    ["Offset of field: syscall_unlink_t::path"]
+        [::core::mem::offset_of!(syscall_unlink_t, path) - 0usize];
+    ["Offset of field: syscall_unlink_t::return_value"]
+        [::core::mem::offset_of!(syscall_unlink_t, return_value) - 16usize];
+};
+pub type syscall_mkdir_error_t = u32;
+pub const SYSCALL_MKDIR_ERROR_NONE: syscall_mkdir_error_t = 0;
+pub const SYSCALL_MKDIR_ERROR_NOT_FOUND: syscall_mkdir_error_t = 1;
+pub const SYSCALL_MKDIR_ERROR_ALREADY_EXISTS: syscall_mkdir_error_t = 2;
+pub const SYSCALL_MKDIR_ERROR_NOT_SUPPORTED: syscall_mkdir_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_mkdir_return_t {
+    pub error: syscall_mkdir_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_mkdir_return_t"][::core::mem::size_of::<syscall_mkdir_return_t>() - 4usize];
+    ["Alignment of syscall_mkdir_return_t"]
+        [::core::mem::align_of::<syscall_mkdir_return_t>() - 4usize];
+    ["Offset of field: syscall_mkdir_return_t::error"]
+        [::core::mem::offset_of!(syscall_mkdir_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_mkdir_t {
+    pub path: string_const_t,
+    pub return_value: syscall_mkdir_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_mkdir_t"][::core::mem::size_of::<syscall_mkdir_t>() - 24usize];
+    ["Alignment of syscall_mkdir_t"][::core::mem::align_of::<syscall_mkdir_t>() - 8usize];
+    ["Offset of field: syscall_mkdir_t::path"]
+        [::core::mem::offset_of!(syscall_mkdir_t, path) - 0usize];
+    ["Offset of field: syscall_mkdir_t::return_value"]
+        [::core::mem::offset_of!(syscall_mkdir_t, return_value) - 16usize];
+};
+pub type syscall_rmdir_error_t = u32;
+pub const SYSCALL_RMDIR_ERROR_NONE: syscall_rmdir_error_t = 0;
+pub const SYSCALL_RMDIR_ERROR_NOT_FOUND: syscall_rmdir_error_t = 1;
+pub const SYSCALL_RMDIR_ERROR_NOT_A_DIRECTORY: syscall_rmdir_error_t = 2;
+pub const SYSCALL_RMDIR_ERROR_NOT_EMPTY: syscall_rmdir_error_t = 3;
+pub const SYSCALL_RMDIR_ERROR_BUSY: syscall_rmdir_error_t = 4;
+pub const SYSCALL_RMDIR_ERROR_NOT_SUPPORTED: syscall_rmdir_error_t = 5;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_rmdir_return_t {
+    pub error: syscall_rmdir_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_rmdir_return_t"][::core::mem::size_of::<syscall_rmdir_return_t>() - 4usize];
+    ["Alignment of syscall_rmdir_return_t"]
+        [::core::mem::align_of::<syscall_rmdir_return_t>() - 4usize];
+    ["Offset of field: syscall_rmdir_return_t::error"]
+        [::core::mem::offset_of!(syscall_rmdir_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_rmdir_t {
+    pub path: string_const_t,
+    pub return_value: syscall_rmdir_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_rmdir_t"][::core::mem::size_of::<syscall_rmdir_t>() - 24usize];
+    ["Alignment of syscall_rmdir_t"][::core::mem::align_of::<syscall_rmdir_t>() - 8usize];
+    ["Offset of field: syscall_rmdir_t::path"]
+        [::core::mem::offset_of!(syscall_rmdir_t, path) - 0usize];
+    ["Offset of field: syscall_rmdir_t::return_value"]
+        [::core::mem::offset_of!(syscall_rmdir_t, return_value) - 16usize];
+};
+pub type syscall_rename_error_t = u32;
+pub const SYSCALL_RENAME_ERROR_NONE: syscall_rename_error_t = 0;
+pub const SYSCALL_RENAME_ERROR_NOT_FOUND: syscall_rename_error_t = 1;
+pub const SYSCALL_RENAME_ERROR_NOT_A_DIRECTORY: syscall_rename_error_t = 2;
+pub const SYSCALL_RENAME_ERROR_IS_A_DIRECTORY: syscall_rename_error_t = 3;
+pub const SYSCALL_RENAME_ERROR_NOT_EMPTY: syscall_rename_error_t = 4;
+pub const SYSCALL_RENAME_ERROR_BUSY: syscall_rename_error_t = 5;
+pub const SYSCALL_RENAME_ERROR_CROSS_DEVICE: syscall_rename_error_t = 6;
+pub const SYSCALL_RENAME_ERROR_INVALID_PATH: syscall_rename_error_t = 7;
+pub const SYSCALL_RENAME_ERROR_NOT_SUPPORTED: syscall_rename_error_t = 8;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_rename_return_t {
+    pub error: syscall_rename_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_rename_return_t"][::core::mem::size_of::<syscall_rename_return_t>() - 4usize];
+    ["Alignment of syscall_rename_return_t"]
+        [::core::mem::align_of::<syscall_rename_return_t>() - 4usize];
+    ["Offset of field: syscall_rename_return_t::error"]
+        [::core::mem::offset_of!(syscall_rename_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_rename_t {
+    pub old_path: string_const_t,
+    pub new_path: string_const_t,
+    pub return_value: syscall_rename_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_rename_t"][::core::mem::size_of::<syscall_rename_t>() - 40usize];
+    ["Alignment of syscall_rename_t"][::core::mem::align_of::<syscall_rename_t>() - 8usize];
+    ["Offset of field: syscall_rename_t::old_path"]
+        [::core::mem::offset_of!(syscall_rename_t, old_path) - 0usize];
+    ["Offset of field: syscall_rename_t::new_path"]
+        [::core::mem::offset_of!(syscall_rename_t, new_path) - 16usize];
+    ["Offset of field: syscall_rename_t::return_value"]
+        [::core::mem::offset_of!(syscall_rename_t, return_value) - 32usize];
+};
+pub type stat_type_t = u32;
+pub const STAT_TYPE_REGULAR: stat_type_t = 0;
+pub const STAT_TYPE_DIRECTORY: stat_type_t = 1;
+pub const STAT_TYPE_STREAM: stat_type_t = 2;
+pub const STAT_TYPE_SYMLINK: stat_type_t = 3;
+pub const STAT_TYPE_DEVICE: stat_type_t = 4;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct stat_t {
+    pub type_: stat_type_t,
+    pub mode: u32,
+    pub nlink: u32,
+    pub size: u64,
+    pub created: u64,
+    pub modified: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of stat_t"][::core::mem::size_of::<stat_t>() - 40usize];
+    ["Alignment of stat_t"][::core::mem::align_of::<stat_t>() - 8usize];
+    ["Offset of field: stat_t::type_"][::core::mem::offset_of!(stat_t, type_) - 0usize];
+    ["Offset of field: stat_t::mode"][::core::mem::offset_of!(stat_t, mode) - 4usize];
+    ["Offset of field: stat_t::nlink"][::core::mem::offset_of!(stat_t, nlink) - 8usize];
+    ["Offset of field: stat_t::size"][::core::mem::offset_of!(stat_t, size) - 16usize];
+    ["Offset of field: stat_t::created"][::core::mem::offset_of!(stat_t, created) - 24usize];
+    ["Offset of field: stat_t::modified"][::core::mem::offset_of!(stat_t, modified) - 32usize];
+};
+pub type syscall_stat_error_t = u32;
+pub const SYSCALL_STAT_ERROR_NONE: syscall_stat_error_t = 0;
+pub const SYSCALL_STAT_ERROR_NOT_FOUND: syscall_stat_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_stat_return_t {
+    pub error: syscall_stat_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_stat_return_t"][::core::mem::size_of::<syscall_stat_return_t>() - 4usize];
+    ["Alignment of syscall_stat_return_t"]
+        [::core::mem::align_of::<syscall_stat_return_t>() - 4usize];
+    ["Offset of field: syscall_stat_return_t::error"]
+        [::core::mem::offset_of!(syscall_stat_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_stat_t {
+    pub path: string_const_t,
+    pub stat: *mut stat_t,
+    pub return_value: syscall_stat_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_stat_t"][::core::mem::size_of::<syscall_stat_t>() - 32usize];
+    ["Alignment of syscall_stat_t"][::core::mem::align_of::<syscall_stat_t>() - 8usize];
+    ["Offset of field: syscall_stat_t::path"]
+        [::core::mem::offset_of!(syscall_stat_t, path) - 0usize];
+    ["Offset of field: syscall_stat_t::stat"]
+        [::core::mem::offset_of!(syscall_stat_t, stat) - 16usize];
+    ["Offset of field: syscall_stat_t::return_value"]
+        [::core::mem::offset_of!(syscall_stat_t, return_value) - 24usize];
+};
+pub type syscall_fstat_error_t = u32;
+pub const SYSCALL_FSTAT_ERROR_NONE: syscall_fstat_error_t = 0;
+pub const SYSCALL_FSTAT_ERROR_INVALID_FD: syscall_fstat_error_t = 1;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_fstat_return_t {
+    pub error: syscall_fstat_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_fstat_return_t"][::core::mem::size_of::<syscall_fstat_return_t>() - 4usize];
+    ["Alignment of syscall_fstat_return_t"]
+        [::core::mem::align_of::<syscall_fstat_return_t>() - 4usize];
+    ["Offset of field: syscall_fstat_return_t::error"]
+        [::core::mem::offset_of!(syscall_fstat_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_fstat_t {
+    pub fd: fd_t,
+    pub stat: *mut stat_t,
+    pub return_value: syscall_fstat_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_fstat_t"][::core::mem::size_of::<syscall_fstat_t>() - 24usize];
+    ["Alignment of syscall_fstat_t"][::core::mem::align_of::<syscall_fstat_t>() - 8usize];
+    ["Offset of field: syscall_fstat_t::fd"][::core::mem::offset_of!(syscall_fstat_t, fd) - 0usize];
+    ["Offset of field: syscall_fstat_t::stat"]
+        [::core::mem::offset_of!(syscall_fstat_t, stat) - 8usize];
+    ["Offset of field: syscall_fstat_t::return_value"]
+        [::core::mem::offset_of!(syscall_fstat_t, return_value) - 16usize];
+};
+pub type syscall_lseek_whence_t = u32;
+pub const SYSCALL_LSEEK_WHENCE_SET: syscall_lseek_whence_t = 0;
+pub const SYSCALL_LSEEK_WHENCE_CURRENT: syscall_lseek_whence_t = 1;
+pub const SYSCALL_LSEEK_WHENCE_END: syscall_lseek_whence_t = 2;
+pub type syscall_lseek_error_t = u32;
+pub const SYSCALL_LSEEK_ERROR_NONE: syscall_lseek_error_t = 0;
+pub const SYSCALL_LSEEK_ERROR_INVALID_FD: syscall_lseek_error_t = 1;
+pub const SYSCALL_LSEEK_ERROR_INVALID_OFFSET: syscall_lseek_error_t = 2;
+pub const SYSCALL_LSEEK_ERROR_NOT_SEEKABLE: syscall_lseek_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_lseek_return_t {
+    pub offset: u64,
+    pub error: syscall_lseek_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_lseek_return_t"][::core::mem::size_of::<syscall_lseek_return_t>() - 16usize];
+    ["Alignment of syscall_lseek_return_t"]
+        [::core::mem::align_of::<syscall_lseek_return_t>() - 8usize];
+    ["Offset of field: syscall_lseek_return_t::offset"]
+        [::core::mem::offset_of!(syscall_lseek_return_t, offset) - 0usize];
+    ["Offset of field: syscall_lseek_return_t::error"]
+        [::core::mem::offset_of!(syscall_lseek_return_t, error) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_lseek_t {
+    pub fd: fd_t,
+    pub offset: i64,
+    pub whence: syscall_lseek_whence_t,
+    pub return_value: syscall_lseek_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_lseek_t"][::core::mem::size_of::<syscall_lseek_t>() - 40usize];
+    ["Alignment of syscall_lseek_t"][::core::mem::align_of::<syscall_lseek_t>() - 8usize];
+    ["Offset of field: syscall_lseek_t::fd"][::core::mem::offset_of!(syscall_lseek_t, fd) - 0usize];
+    ["Offset of field: syscall_lseek_t::offset"]
+        [::core::mem::offset_of!(syscall_lseek_t, offset) - 8usize];
+    ["Offset of field: syscall_lseek_t::whence"]
+        [::core::mem::offset_of!(syscall_lseek_t, whence) - 16usize];
+    ["Offset of field: syscall_lseek_t::return_value"]
+        [::core::mem::offset_of!(syscall_lseek_t, return_value) - 24usize];
+};
+pub type syscall_pread_error_t = u32;
+pub const SYSCALL_PREAD_ERROR_NONE: syscall_pread_error_t = 0;
+pub const SYSCALL_PREAD_ERROR_INVALID_FD: syscall_pread_error_t = 1;
+pub const SYSCALL_PREAD_ERROR_NOT_SEEKABLE: syscall_pread_error_t = 2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_pread_return_t {
+    pub bytes_read: u32,
+    pub error: syscall_pread_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_pread_return_t"][::core::mem::size_of::<syscall_pread_return_t>() - 8usize];
+    ["Alignment of syscall_pread_return_t"]
+        [::core::mem::align_of::<syscall_pread_return_t>() - 4usize];
+    ["Offset of field: syscall_pread_return_t::bytes_read"]
+        [::core::mem::offset_of!(syscall_pread_return_t, bytes_read) - 0usize];
+    ["Offset of field: syscall_pread_return_t::error"]
+        [::core::mem::offset_of!(syscall_pread_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_pread_t {
+    pub fd: fd_t,
+    pub buf: *mut ::core::ffi::c_void,
+    pub len: u32,
+    pub offset: u64,
+    pub return_value: syscall_pread_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_pread_t"][::core::mem::size_of::<syscall_pread_t>() - 40usize];
+    ["Alignment of syscall_pread_t"][::core::mem::align_of::<syscall_pread_t>() - 8usize];
+    ["Offset of field: syscall_pread_t::fd"][::core::mem::offset_of!(syscall_pread_t, fd) - 0usize];
+    ["Offset of field: syscall_pread_t::buf"]
+        [::core::mem::offset_of!(syscall_pread_t, buf) - 8usize];
+    ["Offset of field: syscall_pread_t::len"]
+        [::core::mem::offset_of!(syscall_pread_t, len) - 16usize];
+    ["Offset of field: syscall_pread_t::offset"]
+        [::core::mem::offset_of!(syscall_pread_t, offset) - 24usize];
+    ["Offset of field: syscall_pread_t::return_value"]
+        [::core::mem::offset_of!(syscall_pread_t, return_value) - 32usize];
+};
+pub type syscall_pwrite_error_t = u32;
+pub const SYSCALL_PWRITE_ERROR_NONE: syscall_pwrite_error_t = 0;
+pub const SYSCALL_PWRITE_ERROR_INVALID_FD: syscall_pwrite_error_t = 1;
+pub const SYSCALL_PWRITE_ERROR_NOT_SEEKABLE: syscall_pwrite_error_t = 2;
+pub const SYSCALL_PWRITE_ERROR_INVALID_VALUE: syscall_pwrite_error_t = 3;
+pub const SYSCALL_PWRITE_ERROR_FILE_TOO_LARGE: syscall_pwrite_error_t = 4;
+pub const SYSCALL_PWRITE_ERROR_NO_SPACE: syscall_pwrite_error_t = 5;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_pwrite_return_t {
+    pub bytes_written: u32,
+    pub error: syscall_pwrite_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_pwrite_return_t"][::core::mem::size_of::<syscall_pwrite_return_t>() - 8usize];
+    ["Alignment of syscall_pwrite_return_t"]
+        [::core::mem::align_of::<syscall_pwrite_return_t>() - 4usize];
+    ["Offset of field: syscall_pwrite_return_t::bytes_written"]
+        [::core::mem::offset_of!(syscall_pwrite_return_t, bytes_written) - 0usize];
+    ["Offset of field: syscall_pwrite_return_t::error"]
+        [::core::mem::offset_of!(syscall_pwrite_return_t, error) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_pwrite_t {
+    pub fd: fd_t,
+    pub buf: *const ::core::ffi::c_void,
+    pub len: u32,
+    pub offset: u64,
+    pub return_value: syscall_pwrite_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_pwrite_t"][::core::mem::size_of::<syscall_pwrite_t>() - 40usize];
+    ["Alignment of syscall_pwrite_t"][::core::mem::align_of::<syscall_pwrite_t>() - 8usize];
+    ["Offset of field: syscall_pwrite_t::fd"]
+        [::core::mem::offset_of!(syscall_pwrite_t, fd) - 0usize];
+    ["Offset of field: syscall_pwrite_t::buf"]
+        [::core::mem::offset_of!(syscall_pwrite_t, buf) - 8usize];
+    ["Offset of field: syscall_pwrite_t::len"]
+        [::core::mem::offset_of!(syscall_pwrite_t, len) - 16usize];
+    ["Offset of field: syscall_pwrite_t::offset"]
+        [::core::mem::offset_of!(syscall_pwrite_t, offset) - 24usize];
+    ["Offset of field: syscall_pwrite_t::return_value"]
+        [::core::mem::offset_of!(syscall_pwrite_t, return_value) - 32usize];
+};
+pub type ioctl_request_t = u32;
+pub const IOCTL_TTY_GET_WINDOW_SIZE: ioctl_request_t = 1;
+pub const IOCTL_FRAMEBUFFER_GET_INFO: ioctl_request_t = 2;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct tty_window_size_t {
+    pub rows: u32,
+    pub columns: u32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of tty_window_size_t"][::core::mem::size_of::<tty_window_size_t>() - 8usize];
+    ["Alignment of tty_window_size_t"][::core::mem::align_of::<tty_window_size_t>() - 4usize];
+    ["Offset of field: tty_window_size_t::rows"]
+        [::core::mem::offset_of!(tty_window_size_t, rows) - 0usize];
+    ["Offset of field: tty_window_size_t::columns"]
+        [::core::mem::offset_of!(tty_window_size_t, columns) - 4usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct framebuffer_info_t {
+    pub width: u32,
+    pub height: u32,
+    pub pitch: u32,
+    pub bits_per_pixel: u32,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of framebuffer_info_t"][::core::mem::size_of::<framebuffer_info_t>() - 16usize];
+    ["Alignment of framebuffer_info_t"][::core::mem::align_of::<framebuffer_info_t>() - 4usize];
+    ["Offset of field: framebuffer_info_t::width"]
+        [::core::mem::offset_of!(framebuffer_info_t, width) - 0usize];
+    ["Offset of field: framebuffer_info_t::height"]
+        [::core::mem::offset_of!(framebuffer_info_t, height) - 4usize];
+    ["Offset of field: framebuffer_info_t::pitch"]
+        [::core::mem::offset_of!(framebuffer_info_t, pitch) - 8usize];
+    ["Offset of field: framebuffer_info_t::bits_per_pixel"]
+        [::core::mem::offset_of!(framebuffer_info_t, bits_per_pixel) - 12usize];
+};
+pub type syscall_ioctl_error_t = u32;
+pub const SYSCALL_IOCTL_ERROR_NONE: syscall_ioctl_error_t = 0;
+pub const SYSCALL_IOCTL_ERROR_INVALID_FD: syscall_ioctl_error_t = 1;
+pub const SYSCALL_IOCTL_ERROR_NOT_SUPPORTED: syscall_ioctl_error_t = 2;
+pub const SYSCALL_IOCTL_ERROR_INVALID_ARGUMENT: syscall_ioctl_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_ioctl_return_t {
+    pub error: syscall_ioctl_error_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_ioctl_return_t"][::core::mem::size_of::<syscall_ioctl_return_t>() - 4usize];
+    ["Alignment of syscall_ioctl_return_t"]
+        [::core::mem::align_of::<syscall_ioctl_return_t>() - 4usize];
+    ["Offset of field: syscall_ioctl_return_t::error"]
+        [::core::mem::offset_of!(syscall_ioctl_return_t, error) - 0usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_ioctl_t {
+    pub fd: fd_t,
+    pub request: ioctl_request_t,
+    pub arg: *mut ::core::ffi::c_void,
+    pub arg_len: u32,
+    pub return_value: syscall_ioctl_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_ioctl_t"][::core::mem::size_of::<syscall_ioctl_t>() - 24usize];
+    ["Alignment of syscall_ioctl_t"][::core::mem::align_of::<syscall_ioctl_t>() - 8usize];
+    ["Offset of field: syscall_ioctl_t::fd"][::core::mem::offset_of!(syscall_ioctl_t, fd) - 0usize];
+    ["Offset of field: syscall_ioctl_t::request"]
+        [::core::mem::offset_of!(syscall_ioctl_t, request) - 4usize];
+    ["Offset of field: syscall_ioctl_t::arg"]
+        [::core::mem::offset_of!(syscall_ioctl_t, arg) - 8usize];
+    ["Offset of field: syscall_ioctl_t::arg_len"]
+        [::core::mem::offset_of!(syscall_ioctl_t, arg_len) - 16usize];
+    ["Offset of field: syscall_ioctl_t::return_value"]
+        [::core::mem::offset_of!(syscall_ioctl_t, return_value) - 20usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct dirent_t {
+    pub inode: u64,
+    pub size: u64,
+    pub type_: stat_type_t,
+    pub record_len: u16,
+    pub name_len: u16,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of dirent_t"][::core::mem::size_of::<dirent_t>() - 24usize];
+    ["Alignment of dirent_t"][::core::mem::align_of::<dirent_t>() - 8usize];
+    ["Offset of field: dirent_t::inode"][::core::mem::offset_of!(dirent_t, inode) - 0usize];
+    ["Offset of field: dirent_t::size"][::core::mem::offset_of!(dirent_t, size) - 8usize];
+    ["Offset of field: dirent_t::type_"][::core::mem::offset_of!(dirent_t, type_) - 16usize];
+    ["Offset of field: dirent_t::record_len"]
+        [::core::mem::offset_of!(dirent_t, record_len) - 20usize];
+    ["Offset of field: dirent_t::name_len"][::core::mem::offset_of!(dirent_t, name_len) - 22usize];
+};
+pub type syscall_getdents_error_t = u32;
+pub const SYSCALL_GETDENTS_ERROR_NONE: syscall_getdents_error_t = 0;
+pub const SYSCALL_GETDENTS_ERROR_INVALID_FD: syscall_getdents_error_t = 1;
+pub const SYSCALL_GETDENTS_ERROR_NOT_A_DIRECTORY: syscall_getdents_error_t = 2;
+pub const SYSCALL_GETDENTS_ERROR_BUFFER_TOO_SMALL: syscall_getdents_error_t = 3;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getdents_return_t {
+    pub bytes_written: u32,
+    pub error: syscall_getdents_error_t,
+    pub cookie: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getdents_return_t"]
+        [::core::mem::size_of::<syscall_getdents_return_t>() - 16usize];
+    ["Alignment of syscall_getdents_return_t"]
+        [::core::mem::align_of::<syscall_getdents_return_t>() - 8usize];
+    ["Offset of field: syscall_getdents_return_t::bytes_written"]
+        [::core::mem::offset_of!(syscall_getdents_return_t, bytes_written) - 0usize];
+    ["Offset of field: syscall_getdents_return_t::error"]
+        [::core::mem::offset_of!(syscall_getdents_return_t, error) - 4usize];
+    ["Offset of field: syscall_getdents_return_t::cookie"]
+        [::core::mem::offset_of!(syscall_getdents_return_t, cookie) - 8usize];
+};
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct syscall_getdents_t {
+    pub fd: fd_t,
+    pub cookie: u64,
+    pub buf: *mut ::core::ffi::c_void,
+    pub len: u32,
+    pub return_value: syscall_getdents_return_t,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of syscall_getdents_t"][::core::mem::size_of::<syscall_getdents_t>() - 48usize];
+    ["Alignment of syscall_getdents_t"][::core::mem::align_of::<syscall_getdents_t>() - 8usize];
+    ["Offset of field: syscall_getdents_t::fd"]
+        [::core::mem::offset_of!(syscall_getdents_t, fd) - 0usize];
+    ["Offset of field: syscall_getdents_t::cookie"]
+        [::core::mem::offset_of!(syscall_getdents_t, cookie) - 8usize];
+    ["Offset of field: syscall_getdents_t::buf"]
+        [::core::mem::offset_of!(syscall_getdents_t, buf) - 16usize];
+    ["Offset of field: syscall_getdents_t::len"]
+        [::core::mem::offset_of!(syscall_getdents_t, len) - 24usize];
+    ["Offset of field: syscall_getdents_t::return_value"]
+        [::core::mem::offset_of!(syscall_getdents_t, return_value) - 32usize];
 };
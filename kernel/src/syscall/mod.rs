@@ -1,155 +1,279 @@
 use alloc::{string::String, vec};
 use core::fmt::Write;
 use log::trace;
-use x86_64::structures::paging::{
-    FrameAllocator, FrameDeallocator as _, Mapper, Page, PageSize, PageTableFlags, Size4KiB,
-    Translate,
-};
+use x86_64::structures::paging::{Mapper, Page, PageSize, PageTableFlags, Size4KiB, Translate};
 
-use crate::process::{MappedPage, PROCESSES};
+use crate::process::{
+    memory::{Backing, Fault, Region},
+    pipe::PipeSide,
+    signal, PROCESSES,
+};
+use user::UserMemory;
 
 pub mod generated {
     #![allow(clippy::all)]
     #![allow(warnings)]
     include!("generated.rs");
 }
+mod user;
+
+/// Whether a process that makes a failing syscall is terminated instead of getting an error
+pub static KILL_ON_SYSCALL_ERROR: core::sync::atomic::AtomicBool =
+    core::sync::atomic::AtomicBool::new(false);
+
+/// Errors that every syscall can fail with, in addition to the errors in its return value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallError {
+    Fault(Fault),
+    UnknownSyscall(u64),
+    NotSupported,
+    /// a blocked syscall was interrupted to deliver a signal
+    Interrupted,
+    /// a buffer or string in user memory is longer than [`MAX_TRANSFER`] or [`user::PATH_MAX`]
+    TooLarge,
+    /// the kernel heap has no room for a copy of user memory
+    NoMemory,
+}
+
+impl SyscallError {
+    pub fn code(self) -> generated::syscall_error_t {
+        match self {
+            SyscallError::Fault(_) => generated::SYSCALL_ERROR_FAULT,
+            SyscallError::UnknownSyscall(_) => generated::SYSCALL_ERROR_UNKNOWN_SYSCALL,
+            SyscallError::NotSupported => generated::SYSCALL_ERROR_NOT_SUPPORTED,
+            SyscallError::Interrupted => generated::SYSCALL_ERROR_INTERRUPTED,
+            SyscallError::TooLarge => generated::SYSCALL_ERROR_TOO_LARGE,
+            SyscallError::NoMemory => generated::SYSCALL_ERROR_NO_MEMORY,
+        }
+    }
+}
 
-fn copy_string_t_from_user(string: generated::string_const_t) -> String {
-    let mut bytes = vec![0; string.len as usize];
-    for (i, byte) in bytes.iter_mut().enumerate() {
-        *byte = unsafe { string.ptr.cast::<u8>().add(i).read_volatile() };
+impl From<Fault> for SyscallError {
+    fn from(fault: Fault) -> Self {
+        SyscallError::Fault(fault)
     }
-    String::from_utf8(bytes).expect("Invalid UTF-8 string")
 }
 
-fn print(pid: u32, arg: &mut generated::syscall_print_t) {
-    let string = copy_string_t_from_user(arg.message);
+fn print(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_print_t,
+) -> Result<(), SyscallError> {
+    let string = user.read_string(arg.message)?;
     write!(crate::term::TERM.writer(), "{string}").expect("Failed to write to terminal");
 
     log::debug!("[{pid}]: {string}");
+
+    Ok(())
 }
 
 /// sleep for the number of milliseconds in rbx
-fn sleep(pid: u32, arg: &mut generated::syscall_sleep_t) {
-    PROCESSES.with_process_mut(pid, |p| {
-        trace!(
-            "syscall_handler: sleep for {} milliseconds",
-            arg.milliseconds
-        );
+fn sleep(_pid: u32, tid: u32, arg: &mut generated::syscall_sleep_t) {
+    trace!(
+        "syscall_handler: sleep for {} milliseconds",
+        arg.milliseconds
+    );
 
-        p.state = crate::process::State::Sleeping(unsafe {
-            crate::i8253::TIMER0.ticks() + arg.milliseconds as u64 / 100
-        });
-    });
+    PROCESSES
+        .thread_mut(tid)
+        .set_state(crate::process::State::Sleeping(
+            crate::i8253::timer0().ticks()
+                + crate::i8253::timer0().ms_to_ticks(u64::from(arg.milliseconds)),
+        ));
 }
 
 /// Exit the current process with the exit code in rbx
-fn exit(pid: u32, arg: &mut generated::syscall_exit_t) {
-    PROCESSES.with_process_mut(pid, |p| {
-        trace!("syscall_handler: exit {:#x}", arg.status);
-        p.state = crate::process::State::Terminated(u64::from(arg.status));
+fn exit(pid: u32, _tid: u32, arg: &mut generated::syscall_exit_t) {
+    trace!("syscall_handler: exit {:#x}", arg.status);
 
-        log::debug!("Process {} exited with code {}", p.pid(), arg.status);
-    });
+    exit_process(pid, arg.status);
+}
 
-    for process in PROCESSES.processes_mut().iter_mut() {
-        match process.state {
-            crate::process::State::WaitingForChild {
-                pid: child_pid,
-                arg: child_arg,
-            } if child_pid == pid => {
-                process.load_paging();
-                (unsafe { &mut *child_arg }).return_value.status = arg.status;
-                process.state = crate::process::State::Ready;
-            }
-            _ => {}
+/// Terminates all threads of process `pid` and closes its files.
+/// The process stays a zombie until its parent reaps it with `waitpid`, its children are adopted by [`crate::process::INIT_PID`].
+pub fn exit_process(pid: u32, status: u32) {
+    assert!(
+        pid != crate::process::INIT_PID,
+        "init process exited with status {status}, there is nothing left to run the system"
+    );
+
+    let mut process = PROCESSES.process_mut(pid);
+    // a process can be killed while it exits
+    if process.exit_status().is_some() {
+        return;
+    }
+    let files = process.exited(status);
+    let parent = process.parent();
+    drop(process);
+
+    log::debug!("Process {pid} exited with code {status}");
+
+    for tid in PROCESSES.threads_of(pid) {
+        PROCESSES
+            .thread_mut(tid)
+            .set_state(crate::process::State::Terminated(u64::from(status)));
+    }
+
+    for file in files {
+        closed(file);
+    }
+
+    // the init process adopts the orphans
+    let init_alive = PROCESSES
+        .processes()
+        .iter()
+        .any(|p| p.pid() == crate::process::INIT_PID && p.exit_status().is_none());
+    let new_parent = init_alive.then_some(crate::process::INIT_PID);
+
+    let mut zombies = alloc::vec::Vec::new();
+    for child in PROCESSES
+        .processes_mut()
+        .iter_mut()
+        .filter(|p| p.parent() == Some(pid))
+    {
+        log::debug!("process {} was orphaned by {pid}", child.pid());
+        child.set_parent(new_parent);
+        if child.exit_status().is_some() {
+            zombies.push(child.pid());
         }
     }
+
+    notify_parent(pid);
+    for zombie in zombies {
+        notify_parent(zombie);
+    }
+
+    if let Some(parent) = parent {
+        send_signal(parent, generated::SIGCHLD);
+    }
 }
 
-/// Get the name of the entry at index rdx in the directory at path in rbx
-/// Returns the name to the pointer in r8 and the length of the name in rax
-fn list_directory(pid: u32, arg: &mut generated::syscall_listdir_t) {
-    let process = PROCESSES.process(pid);
+/// Wakes the threads of the parent of the exited process `pid` that wait for a child.
+/// They reap it once they run again, the exiting process may still be in a syscall.
+fn notify_parent(pid: u32) {
+    let Some(parent) = PROCESSES.process(pid).parent() else {
+        return;
+    };
 
-    let path = copy_string_t_from_user(arg.path);
+    PROCESSES.process_mut(parent).child_waiters.wake_deferred();
+}
 
-    let mut fs = crate::FILE_SYSTEM
-        .try_lock()
-        .expect("Failed to lock file system");
+/// Removes the zombie `pid` and returns its exit status
+fn reap(pid: u32) -> u32 {
+    let mut processes = PROCESSES.processes_mut();
+    let index = processes
+        .iter()
+        .position(|p| p.pid() == pid)
+        .unwrap_or_else(|| panic!("process {pid} not found"));
+    let process = processes.remove(index).expect("index is in bounds");
+    drop(processes);
 
-    drop(process); // Drop the process to avoid borrowing issues
+    log::debug!("process {pid} was reaped");
+
+    process
+        .exit_status()
+        .unwrap_or_else(|| panic!("process {pid} has not exited"))
+}
+
+/// Writes the result of the blocked syscall of thread `tid` to `address` and makes the thread ready again
+fn complete<T: Copy>(tid: u32, address: u64, result: &T) {
+    let pid = PROCESSES
+        .thread(tid)
+        .pid()
+        .expect("kernel threads do not make syscalls");
+    let result = UserMemory::of(pid).write(address, result);
+
+    finish(tid, result.map_err(SyscallError::from));
+}
+
+/// Makes thread `tid` ready again with the outcome of its blocked syscall in rax
+fn finish(tid: u32, result: Result<(), SyscallError>) {
+    let mut thread = PROCESSES.thread_mut(tid);
+    thread.registers.rax = u64::from(match result {
+        Ok(()) => generated::SYSCALL_ERROR_NONE,
+        Err(error) => {
+            log::debug!("blocked syscall of thread {tid} failed: {error:x?}");
+            error.code()
+        }
+    });
+    thread.set_state(crate::process::State::Ready);
+}
 
-    let Some(dir) = fs.directory_mut(&path) else {
+/// Get the name of the entry at index rdx in the directory at path in rbx
+/// Returns the name to the pointer in r8 and the length of the name in rax
+fn list_directory(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_listdir_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+
+    let Ok(entries) = crate::FILE_SYSTEM
+        .lookup(&path)
+        .and_then(|dir| dir.readdir())
+    else {
         log::debug!("Directory not found: {path}");
         arg.return_value.entries_count = 0;
         arg.return_value.error = generated::SYSCALL_LISTDIR_ERROR_NOT_FOUND;
-        return;
+        return Ok(());
     };
 
-    if dir.files().len() + dir.directories().len() > arg.entries_len as usize {
+    if entries.len() > arg.entries_len as usize {
         log::debug!(
             "Directory {path} has more entries than the buffer can hold: {} > {}",
-            dir.files().len() + dir.directories().len(),
+            entries.len(),
             arg.entries_len
         );
         arg.return_value.entries_count = 0;
         arg.return_value.error = generated::SYSCALL_LISTDIR_ERROR_BUFFER_TOO_SMALL;
-        return;
+        return Ok(());
     }
 
     let mut i = 0;
 
-    for name in dir.files().keys() {
-        let entry = unsafe { &mut *arg.entries.add(i) };
-        if name.len() > entry.name.len as usize {
-            log::debug!(
-                "File name '{name}' is too long for the entry at index {i}: {} > {}",
-                name.len(),
-                entry.name.len
-            );
-            arg.return_value.entries_count = 0;
-            arg.return_value.error = generated::SYSCALL_LISTDIR_ERROR_BUFFER_TOO_SMALL;
-            return;
-        }
-
-        unsafe {
-            core::ptr::copy_nonoverlapping(name.as_ptr(), entry.name.ptr.cast::<u8>(), name.len());
-        }
-
-        entry.name.len = name.len() as u32;
-        entry.type_ = generated::SYSCALL_LISTDIR_ENTRY_TYPE_FILE;
-        i += 1;
-    }
-
-    for name in dir.directories().keys() {
-        let entry = unsafe { &mut *arg.entries.add(i) };
+    for crate::vfs::DirEntry { name, file_type } in entries {
+        let entry_address =
+            arg.entries as u64 + (i * size_of::<generated::syscall_listdir_entry_t>()) as u64;
+        let mut entry = user.read::<generated::syscall_listdir_entry_t>(entry_address)?;
         if name.len() > entry.name.len as usize {
             log::debug!(
-                "Directory name '{name}' is too long for the entry at index {i}: {} > {}",
+                "Name '{name}' is too long for the entry at index {i}: {} > {}",
                 name.len(),
                 entry.name.len
             );
             arg.return_value.entries_count = 0;
             arg.return_value.error = generated::SYSCALL_LISTDIR_ERROR_BUFFER_TOO_SMALL;
-            return;
+            return Ok(());
         }
 
-        unsafe {
-            core::ptr::copy_nonoverlapping(name.as_ptr(), entry.name.ptr.cast::<u8>(), name.len());
-        }
+        user.write_bytes(entry.name.ptr as u64, name.as_bytes())?;
 
         entry.name.len = name.len() as u32;
-        entry.type_ = generated::SYSCALL_LISTDIR_ENTRY_TYPE_DIR;
+        entry.type_ = match file_type {
+            crate::vfs::FileType::Directory => generated::SYSCALL_LISTDIR_ENTRY_TYPE_DIR,
+            _ => generated::SYSCALL_LISTDIR_ENTRY_TYPE_FILE,
+        };
+        user.write(entry_address, &entry)?;
         i += 1;
     }
 
     arg.return_value.entries_count = i as u32;
     arg.return_value.error = generated::SYSCALL_LISTDIR_ERROR_NONE;
+
+    Ok(())
 }
 
 /// Read from the file descriptor in rbx into the buffer in rcx with length rdx
-fn read(pid: u32, arg: &mut generated::syscall_read_t) {
+fn read(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_read_t,
+) -> Result<(), SyscallError> {
     log::trace!(
         "syscall_handler: read fd {}, buffer {:x}, length {}",
         arg.fd,
@@ -157,347 +281,878 @@ fn read(pid: u32, arg: &mut generated::syscall_read_t) {
         arg.len
     );
 
-    let mut process = crate::process::PROCESSES.process_mut(pid);
-    let Some(fd) = process.file_descriptor_mut(arg.fd) else {
+    let Some(file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
         log::debug!(
             "pid {pid}, invalid file descriptor: {arg:x?} ({:#x})",
             core::ptr::from_ref(arg) as u64
         );
         arg.return_value.bytes_read = 0;
         arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
-        return;
+        return Ok(());
     };
+    let mut fd = file.try_lock().expect("Failed to lock file descriptor");
 
-    match fd {
-        crate::process::FileDescriptor::Regular { path, offset } => {
-            let offset = *offset;
-            let path = path.clone();
-            drop(process);
-
-            let mut fs = crate::FILE_SYSTEM
-                .try_lock()
-                .expect("Failed to lock file system");
-            let file = fs.file_mut(&path).expect("File not found");
-
-            let mut buffer = vec![0; arg.len as usize];
+    match &mut *fd {
+        crate::process::FileDescriptor::Regular {
+            readable: false, ..
+        } => {
+            log::debug!("pid {pid}, fd {} is not open for reading", arg.fd);
+            arg.return_value.bytes_read = 0;
+            arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
+        }
+        crate::process::FileDescriptor::Regular { inode, offset, .. } => {
+            let mut buffer = vec![0; (arg.len as usize).min(MAX_TRANSFER)];
 
-            let read_result = file.read(offset, crate::io::Cursor::new(&mut buffer));
+            let read_result = inode.read(*offset, &mut crate::io::Cursor::new(&mut buffer));
 
             log::trace!(
                 "read_result for file descriptor {:?}: {read_result:?}",
                 arg.fd
             );
 
-            PROCESSES.with_process_mut(pid, |p| {
-                let crate::process::FileDescriptor::Regular { offset, .. } = p
-                    .file_descriptor_mut(arg.fd)
-                    .expect("File descriptor not found")
-                else {
-                    panic!("Expected a regular file descriptor")
-                };
-
-                match read_result {
-                    Ok(n) => {
-                        unsafe {
-                            core::ptr::copy_nonoverlapping(
-                                buffer.as_ptr(),
-                                arg.buf.cast::<u8>(),
-                                n,
-                            );
-                        }
-                        *offset += n;
-
-                        arg.return_value.bytes_read = n as u32;
-                        arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
-                    }
-                    Err(e) => {
-                        log::debug!("Failed to read from file descriptor {:?}: {e}", arg.fd);
-                        arg.return_value.bytes_read = 0;
-                        arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
-                    }
+            match read_result {
+                Ok(n) => {
+                    user.write_bytes(arg.buf as u64, &buffer[..n])?;
+                    *offset += n;
+
+                    arg.return_value.bytes_read = n as u32;
+                    arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
                 }
-            });
-        }
-        crate::process::FileDescriptor::OwnedStream { buffer, .. } => {
-            if buffer.is_empty() {
-                if (arg.options & generated::SYSCALL_READ_OPTION_NON_BLOCKING) != 0 {
+                Err(e) => {
+                    log::debug!("Failed to read from file descriptor {:?}: {e}", arg.fd);
                     arg.return_value.bytes_read = 0;
-                    arg.return_value.error = generated::SYSCALL_READ_OPTION_NONE;
-                    process.state = crate::process::State::Ready;
-                } else {
-                    process.state = crate::process::State::WaitingForStream(arg.fd);
+                    arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
                 }
-            } else {
-                let bytes = buffer.drain(0..(arg.len as usize).min(buffer.len()));
-                let len = bytes.len();
+            }
+        }
+        crate::process::FileDescriptor::OwnedStream { .. }
+        | crate::process::FileDescriptor::ForeignStream { .. }
+        | crate::process::FileDescriptor::Pipe { .. } => {
+            drop(fd);
+            read_stream(pid, tid, user, arg)?;
+        }
+        crate::process::FileDescriptor::Device { device, .. } if device.input().is_some() => {
+            drop(fd);
+            read_stream(pid, tid, user, arg)?;
+        }
+        crate::process::FileDescriptor::Device { device, offset } => {
+            let mut buffer = vec![0; (arg.len as usize).min(MAX_TRANSFER)];
 
-                for (i, byte) in bytes.enumerate() {
-                    unsafe { arg.buf.cast::<u8>().add(i).write_volatile(byte) };
+            match device.read(*offset, &mut buffer) {
+                Ok(n) => {
+                    user.write_bytes(arg.buf as u64, &buffer[..n])?;
+                    *offset += n;
 
-                    log::trace!("Read {i} bytes from stdin");
+                    arg.return_value.bytes_read = n as u32;
+                    arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+                }
+                Err(e) => {
+                    log::debug!("Failed to read from {device:?}: {e}");
+                    arg.return_value.bytes_read = 0;
+                    arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
                 }
+            }
+        }
+    }
 
-                arg.return_value.bytes_read = len as u32;
-                arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+    Ok(())
+}
 
-                process.state = crate::process::State::Ready;
-            }
+/// Reads from the stream or pipe behind the file descriptor, foreign streams are resolved first.
+/// If the stream is empty, the thread waits in the wait queue of the stream until data is written to it.
+/// Returns whether the thread is waiting.
+fn read_stream(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_read_t,
+) -> Result<bool, SyscallError> {
+    let Some(file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
+        // the file descriptor was closed while the thread was waiting
+        log::debug!("pid {pid}, file descriptor {} is not open", arg.fd);
+        arg.return_value.bytes_read = 0;
+        arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
+        return Ok(false);
+    };
+
+    let fd = file.try_lock().expect("Failed to lock file descriptor");
+    let stream = match &*fd {
+        crate::process::FileDescriptor::Pipe { end } if end.side() == PipeSide::Read => {
+            let pipe = end.pipe();
+            drop(fd);
+            return read_pipe(tid, user, &pipe, arg);
         }
-        crate::process::FileDescriptor::ForeignStream { stream_type } => match stream_type {
-            &mut crate::process::ForeignStreamType::Process {
-                pid: other_pid,
-                file_descriptor,
-            } => {
-                drop(process);
-                let mut other_process = PROCESSES.process_mut(other_pid);
-                let fd = other_process
-                    .file_descriptor_mut(file_descriptor)
-                    .expect("Foreign stream file descriptor not found");
-
-                match fd {
-                    crate::process::FileDescriptor::Regular { .. } => todo!(),
-                    crate::process::FileDescriptor::ForeignStream { .. } => todo!(),
-                    crate::process::FileDescriptor::OwnedStream { buffer, .. } => {
-                        if buffer.is_empty() {
-                            drop(other_process);
-                            if (arg.options & generated::SYSCALL_READ_OPTION_NON_BLOCKING) != 0 {
-                                arg.return_value.bytes_read = 0;
-                                arg.return_value.error = generated::SYSCALL_READ_OPTION_NONE;
-                                PROCESSES.process_mut(pid).state = crate::process::State::Ready;
-                            } else {
-                                PROCESSES.process_mut(pid).state =
-                                    crate::process::State::WaitingForStream(arg.fd);
-                            }
-                        } else {
-                            let bytes = buffer.drain(0..(arg.len as usize).min(buffer.len()));
-                            let len = bytes.len();
-
-                            for (i, byte) in bytes.enumerate() {
-                                unsafe {
-                                    arg.buf.cast::<u8>().add(i).write_volatile(byte);
-                                }
-                            }
-
-                            arg.return_value.bytes_read = len as u32;
-                            arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
-
-                            drop(other_process);
-                            PROCESSES.process_mut(pid).state = crate::process::State::Ready;
-                        }
-                    }
-                    crate::process::FileDescriptor::Terminal => todo!(),
-                    crate::process::FileDescriptor::Serial => todo!(),
+        &crate::process::FileDescriptor::Device { device, .. } if device.input().is_some() => {
+            drop(fd);
+            return read_input(tid, user, device, arg);
+        }
+        crate::process::FileDescriptor::OwnedStream { .. } => Some(alloc::sync::Arc::clone(&file)),
+        &crate::process::FileDescriptor::ForeignStream { stream_type } => {
+            resolve_foreign_stream(stream_type)
+        }
+        _ => {
+            log::debug!("pid {pid}, file descriptor {} is not a stream", arg.fd);
+            arg.return_value.bytes_read = 0;
+            arg.return_value.error = generated::SYSCALL_READ_ERROR_INVALID_FD;
+            return Ok(false);
+        }
+    };
+    drop(fd);
+
+    let Some(stream) = stream else {
+        // the process owning the stream exited, so it ended
+        arg.return_value.bytes_read = 0;
+        arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+        return Ok(false);
+    };
+
+    let mut fd = stream.try_lock().expect("Failed to lock file descriptor");
+    let crate::process::FileDescriptor::OwnedStream {
+        buffer, readers, ..
+    } = &mut *fd
+    else {
+        unreachable!("foreign streams resolve to owned streams")
+    };
+
+    if buffer.is_empty() {
+        arg.return_value.bytes_read = 0;
+        arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+
+        if (arg.options & generated::SYSCALL_READ_OPTION_NON_BLOCKING) != 0 {
+            return Ok(false);
+        }
+
+        readers.push(tid);
+        drop(fd);
+
+        // the result is written when the thread is woken
+        let arg_address = PROCESSES.thread(tid).registers.rbx;
+        PROCESSES
+            .thread_mut(tid)
+            .set_state(crate::process::State::WaitingForStream {
+                fd: arg.fd,
+                arg: arg_address,
+            });
+        return Ok(true);
+    }
+
+    let bytes = buffer
+        .iter()
+        .take(arg.len as usize)
+        .copied()
+        .collect::<alloc::vec::Vec<_>>();
+
+    // the bytes stay in the stream if the buffer cannot be written
+    user.write_bytes(arg.buf as u64, &bytes)?;
+    buffer.drain(..bytes.len());
+    drop(fd);
+    log::trace!("Read {} bytes from stream {}", bytes.len(), arg.fd);
+
+    arg.return_value.bytes_read = bytes.len() as u32;
+    arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+
+    Ok(false)
+}
+
+/// Reads from the input of a device, the thread waits in the wait queue of the input until something arrives.
+/// Returns whether the thread is waiting.
+fn read_input(
+    tid: u32,
+    user: &UserMemory,
+    device: &'static dyn crate::driver::device::Device,
+    arg: &mut generated::syscall_read_t,
+) -> Result<bool, SyscallError> {
+    let mut input = device
+        .input()
+        .expect("only devices with input are read as streams")
+        .try_lock()
+        .expect("Failed to lock device input");
+
+    let Some(bytes) = input.peek(arg.len as usize) else {
+        arg.return_value.bytes_read = 0;
+        arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+
+        if (arg.options & generated::SYSCALL_READ_OPTION_NON_BLOCKING) != 0 {
+            return Ok(false);
+        }
+
+        input.readers.push(tid);
+        drop(input);
+
+        // the result is written when the thread is woken
+        let arg_address = PROCESSES.thread(tid).registers.rbx;
+        PROCESSES
+            .thread_mut(tid)
+            .set_state(crate::process::State::WaitingForStream {
+                fd: arg.fd,
+                arg: arg_address,
+            });
+        return Ok(true);
+    };
+
+    // the bytes stay in the input if the buffer cannot be written
+    user.write_bytes(arg.buf as u64, &bytes)?;
+    input.consume(bytes.len());
+    drop(input);
+    log::trace!("Read {} bytes from {device:?}", bytes.len());
+
+    arg.return_value.bytes_read = bytes.len() as u32;
+    arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+
+    Ok(false)
+}
+
+/// Reads from a pipe, the thread waits in the wait queue of the pipe while it is empty and still has writers.
+/// Returns whether the thread is waiting.
+fn read_pipe(
+    tid: u32,
+    user: &UserMemory,
+    pipe: &spin::Mutex<crate::process::pipe::Pipe>,
+    arg: &mut generated::syscall_read_t,
+) -> Result<bool, SyscallError> {
+    let mut pipe = pipe.try_lock().expect("Failed to lock pipe");
+
+    let Some(bytes) = pipe.peek(arg.len as usize) else {
+        arg.return_value.bytes_read = 0;
+        arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+
+        if (arg.options & generated::SYSCALL_READ_OPTION_NON_BLOCKING) != 0 {
+            return Ok(false);
+        }
+
+        pipe.readers.push(tid);
+        drop(pipe);
+
+        // the result is written when the thread is woken
+        let arg_address = PROCESSES.thread(tid).registers.rbx;
+        PROCESSES
+            .thread_mut(tid)
+            .set_state(crate::process::State::WaitingForStream {
+                fd: arg.fd,
+                arg: arg_address,
+            });
+        return Ok(true);
+    };
+
+    // the bytes stay in the pipe if the buffer cannot be written
+    user.write_bytes(arg.buf as u64, &bytes)?;
+    pipe.consume(bytes.len());
+
+    // writers waiting for room can continue
+    let woken = pipe.writers.take();
+    drop(pipe);
+    crate::process::wake(woken);
+
+    log::trace!("Read {} bytes from pipe {}", bytes.len(), arg.fd);
+
+    arg.return_value.bytes_read = bytes.len() as u32;
+    arg.return_value.error = generated::SYSCALL_READ_ERROR_NONE;
+
+    Ok(false)
+}
+
+/// Writes to the pipe behind the file descriptor, as much as fits into it.
+/// If the pipe is full, the thread waits in the wait queue of the pipe until data is read from it.
+/// Returns whether the thread is waiting.
+fn write_pipe(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_write_t,
+) -> Result<bool, SyscallError> {
+    let pipe = PROCESSES
+        .process(pid)
+        .file_descriptor(arg.fd)
+        .and_then(|file| {
+            let fd = file.try_lock().expect("Failed to lock file descriptor");
+            match &*fd {
+                crate::process::FileDescriptor::Pipe { end } if end.side() == PipeSide::Write => {
+                    Some(end.pipe())
                 }
+                _ => None,
             }
-        },
-        crate::process::FileDescriptor::Terminal => todo!(),
-        crate::process::FileDescriptor::Serial => todo!(),
+        });
+    let Some(pipe) = pipe else {
+        // the file descriptor was closed while the thread was waiting, or it is a read end
+        log::debug!(
+            "pid {pid}, file descriptor {} is not a writable pipe",
+            arg.fd
+        );
+        arg.return_value.bytes_written = 0;
+        arg.return_value.error = generated::SYSCALL_WRITE_ERROR_INVALID_FD;
+        return Ok(false);
+    };
+    let mut pipe = pipe.try_lock().expect("Failed to lock pipe");
+
+    let Ok(room) = pipe.room() else {
+        log::debug!("pid {pid}, all read ends of pipe {} are closed", arg.fd);
+        arg.return_value.bytes_written = 0;
+        arg.return_value.error = generated::SYSCALL_WRITE_ERROR_BROKEN_PIPE;
+        return Ok(false);
+    };
+
+    if room == 0 {
+        pipe.writers.push(tid);
+        drop(pipe);
+
+        // the result is written when the thread is woken
+        let arg_address = PROCESSES.thread(tid).registers.rbx;
+        PROCESSES
+            .thread_mut(tid)
+            .set_state(crate::process::State::WaitingForRoom {
+                fd: arg.fd,
+                arg: arg_address,
+            });
+        return Ok(true);
+    }
+
+    let bytes = user.read_bytes(arg.buf as u64, (arg.len as usize).min(room))?;
+    pipe.write(&bytes);
+
+    let woken = pipe.readers.take();
+    drop(pipe);
+    crate::process::wake(woken);
+
+    arg.return_value.bytes_written = bytes.len() as u32;
+    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
+
+    Ok(false)
+}
+
+/// Continues the read of thread `tid` that blocked on an empty stream or pipe once it was woken.
+/// The thread waits again if another reader took the data first.
+pub fn resume_read(tid: u32) {
+    let crate::process::State::WaitingForStream { arg: address, .. } =
+        PROCESSES.thread(tid).state()
+    else {
+        return;
+    };
+
+    resume(tid, address, read_stream);
+}
+
+/// Continues the write of thread `tid` that blocked on a full pipe once it was woken.
+/// The thread waits again if another writer filled the pipe first.
+pub fn resume_write(tid: u32) {
+    let crate::process::State::WaitingForRoom { arg: address, .. } = PROCESSES.thread(tid).state()
+    else {
+        return;
+    };
+
+    resume(tid, address, write_pipe);
+}
+
+/// Runs the blocking part of a syscall again with its argument at `address`,
+/// then completes the syscall unless the thread has to wait again
+fn resume<T: Copy>(
+    tid: u32,
+    address: u64,
+    handler: fn(u32, u32, &UserMemory, &mut T) -> Result<bool, SyscallError>,
+) {
+    let pid = PROCESSES
+        .thread(tid)
+        .pid()
+        .expect("kernel threads do not make syscalls");
+
+    let user = UserMemory::of(pid);
+    let result = user
+        .read::<T>(address)
+        .and_then(|mut arg| Ok((handler(pid, tid, &user, &mut arg)?, arg)));
+
+    match result {
+        Ok((true, _)) => {}
+        Ok((false, arg)) => complete(tid, address, &arg),
+        Err(error) => finish(tid, Err(error)),
     }
 }
 
 /// Fork the current process
-/// The new process will have the same registers and state as the current process
+/// The new process has a single thread with the same registers and state as the calling thread
 /// The new process will be added to the process list
 /// return the pid of each process in rax
-fn fork(pid: u32, arg: &mut generated::syscall_fork_t) {
-    let new_process = PROCESSES.process(pid).fork();
+fn fork(pid: u32, tid: u32, arg: &mut generated::syscall_fork_t) {
+    let thread = PROCESSES.thread(tid);
+    let (new_process, mut new_thread) = PROCESSES.process_mut(pid).fork(&thread);
+    drop(thread);
 
-    // return 0 in the new process
+    // return 0 in the new process, its copy of the argument is never written
+    new_thread.registers.rax = u64::from(generated::SYSCALL_ERROR_NONE);
     let new_pid = new_process.pid();
-    PROCESSES.add_process(new_process);
+    PROCESSES.add_process(new_process, new_thread);
 
     // return the pid of the new process in rax
-    let process = PROCESSES.process_mut(pid);
-    process.load_paging();
+    PROCESSES.process(pid).load_paging();
     arg.return_value.child_pid = new_pid;
 }
 
-/// Open a file at the path in rbx, with the length in rcx
+/// Open a file at the path in rbx, with the length in rcx, creating or truncating it as requested by the mode
 /// Returns the file descriptor in rax
-fn open(pid: u32, arg: &mut generated::syscall_open_t) {
-    let path = copy_string_t_from_user(arg.path);
-
-    log::trace!("syscall_handler: open '{path}'");
-
-    if let Some(file) = crate::FILE_SYSTEM
-        .try_lock()
-        .expect("Failed to lock file system")
-        .file_mut(&path)
-    {
-        let mut process = PROCESSES.process_mut(pid);
-
-        let fd = match file {
-            &mut crate::vfs::File::OwnedStream { stream_type } => {
-                crate::process::FileDescriptor::OwnedStream {
-                    buffer: alloc::collections::VecDeque::with_capacity(1024),
-                    max_size: 1024,
-                    stream_type,
-                }
+fn open(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_open_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+    let mode = arg.mode;
+
+    log::trace!("syscall_handler: open '{path}', mode {mode:#x}");
+
+    let writable = mode & generated::SYSCALL_OPEN_MODE_WRITE != 0;
+    let inode = crate::FILE_SYSTEM
+        .lookup(&path)
+        .or_else(|e| match e {
+            crate::vfs::Error::NotFound if mode & generated::SYSCALL_OPEN_MODE_CREATE != 0 => {
+                crate::FILE_SYSTEM.create(&path, crate::vfs::FileType::Regular)
             }
-            crate::vfs::File::Regular { .. } | crate::vfs::File::Special { .. } => {
-                crate::process::FileDescriptor::Regular { path, offset: 0 }
+            e => Err(e),
+        })
+        .and_then(|inode| {
+            if writable && inode.stat().file_type == crate::vfs::FileType::Directory {
+                return Err(crate::vfs::Error::IsADirectory);
             }
-            &mut crate::vfs::File::ForeignStream { stream_type } => {
-                crate::process::FileDescriptor::ForeignStream { stream_type }
+            if writable && mode & generated::SYSCALL_OPEN_MODE_TRUNCATE != 0 {
+                inode.truncate(0)?;
             }
-        };
-
-        let fd = process.new_file_descriptor(fd);
+            Ok(inode)
+        });
 
-        arg.return_value.fd = fd;
-        arg.return_value.error = generated::SYSCALL_OPEN_ERROR_NONE;
-    } else {
-        log::debug!("File not found: {path}");
+    match inode {
+        Ok(inode) => {
+            let fd = inode
+                .open()
+                .unwrap_or(crate::process::FileDescriptor::Regular {
+                    path,
+                    inode,
+                    offset: 0,
+                    readable: mode & generated::SYSCALL_OPEN_MODE_READ != 0,
+                    writable,
+                    append: mode & generated::SYSCALL_OPEN_MODE_APPEND != 0,
+                });
+
+            let fd = PROCESSES.process_mut(pid).new_file_descriptor(
+                fd,
+                arg.flags & generated::SYSCALL_FD_FLAG_CLOSE_ON_EXEC != 0,
+            );
 
-        arg.return_value.fd = -1; // indicate error
-        arg.return_value.error = generated::SYSCALL_OPEN_ERROR_NOT_FOUND;
+            arg.return_value.fd = fd;
+            arg.return_value.error = generated::SYSCALL_OPEN_ERROR_NONE;
+        }
+        Err(e) => {
+            log::debug!("Cannot open {path}: {e}");
+
+            arg.return_value.fd = -1; // indicate error
+            arg.return_value.error = match e {
+                crate::vfs::Error::IsADirectory => generated::SYSCALL_OPEN_ERROR_IS_A_DIRECTORY,
+                crate::vfs::Error::NotADirectory => generated::SYSCALL_OPEN_ERROR_NOT_A_DIRECTORY,
+                crate::vfs::Error::NotFound => generated::SYSCALL_OPEN_ERROR_NOT_FOUND,
+                _ => generated::SYSCALL_OPEN_ERROR_NOT_SUPPORTED,
+            };
+        }
     }
+
+    Ok(())
 }
 
 /// Close the file descriptor in rbx
 /// Returns 0 in rax on success, -1 on error
-fn close(pid: u32, arg: &mut generated::syscall_close_t) {
-    let mut process = PROCESSES.process_mut(pid);
+fn close(pid: u32, _tid: u32, arg: &mut generated::syscall_close_t) {
+    let file = PROCESSES.process_mut(pid).close_file_descriptor(arg.fd);
 
-    if process.close_file_descriptor(arg.fd).is_some() {
+    if let Some(file) = file {
         log::trace!("syscall_handler: closed file descriptor {}", arg.fd);
+        closed(file);
 
         arg.return_value.error = generated::SYSCALL_CLOSE_ERROR_NONE;
     } else {
-        log::debug!("Failed to close file descriptor {}", process.registers.rbx);
+        log::debug!("Failed to close file descriptor {}", arg.fd);
         arg.return_value.error = generated::SYSCALL_CLOSE_ERROR_INVALID_FD;
     }
 }
 
-/// map a new page into the process and return the address in rax
-/// rbx contains the address to map to or 0
-/// return the address in rax, length in rbx
-fn mmap(pid: u32, arg: &mut generated::syscall_mmap_t) {
-    const START_ADDRESS: u64 = 0x6942_0000_0000;
-
-    assert!(arg.size == 4096, "mmap only supports mapping 4096 bytes");
+/// Wakes the threads reading from an open file that a file descriptor no longer refers to.
+/// Readers using the closed file descriptor fail, the others wait again if the file is still open.
+fn closed(file: crate::process::OpenFile) {
+    let woken = match &mut *file.try_lock().expect("Failed to lock file descriptor") {
+        crate::process::FileDescriptor::OwnedStream { readers, .. } => readers.take(),
+        _ => alloc::vec::Vec::new(),
+    };
+    drop(file);
 
-    let mut process = PROCESSES.process_mut(pid);
+    crate::process::wake(woken);
+}
 
-    let address = process
-        .mapped_pages
-        .iter()
-        .filter(|&m| m.page.start_address().as_u64() >= START_ADDRESS)
-        .max_by_key(|&m| m.page.start_address().as_u64())
-        .map_or(START_ADDRESS, |&m| m.page.start_address().as_u64() + 0x1000);
-    let page = Page::containing_address(x86_64::VirtAddr::new(address));
+/// Duplicate the file descriptor in the argument to the lowest free file descriptor
+fn dup(pid: u32, _tid: u32, arg: &mut generated::syscall_dup_t) {
+    let close_on_exec = arg.flags & generated::SYSCALL_FD_FLAG_CLOSE_ON_EXEC != 0;
+
+    let Some((new_fd, _)) =
+        PROCESSES
+            .process_mut(pid)
+            .duplicate_file_descriptor(arg.fd, None, close_on_exec)
+    else {
+        log::debug!("dup: invalid file descriptor {}", arg.fd);
+        arg.return_value.fd = -1;
+        arg.return_value.error = generated::SYSCALL_DUP_ERROR_INVALID_FD;
+        return;
+    };
 
     log::trace!(
-        "mmap process {}, address {address:#x}, page {page:?}",
-        process.pid()
+        "syscall_handler: duplicated file descriptor {} to {new_fd}",
+        arg.fd
     );
 
-    let mut kernel_paging = crate::kernel_paging();
-    let phys_frame = kernel_paging
-        .allocate_frame()
-        .expect("Failed to allocate frame");
-
-    let frame_virt_addr =
-        kernel_paging.page_table().phys_offset() + phys_frame.start_address().as_u64();
-    (unsafe { *frame_virt_addr.as_mut_ptr::<[u8; 4096]>() }).fill(0);
-
-    let flags = PageTableFlags::PRESENT
-        | PageTableFlags::USER_ACCESSIBLE
-        | PageTableFlags::WRITABLE
-        | PageTableFlags::NO_EXECUTE;
-
-    unsafe {
-        process
-            .paging
-            .page_table
-            .map_to(page, phys_frame, flags, &mut *kernel_paging)
-            .expect("Failed to map page")
-            .flush();
-    }
-
-    process.mapped_pages.push(MappedPage {
-        name: "heap",
-        page,
-        flags,
-    });
-
-    arg.return_value.addr = address as *mut _;
-    arg.return_value.error = generated::SYSCALL_MMAP_ERROR_NONE;
+    arg.return_value.fd = new_fd;
+    arg.return_value.error = generated::SYSCALL_DUP_ERROR_NONE;
 }
 
-/// Unmap the page at the address in rbx
-fn munmap(pid: u32, arg: &mut generated::syscall_munmap_t) {
-    let mut process = PROCESSES.process_mut(pid);
+/// Make the second file descriptor in the argument refer to the open file of the first one,
+/// closing it first if it is open
+fn dup2(pid: u32, _tid: u32, arg: &mut generated::syscall_dup2_t) {
+    let close_on_exec = arg.flags & generated::SYSCALL_FD_FLAG_CLOSE_ON_EXEC != 0;
+
+    let duplicated = if arg.new_fd < 0 {
+        None
+    } else {
+        PROCESSES.process_mut(pid).duplicate_file_descriptor(
+            arg.fd,
+            Some(arg.new_fd),
+            close_on_exec,
+        )
+    };
 
-    let page = Page::<Size4KiB>::containing_address(x86_64::VirtAddr::new(arg.addr as u64));
+    let Some((new_fd, replaced)) = duplicated else {
+        log::debug!("dup2: invalid file descriptor {} or {}", arg.fd, arg.new_fd);
+        arg.return_value.fd = -1;
+        arg.return_value.error = generated::SYSCALL_DUP2_ERROR_INVALID_FD;
+        return;
+    };
 
-    log::debug!(
-        "munmap process {}, address {:#x}, page {page:?}",
-        process.pid(),
-        arg.addr as u64,
+    log::trace!(
+        "syscall_handler: duplicated file descriptor {} to {new_fd}",
+        arg.fd
     );
 
-    match process.paging.page_table.unmap(page) {
-        Ok((frame, flush)) => {
-            flush.flush();
+    if let Some(file) = replaced {
+        closed(file);
+    }
 
-            let mut kernel_paging = crate::kernel_paging();
-            unsafe { kernel_paging.deallocate_frame(frame) };
+    arg.return_value.fd = new_fd;
+    arg.return_value.error = generated::SYSCALL_DUP2_ERROR_NONE;
+}
 
-            arg.return_value.error = generated::SYSCALL_MUNMAP_ERROR_NONE;
-        }
-        Err(e) => {
-            log::warn!("Failed to unmap page at {:#x}: {e:?}", arg.addr as u64);
-            process.state = crate::process::State::Terminated(2);
+fn protection_flags(prot: u32) -> PageTableFlags {
+    let mut flags = PageTableFlags::PRESENT;
+    // pages without any access stay mapped, but only for the kernel
+    if prot != generated::SYSCALL_MMAP_PROT_NONE {
+        flags |= PageTableFlags::USER_ACCESSIBLE;
+    }
+    if prot & generated::SYSCALL_MMAP_PROT_WRITE != 0 {
+        flags |= PageTableFlags::WRITABLE;
+    }
+    if prot & generated::SYSCALL_MMAP_PROT_EXEC == 0 {
+        flags |= PageTableFlags::NO_EXECUTE;
+    }
+    flags
+}
 
-            arg.return_value.error = generated::SYSCALL_MUNMAP_ERROR_INVALID_ADDR;
-        }
+/// Returns the page-aligned range `addr..addr + size` if it is a valid range of user memory
+fn user_range(addr: u64, size: u64) -> Option<(x86_64::VirtAddr, x86_64::VirtAddr)> {
+    let end = addr
+        .checked_add(size)?
+        .checked_next_multiple_of(Size4KiB::SIZE)?;
+    if !addr.is_multiple_of(Size4KiB::SIZE) || end > crate::process::memory::USER_SPACE_END {
+        return None;
     }
 
-    process.mapped_pages.retain(|m| m.page != page);
+    Some((x86_64::VirtAddr::new(addr), x86_64::VirtAddr::new(end)))
 }
 
-/// Execute a new program at the path in rbx (length in rcx), with the number of arguments in rdx
-/// arguments in r8 is a pointer to the list of length-prefixed strings
-fn execve(pid: u32, arg: &mut generated::syscall_execve_t) {
-    let path = copy_string_t_from_user(arg.path);
+/// Map `size` bytes of anonymous memory into the process, at `addr` if `SYSCALL_MMAP_FLAG_FIXED`
+/// is set, otherwise `addr` is only a hint. The pages are populated when they are first touched,
+/// except for shared mappings, which have to exist before the process forks.
+fn mmap(pid: u32, _tid: u32, arg: &mut generated::syscall_mmap_t) {
+    const START_ADDRESS: u64 = 0x6942_0000_0000;
 
-    let mut argv = vec![String::new(); arg.argv_len as usize];
-    for i in 0..arg.argv_len as usize {
-        argv[i] = copy_string_t_from_user(unsafe { arg.argv.add(i).read_volatile() });
-    }
+    let (hint, size, prot, flags) = (arg.addr as u64, arg.size, arg.prot, arg.flags);
 
-    log::debug!(
-        "syscall_handler: execve '{}', argc: {}, argv: {}",
-        path,
-        arg.argv_len,
-        argv.join(", ")
-    );
+    if size == 0 || size > crate::process::memory::USER_SPACE_END {
+        arg.return_value.error = generated::SYSCALL_MMAP_ERROR_INVALID_SIZE;
+        return;
+    }
+    if flags & generated::SYSCALL_MMAP_FLAG_ANONYMOUS == 0 {
+        log::debug!("mmap: only anonymous mappings are supported");
+        arg.return_value.error = generated::SYSCALL_MMAP_ERROR_INVALID_FLAGS;
+        return;
+    }
+    let fixed = flags & generated::SYSCALL_MMAP_FLAG_FIXED != 0;
+    let shared = flags & generated::SYSCALL_MMAP_FLAG_SHARED != 0;
 
-    match crate::FILE_SYSTEM
+    let process = PROCESSES.process(pid);
+    let mut memory = process
+        .memory
         .try_lock()
-        .expect("Failed to lock file system")
-        .file(&path)
-    {
-        Some(crate::vfs::File::Regular { contents }) => {
-            PROCESSES.process_mut(pid).execve(contents, &argv);
-        }
-        Some(_) => {
-            log::debug!("Cannot execute special file: {path}");
-            arg.return_value.error = generated::SYSCALL_EXECVE_ERROR_NOT_FOUND;
-        }
-        None => {
-            log::debug!("File not found: {path}");
-            arg.return_value.error = generated::SYSCALL_EXECVE_ERROR_NOT_FOUND;
+        .expect("Failed to lock address space");
+    let mut kernel_paging = crate::kernel_paging();
+
+    let range = if fixed {
+        let Some((start, end)) = user_range(hint, size) else {
+            arg.return_value.error = generated::SYSCALL_MMAP_ERROR_INVALID_ADDR;
+            return;
+        };
+
+        memory.unmap_range(&mut kernel_paging, start, end);
+        Some((start, end))
+    } else {
+        let from = if hint == 0 { START_ADDRESS } else { hint };
+        memory
+            .find_free(from.min(crate::process::memory::USER_SPACE_END), size)
+            .and_then(|start| user_range(start.as_u64(), size))
+    };
+
+    let Some((start, end)) = range else {
+        arg.return_value.error = generated::SYSCALL_MMAP_ERROR_NO_MEMORY;
+        return;
+    };
+
+    log::trace!(
+        "mmap process {}, range {start:#x} - {end:#x}, prot {prot:#x}, flags {flags:#x}",
+        process.pid()
+    );
+
+    memory.add_region(Region {
+        start,
+        end,
+        flags: protection_flags(prot),
+        name: if shared { "shared" } else { "mmap" },
+        backing: Backing::Anonymous,
+        shared,
+    });
+
+    if shared
+        && Page::range(
+            Page::containing_address(start),
+            Page::containing_address(end),
+        )
+        .any(|page| memory.try_populate(&mut kernel_paging, page).is_none())
+    {
+        log::debug!("mmap: out of frames for the shared region {start:#x} - {end:#x}");
+        memory.remove_region(&mut kernel_paging, start);
+        arg.return_value.error = generated::SYSCALL_MMAP_ERROR_NO_MEMORY;
+        return;
+    }
+
+    arg.return_value.addr = start.as_mut_ptr();
+    arg.return_value.error = generated::SYSCALL_MMAP_ERROR_NONE;
+}
+
+/// Unmap all pages in the range, regions that are only partially inside the range are split
+fn munmap(pid: u32, _tid: u32, arg: &mut generated::syscall_munmap_t) {
+    let (addr, size) = (arg.addr as u64, arg.size);
+
+    if size == 0 {
+        arg.return_value.error = generated::SYSCALL_MUNMAP_ERROR_INVALID_SIZE;
+        return;
+    }
+    let Some((start, end)) = user_range(addr, size) else {
+        arg.return_value.error = generated::SYSCALL_MUNMAP_ERROR_INVALID_ADDR;
+        return;
+    };
+
+    log::debug!("munmap process {pid}, range {start:#x} - {end:#x}");
+
+    let process = PROCESSES.process(pid);
+    let mut kernel_paging = crate::kernel_paging();
+    let unmapped = process
+        .memory
+        .try_lock()
+        .expect("Failed to lock address space")
+        .unmap_range(&mut kernel_paging, start, end);
+    drop(kernel_paging);
+    drop(process);
+
+    if unmapped {
+        arg.return_value.error = generated::SYSCALL_MUNMAP_ERROR_NONE;
+    } else {
+        log::debug!("munmap: nothing is mapped in {start:#x} - {end:#x}");
+        arg.return_value.error = generated::SYSCALL_MUNMAP_ERROR_INVALID_ADDR;
+    }
+}
+
+/// Change the protection of all pages in the range, which has to be mapped completely
+fn mprotect(pid: u32, _tid: u32, arg: &mut generated::syscall_mprotect_t) {
+    let (addr, size, prot) = (arg.addr as u64, arg.size, arg.prot);
+
+    if size == 0 {
+        arg.return_value.error = generated::SYSCALL_MPROTECT_ERROR_INVALID_SIZE;
+        return;
+    }
+    let Some((start, end)) = user_range(addr, size) else {
+        arg.return_value.error = generated::SYSCALL_MPROTECT_ERROR_INVALID_ADDR;
+        return;
+    };
+
+    log::debug!("mprotect process {pid}, range {start:#x} - {end:#x}, prot {prot:#x}");
+
+    let process = PROCESSES.process(pid);
+    let mut kernel_paging = crate::kernel_paging();
+    let protected = process
+        .memory
+        .try_lock()
+        .expect("Failed to lock address space")
+        .protect(&mut kernel_paging, start, end, protection_flags(prot));
+    drop(kernel_paging);
+    drop(process);
+
+    arg.return_value.error = if protected {
+        generated::SYSCALL_MPROTECT_ERROR_NONE
+    } else {
+        generated::SYSCALL_MPROTECT_ERROR_NOT_MAPPED
+    };
+}
+
+/// Move the end of the heap to the address in the argument, or return the current end if it is null
+fn brk(pid: u32, _tid: u32, arg: &mut generated::syscall_brk_t) {
+    let addr = arg.addr as u64;
+
+    let process = PROCESSES.process(pid);
+    let mut memory = process
+        .memory
+        .try_lock()
+        .expect("Failed to lock address space");
+
+    let error = if addr == 0 {
+        generated::SYSCALL_BRK_ERROR_NONE
+    } else if addr >= crate::process::memory::USER_SPACE_END
+        || x86_64::VirtAddr::new(addr) < memory.heap_start()
+    {
+        generated::SYSCALL_BRK_ERROR_INVALID_ADDR
+    } else {
+        let mut kernel_paging = crate::kernel_paging();
+        if memory.set_brk(&mut kernel_paging, x86_64::VirtAddr::new(addr)) {
+            generated::SYSCALL_BRK_ERROR_NONE
+        } else {
+            generated::SYSCALL_BRK_ERROR_NO_MEMORY
+        }
+    };
+
+    let brk = memory.brk();
+    log::trace!("brk process {pid}, requested {addr:#x}, break is {brk:#x}");
+
+    arg.return_value.addr = brk.as_mut_ptr();
+    arg.return_value.error = error;
+}
+
+/// Reads the `len` strings in the user array at `strings`
+/// The most bytes the arguments and environment of execve may take on the new stack, including their string structs
+const MAX_ARGUMENTS_SIZE: usize = 128 * 1024;
+
+/// Reads `len` strings, charging their size to `budget`.
+/// Returns `None` without reading the rest if they do not fit in it.
+fn read_strings(
+    user: &UserMemory,
+    strings: *mut generated::string_const_t,
+    len: u32,
+    budget: &mut usize,
+) -> Result<Option<alloc::vec::Vec<String>>, SyscallError> {
+    let string_size = size_of::<generated::string_const_t>();
+    if len as usize > *budget / string_size {
+        return Ok(None);
+    }
+
+    let mut result = alloc::vec::Vec::new();
+    for i in 0..len as usize {
+        let string: generated::string_const_t =
+            user.read(strings as u64 + (i * string_size) as u64)?;
+        let size = string.len as usize + string_size;
+        if size > *budget {
+            return Ok(None);
+        }
+        *budget -= size;
+        result.push(user.read_string(string)?);
+    }
+    Ok(Some(result))
+}
+
+/// Execute a new program at the path in rbx (length in rcx), with the number of arguments in rdx
+/// arguments in r8 is a pointer to the list of length-prefixed strings, the environment is passed the same way
+fn execve(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_execve_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+
+    let mut budget = MAX_ARGUMENTS_SIZE;
+    let Some(argv) = read_strings(user, arg.argv, arg.argv_len, &mut budget)? else {
+        arg.return_value.error = generated::SYSCALL_EXECVE_ERROR_ARGUMENTS_TOO_LONG;
+        return Ok(());
+    };
+    let Some(envp) = read_strings(user, arg.envp, arg.envp_len, &mut budget)? else {
+        arg.return_value.error = generated::SYSCALL_EXECVE_ERROR_ARGUMENTS_TOO_LONG;
+        return Ok(());
+    };
+
+    log::debug!(
+        "syscall_handler: execve '{}', argc: {}, argv: {}, envp: {}",
+        path,
+        arg.argv_len,
+        argv.join(", "),
+        envp.join(", ")
+    );
+
+    let contents = crate::FILE_SYSTEM.lookup(&path).and_then(|node| {
+        if node.stat().file_type != crate::vfs::FileType::Regular {
+            return Err(crate::vfs::Error::NotSupported);
+        }
+        crate::vfs::read_all(&*node)
+    });
+
+    let contents = match contents {
+        Ok(contents) => contents,
+        Err(e) => {
+            log::debug!("Cannot execute {path}: {e}");
+            arg.return_value.error = generated::SYSCALL_EXECVE_ERROR_NOT_FOUND;
+            return Ok(());
+        }
+    };
+
+    let loaded =
+        PROCESSES
+            .process_mut(pid)
+            .execve(&mut PROCESSES.thread_mut(tid), &contents, &argv, &envp);
+    if let Err(e) = loaded {
+        log::debug!("Cannot execute {path}: {e}");
+        arg.return_value.error = generated::SYSCALL_EXECVE_ERROR_INVALID_EXECUTABLE;
+        return Ok(());
+    }
+
+    // the other threads would run in the new program
+    for other in PROCESSES.threads_of(pid) {
+        if other != tid {
+            PROCESSES
+                .thread_mut(other)
+                .set_state(crate::process::State::Terminated(0));
         }
     }
+
+    Ok(())
 }
 
-fn map_framebuffer(pid: u32, arg: &mut generated::syscall_map_framebuffer_t) {
+fn map_framebuffer(pid: u32, _tid: u32, arg: &mut generated::syscall_map_framebuffer_t) {
     log::debug!("syscall_handler: map framebuffer");
 
     let mut kernel_paging = crate::kernel_paging();
-    let mut process = PROCESSES.process_mut(pid);
+    let process = PROCESSES.process(pid);
+    let mut memory = process
+        .memory
+        .try_lock()
+        .expect("Failed to lock address space");
     let term = &crate::term::TERM;
 
     let size = (term.height_pixels * term.width_pixels * 4) as u64;
@@ -527,7 +1182,7 @@ fn map_framebuffer(pid: u32, arg: &mut generated::syscall_map_framebuffer_t) {
         );
 
         unsafe {
-            process
+            memory
                 .paging
                 .page_table
                 .map_to(
@@ -552,7 +1207,12 @@ fn map_framebuffer(pid: u32, arg: &mut generated::syscall_map_framebuffer_t) {
     };
 }
 
-fn write(pid: u32, arg: &mut generated::syscall_write_t) {
+fn write(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_write_t,
+) -> Result<(), SyscallError> {
     log::trace!(
         "syscall_handler: write fd {}, buffer {:x}, length {}",
         arg.fd,
@@ -560,45 +1220,101 @@ fn write(pid: u32, arg: &mut generated::syscall_write_t) {
         arg.len
     );
 
-    let mut process = PROCESSES.process_mut(pid);
-    let Some(mut fd) = process.file_descriptor_mut(arg.fd) else {
+    let Some(mut file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
         log::debug!("Invalid file descriptor: {}", arg.fd);
         arg.return_value.bytes_written = 0;
         arg.return_value.error = generated::SYSCALL_WRITE_ERROR_INVALID_FD;
-        return;
+        return Ok(());
     };
 
-    if let &mut crate::process::FileDescriptor::ForeignStream { stream_type } = fd {
-        drop(process);
-        let (other_pid, other_fd) =
-            resolve_foreign_stream(stream_type).expect("failed to resolve fd");
-        process = PROCESSES.process_mut(other_pid);
-        fd = process
-            .file_descriptor_mut(other_fd)
-            .expect("Foreign stream file descriptor not found");
+    let foreign = match &*file.try_lock().expect("Failed to lock file descriptor") {
+        &crate::process::FileDescriptor::ForeignStream { stream_type } => Some(stream_type),
+        _ => None,
+    };
+    if let Some(stream_type) = foreign {
+        let Some(stream) = resolve_foreign_stream(stream_type) else {
+            log::debug!("Failed to resolve foreign stream {stream_type:?}");
+            arg.return_value.bytes_written = 0;
+            arg.return_value.error = generated::SYSCALL_WRITE_ERROR_INVALID_FD;
+            return Ok(());
+        };
+        file = stream;
     }
 
-    match fd {
-        crate::process::FileDescriptor::Regular { path, offset } => {
-            todo!(
-                "write to regular file, pid {pid}, fd {}, path {path}, offset {offset}",
-                arg.fd
-            )
+    let mut fd = file.try_lock().expect("Failed to lock file descriptor");
+
+    match &mut *fd {
+        crate::process::FileDescriptor::Pipe { .. } => {
+            drop(fd);
+            write_pipe(pid, tid, user, arg)?;
+        }
+        crate::process::FileDescriptor::Regular {
+            writable: false, ..
+        } => {
+            log::debug!("pid {pid}, fd {} is not open for writing", arg.fd);
+            arg.return_value.bytes_written = 0;
+            arg.return_value.error = generated::SYSCALL_WRITE_ERROR_INVALID_FD;
+        }
+        crate::process::FileDescriptor::Regular {
+            path,
+            inode,
+            offset,
+            append,
+            ..
+        } => {
+            if *append {
+                *offset = inode.stat().size;
+            }
+
+            let start = *offset;
+            match write_chunked(user, arg.buf as u64, arg.len as usize, |done, bytes| {
+                inode.write(start + done, bytes)
+            })? {
+                Ok(n) => {
+                    *offset += n;
+
+                    arg.return_value.bytes_written = n as u32;
+                    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
+                }
+                Err(crate::vfs::Error::InvalidInput) => {
+                    log::debug!("{path} does not accept the written value");
+                    arg.return_value.bytes_written = 0;
+                    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_INVALID_VALUE;
+                }
+                Err(crate::vfs::Error::FileTooLarge) => {
+                    log::debug!("{path} would grow past the maximum file size");
+                    arg.return_value.bytes_written = 0;
+                    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_FILE_TOO_LARGE;
+                }
+                Err(crate::vfs::Error::NoSpace) => {
+                    log::debug!("no memory left to write to {path}");
+                    arg.return_value.bytes_written = 0;
+                    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NO_SPACE;
+                }
+                Err(e) => {
+                    log::debug!("Failed to write to {path}, pid {pid}, fd {}: {e}", arg.fd);
+                    return Err(SyscallError::NotSupported);
+                }
+            }
         }
         crate::process::FileDescriptor::OwnedStream {
-            buffer, max_size, ..
+            buffer,
+            max_size,
+            readers,
+            ..
         } => {
             if buffer.len() >= *max_size {
                 arg.return_value.bytes_written = 0;
                 arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
-                return;
+                return Ok(());
             }
 
             let bytes_to_write = (arg.len as usize).min(*max_size - buffer.len());
-            for i in 0..bytes_to_write {
-                let byte = unsafe { arg.buf.cast::<u8>().add(i).read_volatile() };
-                buffer.push_back(byte);
-            }
+            buffer.extend(user.read_bytes(arg.buf as u64, bytes_to_write)?);
+
+            let woken = readers.take();
+            drop(fd);
+            crate::process::wake(woken);
 
             arg.return_value.bytes_written = bytes_to_write as u32;
             arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
@@ -606,69 +1322,92 @@ fn write(pid: u32, arg: &mut generated::syscall_write_t) {
         crate::process::FileDescriptor::ForeignStream { .. } => {
             unreachable!("foreign streams should be resolved before writing")
         }
-        crate::process::FileDescriptor::Terminal => {
-            let mut writer = crate::term::TERM.writer();
-
-            let mut vec = vec![0; arg.len as usize];
-            unsafe {
-                core::ptr::copy_nonoverlapping(
-                    arg.buf.cast::<u8>(),
-                    vec.as_mut_ptr(),
-                    arg.len as usize,
-                );
+        crate::process::FileDescriptor::Device { device, offset } => {
+            let start = *offset;
+            match write_chunked(user, arg.buf as u64, arg.len as usize, |done, bytes| {
+                device.write(start + done, bytes)
+            })? {
+                Ok(n) => {
+                    *offset += n;
+
+                    arg.return_value.bytes_written = n as u32;
+                    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
+                }
+                Err(crate::vfs::Error::InvalidInput) => {
+                    log::debug!("{device:?} does not accept the written value");
+                    arg.return_value.bytes_written = 0;
+                    arg.return_value.error = generated::SYSCALL_WRITE_ERROR_INVALID_VALUE;
+                }
+                Err(e) => {
+                    log::debug!(
+                        "Failed to write to {device:?}, pid {pid}, fd {}: {e}",
+                        arg.fd
+                    );
+                    return Err(SyscallError::NotSupported);
+                }
             }
-
-            let str = String::from_utf8(vec).unwrap_or_else(|e| {
-                panic!(
-                    "Invalid UTF-8 string in syscall_write: len {}: {}",
-                    arg.len,
-                    e.utf8_error()
-                )
-            });
-            writer.write_str(&str).expect("Failed to write to terminal");
-
-            arg.return_value.bytes_written = arg.len;
-            arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
         }
-        crate::process::FileDescriptor::Serial => {
-            let mut writer = crate::driver::serial::com1()
-                .expect("Failed to get serial port")
-                .writer();
-            let mut vec = vec![0; arg.len as usize];
-            unsafe {
-                core::ptr::copy_nonoverlapping(
-                    arg.buf.cast::<u8>(),
-                    vec.as_mut_ptr(),
-                    arg.len as usize,
-                );
-            }
+    }
 
-            for byte in vec {
-                writer
-                    .write_char(byte as char)
-                    .expect("Failed to write to serial port");
-            }
+    Ok(())
+}
 
-            arg.return_value.bytes_written = arg.len;
-            arg.return_value.error = generated::SYSCALL_WRITE_ERROR_NONE;
+/// Most bytes that are copied through a kernel buffer at once, so user lengths do not run the kernel heap out.
+/// Reads return at most this many bytes, writes are split into chunks of this size.
+const MAX_TRANSFER: usize = 64 * 1024;
+
+/// Copies `len` bytes from `address` in chunks of at most [`MAX_TRANSFER`] bytes and passes each to `write`
+/// with the number of bytes written before it.
+/// Stops at a short write or an error, which is only returned if nothing was written.
+fn write_chunked(
+    user: &UserMemory,
+    address: u64,
+    len: usize,
+    mut write: impl FnMut(usize, &[u8]) -> Result<usize, crate::vfs::Error>,
+) -> Result<Result<usize, crate::vfs::Error>, SyscallError> {
+    let mut written = 0;
+    while written < len {
+        let chunk = (len - written).min(MAX_TRANSFER);
+        let bytes = user.read_bytes(address + written as u64, chunk)?;
+        match write(written, &bytes) {
+            Ok(n) => {
+                written += n;
+                if n < chunk {
+                    break;
+                }
+            }
+            Err(e) if written == 0 => return Ok(Err(e)),
+            Err(_) => break,
         }
     }
+    Ok(Ok(written))
 }
 
-fn resolve_foreign_stream(stream: crate::process::ForeignStreamType) -> Option<(u32, i32)> {
+/// Returns the owned stream a foreign stream refers to
+fn resolve_foreign_stream(
+    stream: crate::process::ForeignStreamType,
+) -> Option<crate::process::OpenFile> {
     log::debug!("resolving foreign stream: {stream:?}");
     match stream {
         crate::process::ForeignStreamType::Process {
             pid,
             file_descriptor,
         } => {
-            let process = PROCESSES.process(pid);
-            match process.file_descriptor(file_descriptor) {
-                Some(crate::process::FileDescriptor::OwnedStream { .. }) => {
-                    Some((pid, file_descriptor))
+            // the process may have exited
+            let file = PROCESSES
+                .processes()
+                .iter()
+                .find(|p| p.pid() == pid)?
+                .file_descriptor(file_descriptor)?;
+
+            let fd = file.try_lock().expect("Failed to lock file descriptor");
+            match &*fd {
+                crate::process::FileDescriptor::OwnedStream { .. } => {
+                    drop(fd);
+                    Some(file)
                 }
-                Some(&crate::process::FileDescriptor::ForeignStream { stream_type }) => {
-                    drop(process);
+                &crate::process::FileDescriptor::ForeignStream { stream_type } => {
+                    drop(fd);
                     resolve_foreign_stream(stream_type)
                 }
                 _ => None,
@@ -677,39 +1416,1295 @@ fn resolve_foreign_stream(stream: crate::process::ForeignStreamType) -> Option<(
     }
 }
 
-fn waitpid(pid: u32, arg: &mut generated::syscall_waitpid_t) {
-    PROCESSES.process_mut(pid).state = crate::process::State::WaitingForChild {
-        pid: arg.pid,
-        arg: core::ptr::from_mut(arg),
+/// Wait for a child of the calling process to exit, then reap it and return its pid and exit status
+fn waitpid(pid: u32, tid: u32, arg: &mut generated::syscall_waitpid_t) {
+    wait_for_child(pid, tid, arg);
+}
+
+/// Continues the waitpid of thread `tid` once a child of its process exited.
+/// The thread waits again if the child is not one it waits for or another thread reaped it first.
+pub fn resume_waitpid(tid: u32) {
+    let crate::process::State::WaitingForChild { arg: address, .. } = PROCESSES.thread(tid).state()
+    else {
+        return;
     };
+
+    resume(tid, address, |pid, tid, _, arg| {
+        Ok(wait_for_child(pid, tid, arg))
+    });
 }
 
-/// Handle system calls
-/// return true if the process still exists, false if it was terminated
-pub fn handle_syscall(pid: u32) {
-    let (rax, rbx) = crate::process::PROCESSES.with_process(pid, |p| {
-        log::trace!("process {pid} called syscall {:#x}", p.registers.rax);
+/// Reaps an exited child that `arg` asks for.
+/// If none has exited yet, the thread waits in the child waiters of its process until one exits.
+/// Returns whether the thread is waiting.
+fn wait_for_child(pid: u32, tid: u32, arg: &mut generated::syscall_waitpid_t) -> bool {
+    let children = PROCESSES
+        .processes()
+        .iter()
+        .filter(|p| {
+            p.parent() == Some(pid)
+                && (arg.pid == generated::SYSCALL_WAITPID_ANY_CHILD || p.pid() == arg.pid)
+        })
+        .map(|p| (p.pid(), p.exit_status()))
+        .collect::<alloc::vec::Vec<_>>();
+
+    if children.is_empty() {
+        log::debug!("waitpid: process {pid} has no child {}", arg.pid);
+        arg.return_value.pid = 0;
+        arg.return_value.status = 0;
+        arg.return_value.error = generated::SYSCALL_WAITPID_ERROR_INVALID_PID;
+        return false;
+    }
+
+    if let Some(&(child, _)) = children.iter().find(|(_, status)| status.is_some()) {
+        arg.return_value.pid = child;
+        arg.return_value.status = reap(child);
+        arg.return_value.error = generated::SYSCALL_WAITPID_ERROR_NONE;
+        return false;
+    }
+
+    if arg.options & generated::SYSCALL_WAITPID_OPTION_NON_BLOCKING != 0 {
+        arg.return_value.pid = 0;
+        arg.return_value.status = 0;
+        arg.return_value.error = generated::SYSCALL_WAITPID_ERROR_NONE;
+        return false;
+    }
+
+    PROCESSES.process_mut(pid).child_waiters.push(tid);
+
+    // the result is written when the child exits
+    let arg_address = PROCESSES.thread(tid).registers.rbx;
+    PROCESSES
+        .thread_mut(tid)
+        .set_state(crate::process::State::WaitingForChild {
+            pid: arg.pid,
+            arg: arg_address,
+        });
+    true
+}
+
+/// Return the pid of the calling process
+fn getpid(pid: u32, _tid: u32, arg: &mut generated::syscall_getpid_t) {
+    arg.return_value.pid = pid;
+}
+
+/// Return the pid of the parent of the calling process, 0 if it has none
+fn getppid(pid: u32, _tid: u32, arg: &mut generated::syscall_getppid_t) {
+    arg.return_value.pid = PROCESSES.process(pid).parent().unwrap_or(0);
+}
+
+/// Changes the working directory of the process to an existing directory
+fn chdir(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_chdir_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+
+    log::debug!("syscall_handler: chdir '{path}'");
+
+    if !crate::FILE_SYSTEM
+        .lookup(&path)
+        .is_ok_and(|node| node.stat().file_type == crate::vfs::FileType::Directory)
+    {
+        arg.return_value.error = generated::SYSCALL_CHDIR_ERROR_NOT_FOUND;
+        return Ok(());
+    }
+
+    PROCESSES.process_mut(pid).set_cwd(path);
+    arg.return_value.error = generated::SYSCALL_CHDIR_ERROR_NONE;
+    Ok(())
+}
+
+/// Writes the absolute path of the working directory to the buffer
+fn getcwd(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_getcwd_t,
+) -> Result<(), SyscallError> {
+    let cwd = String::from(PROCESSES.process(pid).cwd());
+
+    arg.return_value.len = cwd.len() as u32;
+    if cwd.len() > arg.len as usize {
+        arg.return_value.error = generated::SYSCALL_GETCWD_ERROR_BUFFER_TOO_SMALL;
+        return Ok(());
+    }
+
+    user.write_bytes(arg.buf as u64, cwd.as_bytes())?;
+    arg.return_value.error = generated::SYSCALL_GETCWD_ERROR_NONE;
+    Ok(())
+}
+
+/// Mounts a new file system of the requested type on a directory
+fn mount(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_mount_t,
+) -> Result<(), SyscallError> {
+    let fs_type = user.read_string(arg.fs_type)?;
+    let target = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.target)?);
+
+    log::debug!("syscall_handler: mount {fs_type} at '{target}'");
+
+    let Some(fs) = crate::vfs::new_file_system(&fs_type) else {
+        arg.return_value.error = generated::SYSCALL_MOUNT_ERROR_UNKNOWN_FILE_SYSTEM;
+        return Ok(());
+    };
+
+    arg.return_value.error = match crate::FILE_SYSTEM.mount(&target, fs) {
+        Ok(()) => generated::SYSCALL_MOUNT_ERROR_NONE,
+        Err(crate::vfs::Error::Busy) => generated::SYSCALL_MOUNT_ERROR_BUSY,
+        Err(crate::vfs::Error::NotADirectory) => generated::SYSCALL_MOUNT_ERROR_NOT_A_DIRECTORY,
+        Err(_) => generated::SYSCALL_MOUNT_ERROR_NOT_FOUND,
+    };
+    Ok(())
+}
+
+/// Unmounts the file system mounted at the path, files that are open on it stay usable
+fn umount(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_umount_t,
+) -> Result<(), SyscallError> {
+    let target = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.target)?);
+
+    log::debug!("syscall_handler: umount '{target}'");
+
+    arg.return_value.error = match crate::FILE_SYSTEM.umount(&target) {
+        Ok(()) => generated::SYSCALL_UMOUNT_ERROR_NONE,
+        Err(crate::vfs::Error::Busy) => generated::SYSCALL_UMOUNT_ERROR_BUSY,
+        Err(_) => generated::SYSCALL_UMOUNT_ERROR_NOT_MOUNTED,
+    };
+    Ok(())
+}
+
+/// Removes the file at the path, open file descriptors keep it alive
+fn unlink(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_unlink_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+
+    log::debug!("syscall_handler: unlink '{path}'");
+
+    arg.return_value.error = match crate::FILE_SYSTEM.unlink(&path) {
+        Ok(()) => generated::SYSCALL_UNLINK_ERROR_NONE,
+        Err(crate::vfs::Error::NotFound | crate::vfs::Error::NotADirectory) => {
+            generated::SYSCALL_UNLINK_ERROR_NOT_FOUND
+        }
+        Err(crate::vfs::Error::IsADirectory) => generated::SYSCALL_UNLINK_ERROR_IS_A_DIRECTORY,
+        Err(_) => generated::SYSCALL_UNLINK_ERROR_NOT_SUPPORTED,
+    };
+    Ok(())
+}
 
-        p.load_paging();
+/// Creates an empty directory at the path, its parent has to exist
+fn mkdir(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_mkdir_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
 
-        (p.registers.rax, p.registers.rbx)
+    log::debug!("syscall_handler: mkdir '{path}'");
+
+    arg.return_value.error = match crate::FILE_SYSTEM.create(&path, crate::vfs::FileType::Directory)
+    {
+        Ok(_) => generated::SYSCALL_MKDIR_ERROR_NONE,
+        Err(crate::vfs::Error::NotFound | crate::vfs::Error::NotADirectory) => {
+            generated::SYSCALL_MKDIR_ERROR_NOT_FOUND
+        }
+        // the root always exists
+        Err(crate::vfs::Error::AlreadyExists | crate::vfs::Error::Busy) => {
+            generated::SYSCALL_MKDIR_ERROR_ALREADY_EXISTS
+        }
+        Err(_) => generated::SYSCALL_MKDIR_ERROR_NOT_SUPPORTED,
+    };
+    Ok(())
+}
+
+/// Removes the empty directory at the path
+fn rmdir(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_rmdir_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+
+    log::debug!("syscall_handler: rmdir '{path}'");
+
+    arg.return_value.error = match crate::FILE_SYSTEM.rmdir(&path) {
+        Ok(()) => generated::SYSCALL_RMDIR_ERROR_NONE,
+        Err(crate::vfs::Error::NotFound) => generated::SYSCALL_RMDIR_ERROR_NOT_FOUND,
+        Err(crate::vfs::Error::NotADirectory) => generated::SYSCALL_RMDIR_ERROR_NOT_A_DIRECTORY,
+        Err(crate::vfs::Error::NotEmpty) => generated::SYSCALL_RMDIR_ERROR_NOT_EMPTY,
+        Err(crate::vfs::Error::Busy) => generated::SYSCALL_RMDIR_ERROR_BUSY,
+        Err(_) => generated::SYSCALL_RMDIR_ERROR_NOT_SUPPORTED,
+    };
+    Ok(())
+}
+
+/// Moves the node at the old path to the new path
+fn rename(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_rename_t,
+) -> Result<(), SyscallError> {
+    let (old_path, new_path) = {
+        let process = PROCESSES.process(pid);
+        (
+            process.absolute_path(&user.read_path(arg.old_path)?),
+            process.absolute_path(&user.read_path(arg.new_path)?),
+        )
+    };
+
+    log::debug!("syscall_handler: rename '{old_path}' to '{new_path}'");
+
+    arg.return_value.error = match crate::FILE_SYSTEM.rename(&old_path, &new_path) {
+        Ok(()) => generated::SYSCALL_RENAME_ERROR_NONE,
+        Err(crate::vfs::Error::NotFound) => generated::SYSCALL_RENAME_ERROR_NOT_FOUND,
+        Err(crate::vfs::Error::NotADirectory) => generated::SYSCALL_RENAME_ERROR_NOT_A_DIRECTORY,
+        Err(crate::vfs::Error::IsADirectory) => generated::SYSCALL_RENAME_ERROR_IS_A_DIRECTORY,
+        Err(crate::vfs::Error::NotEmpty) => generated::SYSCALL_RENAME_ERROR_NOT_EMPTY,
+        Err(crate::vfs::Error::Busy) => generated::SYSCALL_RENAME_ERROR_BUSY,
+        Err(crate::vfs::Error::CrossDevice) => generated::SYSCALL_RENAME_ERROR_CROSS_DEVICE,
+        Err(crate::vfs::Error::InvalidInput) => generated::SYSCALL_RENAME_ERROR_INVALID_PATH,
+        Err(_) => generated::SYSCALL_RENAME_ERROR_NOT_SUPPORTED,
+    };
+    Ok(())
+}
+
+fn stat_type(file_type: crate::vfs::FileType) -> generated::stat_type_t {
+    match file_type {
+        crate::vfs::FileType::Regular => generated::STAT_TYPE_REGULAR,
+        crate::vfs::FileType::Directory => generated::STAT_TYPE_DIRECTORY,
+        crate::vfs::FileType::Stream => generated::STAT_TYPE_STREAM,
+        crate::vfs::FileType::Symlink => generated::STAT_TYPE_SYMLINK,
+        crate::vfs::FileType::Device => generated::STAT_TYPE_DEVICE,
+    }
+}
+
+fn stat_entry(stat: crate::vfs::Stat) -> generated::stat_t {
+    generated::stat_t {
+        type_: stat_type(stat.file_type),
+        mode: stat.mode,
+        nlink: stat.nlink,
+        size: stat.size as u64,
+        created: stat.created,
+        modified: stat.modified,
+    }
+}
+
+/// Writes the type, size, link count, mode and timestamps of the file at the path to the stat struct
+fn stat(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_stat_t,
+) -> Result<(), SyscallError> {
+    let path = PROCESSES
+        .process(pid)
+        .absolute_path(&user.read_path(arg.path)?);
+
+    log::trace!("syscall_handler: stat '{path}'");
+
+    match crate::FILE_SYSTEM.lookup(&path) {
+        Ok(node) => {
+            user.write(arg.stat as u64, &stat_entry(node.stat()))?;
+            arg.return_value.error = generated::SYSCALL_STAT_ERROR_NONE;
+        }
+        Err(e) => {
+            log::debug!("Cannot stat {path}: {e}");
+            arg.return_value.error = generated::SYSCALL_STAT_ERROR_NOT_FOUND;
+        }
+    }
+    Ok(())
+}
+
+/// Like `stat`, for the file that is open as the file descriptor
+fn fstat(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_fstat_t,
+) -> Result<(), SyscallError> {
+    let Some(file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
+        arg.return_value.error = generated::SYSCALL_FSTAT_ERROR_INVALID_FD;
+        return Ok(());
+    };
+
+    let stat = match &*file.try_lock().expect("Failed to lock file descriptor") {
+        crate::process::FileDescriptor::Regular { inode, .. } => inode.stat(),
+        crate::process::FileDescriptor::Device { device, .. } => {
+            crate::vfs::Stat::new(crate::vfs::FileType::Device, device.size())
+        }
+        _ => crate::vfs::Stat::new(crate::vfs::FileType::Stream, 0),
+    };
+
+    user.write(arg.stat as u64, &stat_entry(stat))?;
+    arg.return_value.error = generated::SYSCALL_FSTAT_ERROR_NONE;
+    Ok(())
+}
+
+/// Moves the offset of a regular file or a device without input relative to the start, the current offset or the end
+fn lseek(pid: u32, _tid: u32, arg: &mut generated::syscall_lseek_t) {
+    let Some(file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
+        arg.return_value.error = generated::SYSCALL_LSEEK_ERROR_INVALID_FD;
+        return;
+    };
+    let mut fd = file.try_lock().expect("Failed to lock file descriptor");
+
+    let (offset, size) = match &mut *fd {
+        crate::process::FileDescriptor::Regular { inode, offset, .. } => {
+            (offset, inode.stat().size)
+        }
+        crate::process::FileDescriptor::Device { device, offset } if device.input().is_none() => {
+            (offset, device.size())
+        }
+        _ => {
+            arg.return_value.error = generated::SYSCALL_LSEEK_ERROR_NOT_SEEKABLE;
+            return;
+        }
+    };
+
+    let base = match arg.whence {
+        generated::SYSCALL_LSEEK_WHENCE_SET => 0,
+        generated::SYSCALL_LSEEK_WHENCE_CURRENT => *offset,
+        generated::SYSCALL_LSEEK_WHENCE_END => size,
+        _ => {
+            arg.return_value.error = generated::SYSCALL_LSEEK_ERROR_INVALID_OFFSET;
+            return;
+        }
+    };
+
+    let Some(new_offset) = (base as u64)
+        .checked_add_signed(arg.offset)
+        .filter(|&new_offset| isize::try_from(new_offset).is_ok())
+    else {
+        arg.return_value.error = generated::SYSCALL_LSEEK_ERROR_INVALID_OFFSET;
+        return;
+    };
+
+    *offset = new_offset as usize;
+    arg.return_value.offset = new_offset;
+    arg.return_value.error = generated::SYSCALL_LSEEK_ERROR_NONE;
+}
+
+/// Reads from a regular file at the offset in the argument, the offset of the file descriptor stays the same
+fn pread(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_pread_t,
+) -> Result<(), SyscallError> {
+    arg.return_value.bytes_read = 0;
+
+    let Some(file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
+        arg.return_value.error = generated::SYSCALL_PREAD_ERROR_INVALID_FD;
+        return Ok(());
+    };
+    let fd = file.try_lock().expect("Failed to lock file descriptor");
+
+    let inode = match &*fd {
+        crate::process::FileDescriptor::Regular {
+            inode,
+            readable: true,
+            ..
+        } => inode,
+        crate::process::FileDescriptor::Regular { .. } => {
+            arg.return_value.error = generated::SYSCALL_PREAD_ERROR_INVALID_FD;
+            return Ok(());
+        }
+        _ => {
+            arg.return_value.error = generated::SYSCALL_PREAD_ERROR_NOT_SEEKABLE;
+            return Ok(());
+        }
+    };
+
+    let mut buffer = vec![0; (arg.len as usize).min(MAX_TRANSFER)];
+    match inode.read(
+        arg.offset as usize,
+        &mut crate::io::Cursor::new(&mut buffer),
+    ) {
+        Ok(n) => {
+            user.write_bytes(arg.buf as u64, &buffer[..n])?;
+            arg.return_value.bytes_read = n as u32;
+            arg.return_value.error = generated::SYSCALL_PREAD_ERROR_NONE;
+        }
+        Err(e) => {
+            log::debug!("Failed to read from file descriptor {}: {e}", arg.fd);
+            arg.return_value.error = generated::SYSCALL_PREAD_ERROR_INVALID_FD;
+        }
+    }
+    Ok(())
+}
+
+/// Writes to a regular file at the offset in the argument, the offset of the file descriptor stays the same
+fn pwrite(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_pwrite_t,
+) -> Result<(), SyscallError> {
+    arg.return_value.bytes_written = 0;
+
+    let Some(file) = PROCESSES.process(pid).file_descriptor(arg.fd) else {
+        arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_INVALID_FD;
+        return Ok(());
+    };
+    let fd = file.try_lock().expect("Failed to lock file descriptor");
+
+    let (path, inode) = match &*fd {
+        crate::process::FileDescriptor::Regular {
+            path,
+            inode,
+            writable: true,
+            ..
+        } => (path, inode),
+        crate::process::FileDescriptor::Regular { .. } => {
+            arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_INVALID_FD;
+            return Ok(());
+        }
+        _ => {
+            arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_NOT_SEEKABLE;
+            return Ok(());
+        }
+    };
+
+    let start = arg.offset as usize;
+    match write_chunked(user, arg.buf as u64, arg.len as usize, |done, bytes| {
+        inode.write(start.saturating_add(done), bytes)
+    })? {
+        Ok(n) => {
+            arg.return_value.bytes_written = n as u32;
+            arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_NONE;
+        }
+        Err(crate::vfs::Error::InvalidInput) => {
+            log::debug!("{path} does not accept the written value");
+            arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_INVALID_VALUE;
+        }
+        Err(crate::vfs::Error::FileTooLarge) => {
+            log::debug!("{path} would grow past the maximum file size");
+            arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_FILE_TOO_LARGE;
+        }
+        Err(crate::vfs::Error::NoSpace) => {
+            log::debug!("no memory left to write to {path}");
+            arg.return_value.error = generated::SYSCALL_PWRITE_ERROR_NO_SPACE;
+        }
+        Err(e) => {
+            log::debug!("Failed to write to {path}, pid {pid}, fd {}: {e}", arg.fd);
+            return Err(SyscallError::NotSupported);
+        }
+    }
+    Ok(())
+}
+
+/// Passes the request and the argument buffer to the device behind the file descriptor
+fn ioctl(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_ioctl_t,
+) -> Result<(), SyscallError> {
+    // no request takes a larger argument than the largest of the ioctl structs
+    const MAX_ARG_LEN: usize = const {
+        let (tty, framebuffer) = (
+            size_of::<generated::tty_window_size_t>(),
+            size_of::<generated::framebuffer_info_t>(),
+        );
+        if tty > framebuffer {
+            tty
+        } else {
+            framebuffer
+        }
+    };
+
+    let device = PROCESSES
+        .process(pid)
+        .file_descriptor(arg.fd)
+        .and_then(
+            |file| match &*file.try_lock().expect("Failed to lock file descriptor") {
+                &crate::process::FileDescriptor::Device { device, .. } => Some(device),
+                _ => None,
+            },
+        );
+    let Some(device) = device else {
+        log::debug!("pid {pid}, fd {} is not a device", arg.fd);
+        arg.return_value.error = generated::SYSCALL_IOCTL_ERROR_INVALID_FD;
+        return Ok(());
+    };
+
+    if arg.arg_len as usize > MAX_ARG_LEN {
+        arg.return_value.error = generated::SYSCALL_IOCTL_ERROR_INVALID_ARGUMENT;
+        return Ok(());
+    }
+
+    let mut buffer = user.read_bytes(arg.arg as u64, arg.arg_len as usize)?;
+    arg.return_value.error = match device.ioctl(arg.request, &mut buffer) {
+        Ok(()) => {
+            user.write_bytes(arg.arg as u64, &buffer)?;
+            generated::SYSCALL_IOCTL_ERROR_NONE
+        }
+        Err(crate::vfs::Error::InvalidInput) => generated::SYSCALL_IOCTL_ERROR_INVALID_ARGUMENT,
+        Err(e) => {
+            log::debug!("{device:?} cannot handle ioctl {}: {e}", arg.request);
+            generated::SYSCALL_IOCTL_ERROR_NOT_SUPPORTED
+        }
+    };
+    Ok(())
+}
+
+/// Writes a record for each entry of the directory fd from the cookie on, as long as they fit into the buffer.
+/// The cookie is the index of the entry, entries that are added or removed in between can shift the listing.
+fn getdents(
+    pid: u32,
+    _tid: u32,
+    user: &UserMemory,
+    arg: &mut generated::syscall_getdents_t,
+) -> Result<(), SyscallError> {
+    let directory = PROCESSES
+        .process(pid)
+        .file_descriptor(arg.fd)
+        .and_then(
+            |file| match &*file.try_lock().expect("Failed to lock file descriptor") {
+                crate::process::FileDescriptor::Regular { inode, .. } => Some(inode.clone()),
+                _ => None,
+            },
+        );
+    let Some(directory) = directory else {
+        log::debug!("pid {pid}, fd {} is not a file of the VFS", arg.fd);
+        arg.return_value.error = generated::SYSCALL_GETDENTS_ERROR_INVALID_FD;
+        return Ok(());
+    };
+
+    let entries = match directory.readdir() {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::debug!("pid {pid}, fd {} cannot be listed: {e}", arg.fd);
+            arg.return_value.error = generated::SYSCALL_GETDENTS_ERROR_NOT_A_DIRECTORY;
+            return Ok(());
+        }
+    };
+
+    let mut written = 0;
+    let mut cookie = arg.cookie;
+    for crate::vfs::DirEntry { name, file_type } in entries.iter().skip(arg.cookie as usize) {
+        let record_len =
+            (size_of::<generated::dirent_t>() + name.len()).next_multiple_of(size_of::<u64>());
+        if written + record_len > arg.len as usize {
+            break;
+        }
+
+        let node = directory.lookup(name).ok();
+        let record = generated::dirent_t {
+            inode: node.as_ref().map_or(0, |node| node.inode_number()),
+            size: node.as_ref().map_or(0, |node| node.stat().size as u64),
+            type_: stat_type(*file_type),
+            record_len: record_len as u16,
+            name_len: name.len() as u16,
+        };
+
+        let address = arg.buf as u64 + written as u64;
+        user.write(address, &record)?;
+        user.write_bytes(
+            address + size_of::<generated::dirent_t>() as u64,
+            name.as_bytes(),
+        )?;
+
+        written += record_len;
+        cookie += 1;
+    }
+
+    if written == 0 && (cookie as usize) < entries.len() {
+        log::debug!(
+            "the record of '{}' does not fit into {} bytes",
+            entries[cookie as usize].name,
+            arg.len
+        );
+        arg.return_value.error = generated::SYSCALL_GETDENTS_ERROR_BUFFER_TOO_SMALL;
+        return Ok(());
+    }
+
+    arg.return_value.bytes_written = written as u32;
+    arg.return_value.cookie = cookie;
+    arg.return_value.error = generated::SYSCALL_GETDENTS_ERROR_NONE;
+    Ok(())
+}
+
+/// Adds the increment in rbx to the nice value of the calling thread.
+/// The scheduler always runs the highest priority first, so only init may lower the value.
+fn nice(pid: u32, tid: u32, arg: &mut generated::syscall_nice_t) {
+    if arg.increment < 0 && pid != crate::process::INIT_PID {
+        arg.return_value.error = generated::SYSCALL_NICE_ERROR_PERMISSION_DENIED;
+        return;
+    }
+
+    let mut thread = PROCESSES.thread_mut(tid);
+
+    let nice = i32::from(thread.nice())
+        .saturating_add(arg.increment)
+        .clamp(
+            i32::from(crate::process::scheduler::NICE_MIN),
+            i32::from(crate::process::scheduler::NICE_MAX),
+        );
+    thread.set_nice(nice as i8);
+
+    log::debug!("nice of thread {tid} is now {nice}");
+
+    arg.return_value.nice = nice;
+    arg.return_value.error = generated::SYSCALL_NICE_ERROR_NONE;
+}
+
+/// Start a thread in the calling process at the entry point in the argument, with its own stack
+fn thread_create(pid: u32, tid: u32, arg: &mut generated::syscall_thread_create_t) {
+    let (entry, stack) = (arg.entry as u64, arg.stack as u64);
+
+    if entry == 0
+        || entry >= crate::process::memory::USER_SPACE_END
+        || !(16..=crate::process::memory::USER_SPACE_END).contains(&stack)
+    {
+        arg.return_value.tid = 0;
+        arg.return_value.error = generated::SYSCALL_THREAD_CREATE_ERROR_INVALID_ADDR;
+        return;
+    }
+
+    // aligned as if the entry point was called
+    let mut thread = PROCESSES.thread(tid).sibling(entry, (stack & !0xF) - 8);
+    thread.registers.rdi = arg.arg as u64;
+
+    let new_tid = thread.tid();
+    log::debug!("process {pid} created thread {new_tid} at {entry:#x}, stack {stack:#x}");
+
+    PROCESSES.add_thread(thread);
+
+    arg.return_value.tid = new_tid;
+    arg.return_value.error = generated::SYSCALL_THREAD_CREATE_ERROR_NONE;
+}
+
+/// Exit the calling thread, the whole process exits if it is the last thread
+fn thread_exit(pid: u32, tid: u32, arg: &mut generated::syscall_thread_exit_t) {
+    if PROCESSES.threads_of(pid).len() <= 1 {
+        exit_process(pid, arg.status);
+        return;
+    }
+
+    log::debug!(
+        "thread {tid} of process {pid} exited with code {}",
+        arg.status
+    );
+
+    PROCESSES
+        .thread_mut(tid)
+        .set_state(crate::process::State::Terminated(u64::from(arg.status)));
+
+    let joiners = PROCESSES.thread_mut(tid).joiners.take();
+    // joining threads may have been terminated in the meantime
+    let joining = joiners
+        .into_iter()
+        .filter_map(
+            |joining_tid| match PROCESSES.threads().get(&joining_tid)?.state() {
+                crate::process::State::WaitingForThread {
+                    tid: joined_tid,
+                    arg: join_arg,
+                } if joined_tid == tid => Some((joining_tid, join_arg)),
+                _ => None,
+            },
+        )
+        .collect::<alloc::vec::Vec<_>>();
+
+    if joining.is_empty() {
+        PROCESSES.process_mut(pid).thread_exited(tid, arg.status);
+    }
+
+    // only one thread can join, the others do not find the thread anymore
+    for (i, (joining_tid, join_arg)) in joining.into_iter().enumerate() {
+        complete(
+            joining_tid,
+            join_arg + core::mem::offset_of!(generated::syscall_thread_join_t, return_value) as u64,
+            &if i == 0 {
+                generated::syscall_thread_join_return_t {
+                    status: arg.status,
+                    error: generated::SYSCALL_THREAD_JOIN_ERROR_NONE,
+                }
+            } else {
+                generated::syscall_thread_join_return_t {
+                    status: 0,
+                    error: generated::SYSCALL_THREAD_JOIN_ERROR_NOT_FOUND,
+                }
+            },
+        );
+    }
+}
+
+/// Wait for another thread of the calling process to exit and return its exit status
+fn thread_join(pid: u32, tid: u32, arg: &mut generated::syscall_thread_join_t) {
+    if let Some(status) = PROCESSES.process_mut(pid).join_thread(arg.tid) {
+        arg.return_value.status = status;
+        arg.return_value.error = generated::SYSCALL_THREAD_JOIN_ERROR_NONE;
+        return;
+    }
+
+    if arg.tid == tid || !PROCESSES.threads_of(pid).contains(&arg.tid) {
+        log::debug!("thread {tid} cannot join thread {}", arg.tid);
+        arg.return_value.status = 0;
+        arg.return_value.error = generated::SYSCALL_THREAD_JOIN_ERROR_NOT_FOUND;
+        return;
+    }
+
+    PROCESSES.thread_mut(arg.tid).joiners.push(tid);
+
+    // the result is written when the thread exits
+    let arg_address = PROCESSES.thread(tid).registers.rbx;
+    PROCESSES
+        .thread_mut(tid)
+        .set_state(crate::process::State::WaitingForThread {
+            tid: arg.tid,
+            arg: arg_address,
+        });
+}
+
+/// Create a pipe, its read and write end are returned as new file descriptors
+fn pipe(pid: u32, _tid: u32, arg: &mut generated::syscall_pipe_t) {
+    let (read_end, write_end) = crate::process::pipe::create();
+
+    let close_on_exec = arg.flags & generated::SYSCALL_FD_FLAG_CLOSE_ON_EXEC != 0;
+
+    let mut process = PROCESSES.process_mut(pid);
+    let read_fd = process.new_file_descriptor(
+        crate::process::FileDescriptor::Pipe { end: read_end },
+        close_on_exec,
+    );
+    let write_fd = process.new_file_descriptor(
+        crate::process::FileDescriptor::Pipe { end: write_end },
+        close_on_exec,
+    );
+
+    log::debug!("process {pid} created pipe {read_fd} -> {write_fd}");
+
+    arg.return_value.read_fd = read_fd;
+    arg.return_value.write_fd = write_fd;
+}
+
+/// Send the signal in the argument to a process
+fn kill(_pid: u32, _tid: u32, arg: &mut generated::syscall_kill_t) {
+    if !signal::is_valid(arg.signal) {
+        log::debug!("kill: invalid signal {}", arg.signal);
+        arg.return_value.error = generated::SYSCALL_KILL_ERROR_INVALID_SIGNAL;
+        return;
+    }
+
+    if !PROCESSES.processes().iter().any(|p| p.pid() == arg.pid) {
+        log::debug!("kill: process {} not found", arg.pid);
+        arg.return_value.error = generated::SYSCALL_KILL_ERROR_INVALID_PID;
+        return;
+    }
+
+    if refused_by_init(arg.pid, arg.signal) {
+        log::debug!("kill: init does not handle signal {}", arg.signal);
+        arg.return_value.error = generated::SYSCALL_KILL_ERROR_PERMISSION_DENIED;
+        return;
+    }
+
+    send_signal(arg.pid, arg.signal);
+
+    arg.return_value.error = generated::SYSCALL_KILL_ERROR_NONE;
+}
+
+/// Set what happens when the process receives a signal, returns the previous handler
+fn sigaction(pid: u32, _tid: u32, arg: &mut generated::syscall_sigaction_t) {
+    if !signal::is_valid(arg.signal) || signal::is_fixed(arg.signal) {
+        log::debug!("sigaction: invalid signal {}", arg.signal);
+        arg.return_value.old_handler = generated::SIGNAL_HANDLER_DEFAULT;
+        arg.return_value.error = generated::SYSCALL_SIGACTION_ERROR_INVALID_SIGNAL;
+        return;
+    }
+
+    let action = signal::Action::from_handler(arg.handler, arg.mask, arg.restorer);
+    let old_action = PROCESSES
+        .process_mut(pid)
+        .signals
+        .set_action(arg.signal, action);
+
+    arg.return_value.old_handler = old_action.handler();
+    arg.return_value.error = generated::SYSCALL_SIGACTION_ERROR_NONE;
+}
+
+/// Change the signals blocked by the process, returns the previously blocked signals
+fn sigprocmask(pid: u32, _tid: u32, arg: &mut generated::syscall_sigprocmask_t) {
+    let mut process = PROCESSES.process_mut(pid);
+    let old_mask = process.signals.blocked();
+
+    let blocked = match arg.how {
+        generated::SYSCALL_SIGPROCMASK_BLOCK => old_mask | arg.mask,
+        generated::SYSCALL_SIGPROCMASK_UNBLOCK => old_mask & !arg.mask,
+        generated::SYSCALL_SIGPROCMASK_SET => arg.mask,
+        how => {
+            log::debug!("sigprocmask: invalid how {how}");
+            arg.return_value.old_mask = old_mask;
+            arg.return_value.error = generated::SYSCALL_SIGPROCMASK_ERROR_INVALID_HOW;
+            return;
+        }
+    };
+    process.signals.set_blocked(blocked);
+
+    arg.return_value.old_mask = old_mask;
+    arg.return_value.error = generated::SYSCALL_SIGPROCMASK_ERROR_NONE;
+}
+
+/// Flags of the interrupted code that a signal frame may change
+const SIGNAL_FRAME_FLAGS: x86_64::registers::rflags::RFlags =
+    x86_64::registers::rflags::RFlags::CARRY_FLAG
+        .union(x86_64::registers::rflags::RFlags::PARITY_FLAG)
+        .union(x86_64::registers::rflags::RFlags::AUXILIARY_CARRY_FLAG)
+        .union(x86_64::registers::rflags::RFlags::ZERO_FLAG)
+        .union(x86_64::registers::rflags::RFlags::SIGN_FLAG)
+        .union(x86_64::registers::rflags::RFlags::DIRECTION_FLAG)
+        .union(x86_64::registers::rflags::RFlags::OVERFLOW_FLAG);
+
+/// Return from a signal handler to the code it interrupted.
+/// The registers and blocked signals are restored from the [`generated::signal_frame_t`] at the stack pointer.
+fn sigreturn(pid: u32, tid: u32, user: &UserMemory) -> Result<(), SyscallError> {
+    use x86_64::registers::rflags::RFlags;
+
+    let rsp = PROCESSES.thread(tid).registers.rsp;
+    let frame = user.read::<generated::signal_frame_t>(rsp)?;
+
+    // iretq to a kernel or non-canonical address faults in the kernel, not in the process
+    for address in [frame.rip, frame.rsp] {
+        if address >= crate::process::memory::USER_SPACE_END {
+            return Err(SyscallError::Fault(crate::process::memory::Fault {
+                address,
+            }));
+        }
+    }
+
+    PROCESSES
+        .process_mut(pid)
+        .signals
+        .set_blocked(frame.blocked);
+
+    let mut thread = PROCESSES.thread_mut(tid);
+    thread.rip = frame.rip;
+    // interrupts stay enabled and I/O ports stay privileged, whatever the frame says
+    let flags = RFlags::from_bits_truncate(thread.flags)
+        - SIGNAL_FRAME_FLAGS
+        - RFlags::IOPL_HIGH
+        - RFlags::IOPL_LOW;
+    thread.flags = (flags
+        | (RFlags::from_bits_truncate(frame.flags) & SIGNAL_FRAME_FLAGS)
+        | RFlags::INTERRUPT_FLAG)
+        .bits();
+    thread.registers = crate::idt::GPRegisters {
+        rax: frame.rax,
+        rbx: frame.rbx,
+        rcx: frame.rcx,
+        rdx: frame.rdx,
+        rsi: frame.rsi,
+        rdi: frame.rdi,
+        rsp: frame.rsp,
+        rbp: frame.rbp,
+        r8: frame.r8,
+        r9: frame.r9,
+        r10: frame.r10,
+        r11: frame.r11,
+        r12: frame.r12,
+        r13: frame.r13,
+        r14: frame.r14,
+        r15: frame.r15,
+    };
+    if let Some(xsave) = thread.signal_xsave.pop() {
+        thread.xsave = xsave;
+    }
+
+    log::trace!(
+        "thread {tid} returned from the handler for signal {}",
+        frame.signal
+    );
+
+    Ok(())
+}
+
+/// Sends `signal` to process `pid`.
+/// `SIGKILL` terminates the process and `SIGCONT` continues it right away,
+/// other signals are acted on when a thread of the process returns to userspace next.
+/// Signals that would terminate or stop the init process are dropped.
+pub fn send_signal(pid: u32, signal: generated::signal_t) {
+    if refused_by_init(pid, signal) {
+        log::debug!("init does not handle signal {signal}, dropping it");
+        return;
+    }
+
+    log::debug!("sending signal {signal} to process {pid}");
+
+    match signal {
+        generated::SIGKILL => {
+            exit_process(pid, signal::exit_status(signal));
+            return;
+        }
+        generated::SIGCONT => continue_process(pid),
+        _ => {}
+    }
+
+    if PROCESSES.process_mut(pid).signals.raise(signal) {
+        interrupt_process(pid);
+    }
+}
+
+/// Like Linux, the init process only gets the signals it handles or ignores, nothing is left to run the system without it
+fn refused_by_init(pid: u32, signal: generated::signal_t) -> bool {
+    pid == crate::process::INIT_PID && PROCESSES.process(pid).signals.is_fatal(signal)
+}
+
+/// Sends `signal` to process `pid` for a fault of one of its threads.
+/// The state of the faulting thread was not saved, so the signal cannot be handled, ignored or blocked.
+pub fn force_signal(pid: u32, signal: generated::signal_t) {
+    log::debug!("forcing signal {signal} on process {pid}");
+
+    PROCESSES.process_mut(pid).signals.force(signal);
+    interrupt_process(pid);
+}
+
+/// Makes a thread of process `pid` return to userspace to act on its pending signals.
+/// If no thread is ready, the blocked syscall of one of them fails with [`SyscallError::Interrupted`].
+fn interrupt_process(pid: u32) {
+    // stopped processes only act on signals once they are continued
+    if PROCESSES.process(pid).signals.stopped {
+        return;
+    }
+
+    let states = PROCESSES
+        .threads_of(pid)
+        .into_iter()
+        .map(|tid| (tid, PROCESSES.thread(tid).state()))
+        .collect::<alloc::vec::Vec<_>>();
+
+    if states
+        .iter()
+        .any(|(_, state)| *state == crate::process::State::Ready)
+    {
+        return;
+    }
+
+    let blocked = states.iter().find(|(_, state)| {
+        matches!(
+            state,
+            crate::process::State::Sleeping(_)
+                | crate::process::State::WaitingForStream { .. }
+                | crate::process::State::WaitingForRoom { .. }
+                | crate::process::State::WaitingForChild { .. }
+                | crate::process::State::WaitingForThread { .. }
+        )
     });
 
-    match rax {
-        0 => print(pid, unsafe { &mut *(rbx as *mut _) }),
-        1 => sleep(pid, unsafe { &mut *(rbx as *mut _) }),
-        2 => exit(pid, unsafe { &mut *(rbx as *mut _) }),
-        3 => list_directory(pid, unsafe { &mut *(rbx as *mut _) }),
-        4 => read(pid, unsafe { &mut *(rbx as *mut _) }),
-        5 => fork(pid, unsafe { &mut *(rbx as *mut _) }),
-        6 => open(pid, unsafe { &mut *(rbx as *mut _) }),
-        7 => close(pid, unsafe { &mut *(rbx as *mut _) }),
-        8 => mmap(pid, unsafe { &mut *(rbx as *mut _) }),
-        9 => munmap(pid, unsafe { &mut *(rbx as *mut _) }),
-        10 => execve(pid, unsafe { &mut *(rbx as *mut _) }),
-        11 => map_framebuffer(pid, unsafe { &mut *(rbx as *mut _) }),
-        12 => write(pid, unsafe { &mut *(rbx as *mut _) }),
-        13 => waitpid(pid, unsafe { &mut *(rbx as *mut _) }),
-        n => panic!("unknown syscall: {n:#x}"),
+    if let Some(&(tid, _)) = blocked {
+        log::debug!("interrupting the blocked syscall of thread {tid}");
+        finish(tid, Err(SyscallError::Interrupted));
+    }
+}
+
+fn continue_process(pid: u32) {
+    let mut process = PROCESSES.process_mut(pid);
+    if !process.signals.stopped {
+        return;
+    }
+    process.signals.stopped = false;
+    drop(process);
+
+    log::debug!("process {pid} continued");
+
+    for tid in PROCESSES.threads_of(pid) {
+        let mut thread = PROCESSES.thread_mut(tid);
+        if thread.state() == crate::process::State::Stopped {
+            thread.set_state(crate::process::State::Ready);
+        }
+    }
+}
+
+/// Acts on the pending signals of the process of thread `tid` before the thread returns to userspace.
+/// Returns whether the thread can still run, it may have been terminated or stopped instead.
+pub fn deliver_signals(tid: u32) -> bool {
+    let thread = PROCESSES.thread(tid);
+    let (pid, state) = (thread.pid(), thread.state());
+    drop(thread);
+
+    if state != crate::process::State::Ready {
+        return false;
+    }
+    // kernel threads do not receive signals
+    let Some(pid) = pid else {
+        return true;
+    };
+
+    loop {
+        let mut process = PROCESSES.process_mut(pid);
+
+        if process.signals.stopped {
+            drop(process);
+            PROCESSES
+                .thread_mut(tid)
+                .set_state(crate::process::State::Stopped);
+            return false;
+        }
+
+        let Some(signal) = process.signals.take_deliverable() else {
+            return true;
+        };
+
+        match process.signals.action(signal) {
+            signal::Action::Ignore => {}
+            signal::Action::Default => match signal::default_action(signal) {
+                signal::DefaultAction::Ignore | signal::DefaultAction::Continue => {}
+                signal::DefaultAction::Stop => {
+                    log::debug!("process {pid} stopped by signal {signal}");
+                    process.signals.stopped = true;
+                }
+                signal::DefaultAction::Terminate => {
+                    drop(process);
+                    log::debug!("process {pid} terminated by signal {signal}");
+                    exit_process(pid, signal::exit_status(signal));
+                    return false;
+                }
+            },
+            signal::Action::Handler {
+                handler,
+                mask,
+                restorer,
+            } => {
+                drop(process);
+
+                if let Err(fault) = call_handler(pid, tid, signal, handler, mask, restorer) {
+                    log::debug!("failed to call the handler for signal {signal} in process {pid}: {fault:x?}");
+                    exit_process(pid, signal::exit_status(generated::SIGSEGV));
+                    return false;
+                }
+
+                return true;
+            }
+        }
+    }
+}
+
+/// Bytes below the stack pointer that the interrupted code may still use
+const RED_ZONE_SIZE: u64 = 128;
+
+/// Makes thread `tid` continue in `handler` with the state of the interrupted code in a
+/// [`generated::signal_frame_t`] on its stack, `handler` returns to `restorer`
+fn call_handler(
+    pid: u32,
+    tid: u32,
+    signal: generated::signal_t,
+    handler: u64,
+    mask: generated::signal_mask_t,
+    restorer: u64,
+) -> Result<(), Fault> {
+    let thread = PROCESSES.thread(tid);
+    let (registers, rip, flags) = (thread.registers, thread.rip, thread.flags);
+    drop(thread);
+
+    let blocked = PROCESSES.process(pid).signals.blocked();
+
+    let frame = generated::signal_frame_t {
+        rax: registers.rax,
+        rbx: registers.rbx,
+        rcx: registers.rcx,
+        rdx: registers.rdx,
+        rsi: registers.rsi,
+        rdi: registers.rdi,
+        rsp: registers.rsp,
+        rbp: registers.rbp,
+        r8: registers.r8,
+        r9: registers.r9,
+        r10: registers.r10,
+        r11: registers.r11,
+        r12: registers.r12,
+        r13: registers.r13,
+        r14: registers.r14,
+        r15: registers.r15,
+        rip,
+        flags,
+        blocked,
+        signal,
+    };
+
+    // the handler is entered as if it was called, with the restorer as its return address
+    let frame_address = registers
+        .rsp
+        .checked_sub(RED_ZONE_SIZE + size_of::<generated::signal_frame_t>() as u64)
+        .ok_or(Fault {
+            address: registers.rsp,
+        })?
+        & !0xF;
+    let return_address = frame_address - 8;
+
+    let user = UserMemory::of(pid);
+    user.write(frame_address, &frame)?;
+    user.write(return_address, &restorer)?;
+
+    PROCESSES
+        .process_mut(pid)
+        .signals
+        .set_blocked(blocked | mask | signal::mask(signal));
+
+    let mut thread = PROCESSES.thread_mut(tid);
+    let xsave = thread.xsave;
+    thread.signal_xsave.push(xsave);
+    thread.rip = handler;
+    thread.registers.rsp = return_address;
+    thread.registers.rdi = u64::from(signal);
+
+    log::trace!("calling handler {handler:#x} for signal {signal} in thread {tid}");
+
+    Ok(())
+}
+
+/// Copies the argument of a syscall from userspace, runs the handler on it and copies it back,
+/// unless the process no longer has the same address space
+fn dispatch<T: Copy>(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    address: u64,
+    handler: impl FnOnce(u32, u32, &UserMemory, &mut T) -> Result<(), SyscallError>,
+) -> Result<(), SyscallError> {
+    let mut arg = user.read::<T>(address)?;
+
+    handler(pid, tid, user, &mut arg)?;
+
+    if user.belongs_to(pid) {
+        user.write(address, &arg)?;
+    }
+
+    Ok(())
+}
+
+/// [`dispatch`] for syscalls that do not access user memory besides their argument
+fn dispatch_simple<T: Copy>(
+    pid: u32,
+    tid: u32,
+    user: &UserMemory,
+    address: u64,
+    handler: fn(u32, u32, &mut T),
+) -> Result<(), SyscallError> {
+    dispatch(pid, tid, user, address, |pid, tid, _, arg| {
+        handler(pid, tid, arg);
+        Ok(())
+    })
+}
+
+/// Handle system calls
+/// The syscall number is in rax and a pointer to its argument in rbx.
+/// Once the syscall completed, rax contains a [`generated::syscall_error_t`].
+pub fn handle_syscall(tid: u32) {
+    let thread = PROCESSES.thread(tid);
+    let pid = thread.pid().expect("kernel threads do not make syscalls");
+    let (rax, rbx) = (thread.registers.rax, thread.registers.rbx);
+    drop(thread);
+
+    log::trace!("thread {tid} of process {pid} called syscall {rax:#x}");
+
+    PROCESSES.process(pid).load_paging();
+
+    let user = UserMemory::of(pid);
+
+    let result = match rax {
+        0 => dispatch(pid, tid, &user, rbx, print),
+        1 => dispatch_simple(pid, tid, &user, rbx, sleep),
+        2 => dispatch_simple(pid, tid, &user, rbx, exit),
+        3 => dispatch(pid, tid, &user, rbx, list_directory),
+        4 => dispatch(pid, tid, &user, rbx, read),
+        5 => dispatch_simple(pid, tid, &user, rbx, fork),
+        6 => dispatch(pid, tid, &user, rbx, open),
+        7 => dispatch_simple(pid, tid, &user, rbx, close),
+        8 => dispatch_simple(pid, tid, &user, rbx, mmap),
+        9 => dispatch_simple(pid, tid, &user, rbx, munmap),
+        10 => dispatch(pid, tid, &user, rbx, execve),
+        11 => dispatch_simple(pid, tid, &user, rbx, map_framebuffer),
+        12 => dispatch(pid, tid, &user, rbx, write),
+        13 => dispatch_simple(pid, tid, &user, rbx, waitpid),
+        14 => dispatch_simple(pid, tid, &user, rbx, mprotect),
+        15 => dispatch_simple(pid, tid, &user, rbx, brk),
+        16 => dispatch_simple(pid, tid, &user, rbx, nice),
+        17 => dispatch_simple(pid, tid, &user, rbx, thread_create),
+        18 => dispatch_simple(pid, tid, &user, rbx, thread_exit),
+        19 => dispatch_simple(pid, tid, &user, rbx, thread_join),
+        20 => dispatch_simple(pid, tid, &user, rbx, pipe),
+        21 => dispatch_simple(pid, tid, &user, rbx, dup),
+        22 => dispatch_simple(pid, tid, &user, rbx, dup2),
+        23 => dispatch_simple(pid, tid, &user, rbx, kill),
+        24 => dispatch_simple(pid, tid, &user, rbx, sigaction),
+        25 => dispatch_simple(pid, tid, &user, rbx, sigprocmask),
+        26 => {
+            // all registers of the interrupted code are restored, including rax
+            if let Err(error) = sigreturn(pid, tid, &user) {
+                log::warn!("sigreturn of thread {tid} of process {pid} failed: {error:x?}");
+                force_signal(pid, generated::SIGSEGV);
+            }
+            return;
+        }
+        27 => dispatch_simple(pid, tid, &user, rbx, getpid),
+        28 => dispatch_simple(pid, tid, &user, rbx, getppid),
+        29 => dispatch(pid, tid, &user, rbx, chdir),
+        30 => dispatch(pid, tid, &user, rbx, getcwd),
+        31 => dispatch(pid, tid, &user, rbx, mount),
+        32 => dispatch(pid, tid, &user, rbx, umount),
+        33 => dispatch(pid, tid, &user, rbx, unlink),
+        34 => dispatch(pid, tid, &user, rbx, mkdir),
+        35 => dispatch(pid, tid, &user, rbx, rmdir),
+        36 => dispatch(pid, tid, &user, rbx, rename),
+        37 => dispatch(pid, tid, &user, rbx, stat),
+        38 => dispatch(pid, tid, &user, rbx, fstat),
+        39 => dispatch_simple(pid, tid, &user, rbx, lseek),
+        40 => dispatch(pid, tid, &user, rbx, pread),
+        41 => dispatch(pid, tid, &user, rbx, pwrite),
+        42 => dispatch(pid, tid, &user, rbx, ioctl),
+        43 => dispatch(pid, tid, &user, rbx, getdents),
+        n => Err(SyscallError::UnknownSyscall(n)),
+    };
+
+    let error = match result {
+        Ok(()) => generated::SYSCALL_ERROR_NONE,
+        Err(error) => {
+            log::warn!("syscall {rax:#x} of thread {tid} of process {pid} failed: {error:x?}");
+
+            if KILL_ON_SYSCALL_ERROR.load(core::sync::atomic::Ordering::Relaxed) {
+                exit_process(pid, crate::process::EXIT_BAD_SYSCALL);
+            }
+
+            error.code()
+        }
+    };
+
+    let mut thread = PROCESSES.thread_mut(tid);
+
+    // blocked syscalls are completed when they are woken, until then rax still holds their number
+    if !matches!(
+        thread.state(),
+        crate::process::State::WaitingForStream { .. }
+            | crate::process::State::WaitingForRoom { .. }
+            | crate::process::State::WaitingForChild { .. }
+            | crate::process::State::WaitingForThread { .. }
+    ) {
+        thread.registers.rax = u64::from(error);
     }
 }
@@ -0,0 +1,95 @@
+use alloc::{string::String, sync::Arc, vec::Vec};
+use x86_64::VirtAddr;
+
+use crate::process::{
+    memory::{AddressSpace, Fault},
+    PROCESSES,
+};
+
+use super::{generated, SyscallError, MAX_TRANSFER};
+
+/// Longest path a syscall accepts
+pub const PATH_MAX: usize = 4096;
+
+/// The memory of a process as seen by a syscall.
+/// All accesses are checked against the regions and page table of the process,
+/// user pointers are never dereferenced directly.
+pub struct UserMemory(Arc<spin::Mutex<AddressSpace>>);
+
+impl UserMemory {
+    pub fn of(pid: u32) -> Self {
+        UserMemory(Arc::clone(&PROCESSES.process(pid).memory))
+    }
+
+    /// Returns whether this is still the address space of the process,
+    /// which is not the case after `execve` or once the process was reaped
+    pub fn belongs_to(&self, pid: u32) -> bool {
+        PROCESSES
+            .processes()
+            .iter()
+            .find(|p| p.pid() == pid)
+            .is_some_and(|p| Arc::ptr_eq(&self.0, &p.memory))
+    }
+
+    fn address(address: u64) -> Result<VirtAddr, Fault> {
+        VirtAddr::try_new(address).map_err(|_| Fault { address })
+    }
+
+    /// Copies `len` bytes at `address` into a kernel buffer, which is only allocated once the process may read all of them.
+    /// Longer buffers than [`MAX_TRANSFER`] have to be copied in chunks.
+    pub fn read_bytes(&self, address: u64, len: usize) -> Result<Vec<u8>, SyscallError> {
+        if len > MAX_TRANSFER {
+            return Err(SyscallError::TooLarge);
+        }
+        let address = Self::address(address)?;
+
+        let mut kernel_paging = crate::kernel_paging();
+        let mut memory = self.0.try_lock().expect("Failed to lock address space");
+        memory.check_readable(address, len)?;
+
+        let mut buffer = Vec::new();
+        buffer
+            .try_reserve_exact(len)
+            .map_err(|_| SyscallError::NoMemory)?;
+        buffer.resize(len, 0);
+
+        memory.copy_from_user(&mut kernel_paging, address, &mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    pub fn write_bytes(&self, address: u64, bytes: &[u8]) -> Result<(), Fault> {
+        let mut kernel_paging = crate::kernel_paging();
+        self.0
+            .try_lock()
+            .expect("Failed to lock address space")
+            .copy_to_user(&mut kernel_paging, Self::address(address)?, bytes)
+    }
+
+    /// Reads a value of one of the types in [`generated`], which are valid for any bit pattern
+    pub fn read<T: Copy>(&self, address: u64) -> Result<T, SyscallError> {
+        let bytes = self.read_bytes(address, size_of::<T>())?;
+        Ok(unsafe { bytes.as_ptr().cast::<T>().read_unaligned() })
+    }
+
+    pub fn write<T: Copy>(&self, address: u64, value: &T) -> Result<(), Fault> {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(core::ptr::from_ref(value).cast::<u8>(), size_of::<T>())
+        };
+        self.write_bytes(address, bytes)
+    }
+
+    pub fn read_string(&self, string: generated::string_const_t) -> Result<String, SyscallError> {
+        let bytes = self.read_bytes(string.ptr as u64, string.len as usize)?;
+        // invalid UTF-8 is not a fault, the bytes are replaced instead
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Like [`UserMemory::read_string`], but for paths, which are at most [`PATH_MAX`] bytes long
+    pub fn read_path(&self, path: generated::string_const_t) -> Result<String, SyscallError> {
+        if path.len as usize > PATH_MAX {
+            return Err(SyscallError::TooLarge);
+        }
+        self.read_string(path)
+    }
+}
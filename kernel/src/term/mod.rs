@@ -87,10 +87,10 @@ impl Term {
 
 // char based interface
 impl Term {
-    fn columns(&self) -> usize {
+    pub fn columns(&self) -> usize {
         self.width_pixels / self.font.width_pixels()
     }
-    fn rows(&self) -> usize {
+    pub fn rows(&self) -> usize {
         self.height_pixels / self.font.height_pixels()
     }
     fn current_pixel_position(&self) -> (usize, usize) {
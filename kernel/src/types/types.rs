@@ -218,16 +218,45 @@ const _: () = {
 };
 pub type fd_t = i32;
 pub const FD_STDIN: fd_t = 0;
+pub type auxv_key_t = u64;
+pub const AT_NULL: auxv_key_t = 0;
+pub const AT_PHDR: auxv_key_t = 3;
+pub const AT_PHENT: auxv_key_t = 4;
+pub const AT_PHNUM: auxv_key_t = 5;
+pub const AT_PAGESZ: auxv_key_t = 6;
+pub const AT_ENTRY: auxv_key_t = 9;
+pub const AT_RANDOM: auxv_key_t = 25;
+pub const AT_FRAMEBUFFER_WIDTH: auxv_key_t = 0x1000;
+pub const AT_FRAMEBUFFER_HEIGHT: auxv_key_t = 0x1001;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct auxv_t {
+    pub key: auxv_key_t,
+    pub value: u64,
+}
+#[allow(clippy::unnecessary_operation, clippy::identity_op)]
+const _: () = {
+    ["Size of auxv_t"][::core::mem::size_of::<auxv_t>() - 16usize];
+    ["Alignment of auxv_t"][::core::mem::align_of::<auxv_t>() - 8usize];
+    ["Offset of field: auxv_t::key"][::core::mem::offset_of!(auxv_t, key) - 0usize];
+    ["Offset of field: auxv_t::value"][::core::mem::offset_of!(auxv_t, value) - 8usize];
+};
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct entry_t {
     pub argc: u32,
     pub argv: *mut string_const_t,
+    pub envc: u32,
+    pub envp: *mut string_const_t,
+    pub auxv: *mut auxv_t,
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of entry_t"][::core::mem::size_of::<entry_t>() - 16usize];
+    ["Size of entry_t"][::core::mem::size_of::<entry_t>() - 40usize];
     ["Alignment of entry_t"][::core::mem::align_of::<entry_t>() - 8usize];
     ["Offset of field: entry_t::argc"][::core::mem::offset_of!(entry_t, argc) - 0usize];
     ["Offset of field: entry_t::argv"][::core::mem::offset_of!(entry_t, argv) - 8usize];
+    ["Offset of field: entry_t::envc"][::core::mem::offset_of!(entry_t, envc) - 16usize];
+    ["Offset of field: entry_t::envp"][::core::mem::offset_of!(entry_t, envp) - 24usize];
+    ["Offset of field: entry_t::auxv"][::core::mem::offset_of!(entry_t, auxv) - 32usize];
 };
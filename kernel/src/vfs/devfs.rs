@@ -0,0 +1,46 @@
+//! The devices, opening one of them gives a stream or a device instead of a file with contents
+
+use alloc::sync::Arc;
+
+use super::{
+    special::{DeviceNode, OwnedStream, SpecialDirectory},
+    FileSystem, Inode,
+};
+use crate::{driver::device, process::OwnedStreamType};
+
+pub struct DevFs {
+    root: Arc<SpecialDirectory>,
+}
+
+impl DevFs {
+    pub fn new() -> Self {
+        let mut root = SpecialDirectory::new()
+            .with("null", DeviceNode::new(&device::NULL))
+            .with("zero", DeviceNode::new(&device::ZERO))
+            .with("random", DeviceNode::new(&device::RANDOM))
+            .with("tty", DeviceNode::new(&device::TTY))
+            .with("console", DeviceNode::new(&device::CONSOLE))
+            .with("fb0", DeviceNode::new(&device::FB0))
+            // every open file gets a copy of the raw input, unlike the shared input of the tty
+            .with("mouse", OwnedStream::new(OwnedStreamType::Mouse))
+            .with("keyboard", OwnedStream::new(OwnedStreamType::Keyboard));
+
+        if crate::driver::serial::com1().is_ok() {
+            root = root.with("ttyS0", DeviceNode::new(&device::TTY_S0));
+        }
+
+        DevFs {
+            root: Arc::new(root),
+        }
+    }
+}
+
+impl FileSystem for DevFs {
+    fn name(&self) -> &'static str {
+        "dev"
+    }
+
+    fn root(&self) -> Arc<dyn Inode> {
+        self.root.clone()
+    }
+}
@@ -0,0 +1,237 @@
+//! Unpacks the archive the bootloader loads as a module into the root file system.
+//! Both ustar tar archives and cpio archives in the portable "newc" format are supported.
+
+use alloc::{string::String, sync::Arc};
+
+use super::{
+    absolute_path,
+    ramfs::{RamDirectory, RamFile, RamSymlink},
+    FileType, Inode, Vfs,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("the archive is neither a ustar tar archive nor a newc cpio archive")]
+    UnknownFormat,
+    #[error("the archive ends in the middle of the entry at offset {offset:#x}")]
+    Truncated { offset: usize },
+    #[error("invalid header at offset {offset:#x}: {reason}")]
+    InvalidHeader { offset: usize, reason: &'static str },
+    #[error("cannot create '{path}': {error}")]
+    Create { path: String, error: super::Error },
+}
+
+/// What an entry of the archive creates
+#[derive(Clone, Copy)]
+enum Kind<'a> {
+    File(&'a [u8]),
+    Directory,
+    Symlink(&'a str),
+    /// another name for the file at the path, which comes earlier in the archive
+    HardLink(&'a str),
+}
+
+/// Unpacks `archive` into `vfs` and returns the number of entries.
+/// Existing directories are kept, so the archive can contain the mount points.
+pub fn unpack(vfs: &Vfs, archive: &[u8]) -> Result<usize, Error> {
+    if archive.get(257..262) == Some(b"ustar") {
+        unpack_tar(vfs, archive)
+    } else if archive.starts_with(b"070701") || archive.starts_with(b"070702") {
+        unpack_cpio(vfs, archive)
+    } else {
+        Err(Error::UnknownFormat)
+    }
+}
+
+fn create(vfs: &Vfs, name: &str, mode: u32, kind: Kind) -> Result<(), Error> {
+    let path = absolute_path("/", name);
+    let create_error = |error| Error::Create {
+        path: path.clone(),
+        error,
+    };
+
+    let node: Arc<dyn Inode> = match kind {
+        Kind::File(contents) => Arc::new(RamFile::new(contents).with_mode(mode)),
+        Kind::Directory => match vfs.lookup(&path) {
+            Ok(node) if node.stat().file_type == FileType::Directory => return Ok(()),
+            _ => Arc::new(RamDirectory::new().with_mode(mode)),
+        },
+        Kind::Symlink(target) => Arc::new(RamSymlink::new(target)),
+        Kind::HardLink(target) => vfs
+            .lookup_link(&absolute_path("/", target))
+            .map_err(create_error)?,
+    };
+
+    // the root is not an entry of a directory
+    if path == "/" {
+        return Ok(());
+    }
+
+    log::trace!("initramfs: {path} ({:?}, {mode:#o})", node.stat().file_type);
+    vfs.create_node(&path, node).map_err(create_error)
+}
+
+/// Parses an octal number field, which is padded with spaces or NUL bytes
+fn octal(field: &[u8]) -> Option<u32> {
+    let digits = core::str::from_utf8(field)
+        .ok()?
+        .trim_matches(|c: char| c == ' ' || c == '\0');
+    if digits.is_empty() {
+        return Some(0);
+    }
+    u32::from_str_radix(digits, 8).ok()
+}
+
+/// A string field, which ends at the first NUL byte if it does not fill the whole field
+fn string(field: &[u8]) -> Option<&str> {
+    let len = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+    core::str::from_utf8(&field[..len]).ok()
+}
+
+fn unpack_tar(vfs: &Vfs, archive: &[u8]) -> Result<usize, Error> {
+    const BLOCK_SIZE: usize = 512;
+
+    let mut offset = 0;
+    let mut entries = 0;
+    loop {
+        let header = archive
+            .get(offset..offset + BLOCK_SIZE)
+            .ok_or(Error::Truncated { offset })?;
+        let invalid = |reason| Error::InvalidHeader { offset, reason };
+
+        // the archive ends with zero blocks
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        if &header[257..262] != b"ustar" {
+            return Err(invalid("missing ustar magic"));
+        }
+
+        // the checksum field counts as spaces
+        let checksum = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| {
+                if (148..156).contains(&i) {
+                    u32::from(b' ')
+                } else {
+                    u32::from(b)
+                }
+            })
+            .sum::<u32>();
+        if octal(&header[148..156]) != Some(checksum) {
+            return Err(invalid("wrong checksum"));
+        }
+
+        let name = string(&header[0..100]).ok_or(invalid("name is not UTF-8"))?;
+        let prefix = string(&header[345..500]).ok_or(invalid("prefix is not UTF-8"))?;
+        let mode = octal(&header[100..108]).ok_or(invalid("invalid mode"))? & 0o7777;
+        let size = octal(&header[124..136]).ok_or(invalid("invalid size"))? as usize;
+        let link_name = string(&header[157..257]).ok_or(invalid("link name is not UTF-8"))?;
+
+        let data_start = offset + BLOCK_SIZE;
+        let contents = archive
+            .get(data_start..data_start + size)
+            .ok_or(Error::Truncated { offset })?;
+
+        let path = if prefix.is_empty() {
+            String::from(name)
+        } else {
+            alloc::format!("{prefix}/{name}")
+        };
+
+        let kind = match header[156] {
+            b'0' | b'\0' | b'7' => Some(Kind::File(contents)),
+            b'5' => Some(Kind::Directory),
+            b'2' => Some(Kind::Symlink(link_name)),
+            b'1' => Some(Kind::HardLink(link_name)),
+            type_ => {
+                log::warn!(
+                    "initramfs: skipping '{path}' with unsupported tar entry type '{}'",
+                    type_ as char
+                );
+                None
+            }
+        };
+        if let Some(kind) = kind {
+            create(vfs, &path, mode, kind)?;
+            entries += 1;
+        }
+
+        offset = data_start + size.div_ceil(BLOCK_SIZE) * BLOCK_SIZE;
+    }
+
+    Ok(entries)
+}
+
+fn unpack_cpio(vfs: &Vfs, archive: &[u8]) -> Result<usize, Error> {
+    const HEADER_SIZE: usize = 110;
+    const S_IFMT: u32 = 0o170_000;
+    const S_IFREG: u32 = 0o100_000;
+    const S_IFDIR: u32 = 0o040_000;
+    const S_IFLNK: u32 = 0o120_000;
+
+    let mut offset = 0;
+    let mut entries = 0;
+    loop {
+        let header = archive
+            .get(offset..offset + HEADER_SIZE)
+            .ok_or(Error::Truncated { offset })?;
+        let invalid = |reason| Error::InvalidHeader { offset, reason };
+
+        if &header[..6] != b"070701" && &header[..6] != b"070702" {
+            return Err(invalid("missing newc magic"));
+        }
+
+        // 13 fields of 8 hex digits follow the magic
+        let field = |index: usize| {
+            let start = 6 + index * 8;
+            core::str::from_utf8(&header[start..start + 8])
+                .ok()
+                .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                .ok_or(invalid("invalid number"))
+        };
+        let mode = field(1)?;
+        let size = field(6)? as usize;
+        let name_size = field(11)? as usize;
+
+        let name_start = offset + HEADER_SIZE;
+        let name = archive
+            .get(name_start..name_start + name_size)
+            .ok_or(Error::Truncated { offset })?;
+        let name = string(name).ok_or(invalid("name is not UTF-8"))?;
+
+        // the name and the contents are padded to 4 bytes
+        let data_start = (name_start + name_size).next_multiple_of(4);
+        let contents = archive
+            .get(data_start..data_start + size)
+            .ok_or(Error::Truncated { offset })?;
+
+        if name == "TRAILER!!!" {
+            break;
+        }
+
+        let kind = match mode & S_IFMT {
+            S_IFREG => Some(Kind::File(contents)),
+            S_IFDIR => Some(Kind::Directory),
+            S_IFLNK => Some(Kind::Symlink(
+                core::str::from_utf8(contents).map_err(|_| invalid("link target is not UTF-8"))?,
+            )),
+            type_ => {
+                log::warn!(
+                    "initramfs: skipping '{name}' with unsupported cpio file type {type_:#o}"
+                );
+                None
+            }
+        };
+        if let Some(kind) = kind {
+            create(vfs, name, mode & 0o7777, kind)?;
+            entries += 1;
+        }
+
+        offset = (data_start + size).next_multiple_of(4);
+    }
+
+    Ok(entries)
+}
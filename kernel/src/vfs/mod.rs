@@ -1,355 +1,505 @@
-use alloc::string::{String, ToString as _};
-
-use crate::process::{ForeignStreamType, OwnedStreamType};
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
 
+pub mod devfs;
+pub mod initramfs;
+pub mod procfs;
+pub mod ramfs;
 pub mod root;
+pub mod special;
+pub mod sysfs;
+
+/// Splits the absolute `path` into the names of the directories leading to it.
+/// Repeated slashes and `.` are skipped, `..` goes to the parent directory and stays at the root.
+pub fn components(path: &str) -> Vec<&str> {
+    let mut components = Vec::new();
+    for name in path.split('/') {
+        match name {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            name => components.push(name),
+        }
+    }
+    components
+}
 
-pub enum Directory {
-    Regular {
-        files: alloc::collections::BTreeMap<String, File>,
-        directories: alloc::collections::BTreeMap<String, Directory>,
-    },
-    Special {
-        files: alloc::collections::BTreeMap<String, File>,
-        directories: alloc::collections::BTreeMap<String, Directory>,
-        update: fn(
-            files: &mut alloc::collections::BTreeMap<String, File>,
-            directories: &mut alloc::collections::BTreeMap<String, Directory>,
-        ),
-    },
+/// Resolves `path` against the absolute directory `cwd` unless it is absolute.
+/// The result is normalised, it starts with a slash and has no `.` or `..` components.
+pub fn absolute_path(cwd: &str, path: &str) -> String {
+    let joined = if path.starts_with('/') {
+        String::from(path)
+    } else {
+        alloc::format!("{cwd}/{path}")
+    };
+
+    let mut absolute = String::new();
+    for name in components(&joined) {
+        absolute.push('/');
+        absolute.push_str(name);
+    }
+    if absolute.is_empty() {
+        absolute.push('/');
+    }
+    absolute
 }
 
-unsafe impl Send for Directory {}
-
-pub enum File {
-    Regular {
-        contents: alloc::vec::Vec<u8>,
-    },
-    Special {
-        read: alloc::boxed::Box<
-            dyn Fn(
-                &File,
-                usize,
-                &mut dyn crate::io::Write,
-            ) -> Result<usize, crate::io::WriterError>,
-        >,
-        write: (),
-    },
-    OwnedStream {
-        stream_type: OwnedStreamType,
-    },
-    ForeignStream {
-        stream_type: ForeignStreamType,
-    },
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    #[error("no such file or directory")]
+    NotFound,
+    #[error("not a directory")]
+    NotADirectory,
+    #[error("is a directory")]
+    IsADirectory,
+    #[error("file exists")]
+    AlreadyExists,
+    #[error("the file system does not support the operation")]
+    NotSupported,
+    #[error("invalid offset")]
+    InvalidOffset,
+    #[error("the file system is in use")]
+    Busy,
+    #[error("directory not empty")]
+    NotEmpty,
+    #[error("cannot move a node to another file system")]
+    CrossDevice,
+    #[error("invalid argument")]
+    InvalidInput,
+    #[error("too many levels of symbolic links")]
+    SymlinkLoop,
+    #[error("file too large")]
+    FileTooLarge,
+    #[error("no space left for the file")]
+    NoSpace,
 }
 
-impl File {
-    pub fn regular(contents: impl Into<alloc::vec::Vec<u8>>) -> Self {
-        File::Regular {
-            contents: contents.into(),
+impl From<crate::io::WriterError> for Error {
+    fn from(error: crate::io::WriterError) -> Self {
+        match error {
+            crate::io::WriterError::InvalidOffset => Error::InvalidOffset,
         }
     }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    Regular,
+    Directory,
+    /// a stream that is not read at an offset
+    Stream,
+    /// a symbolic link, paths are resolved through it
+    Symlink,
+    /// a device that is read and written through its driver
+    Device,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Stat {
+    pub file_type: FileType,
+    /// size of the contents in bytes, 0 if it is not known in advance
+    pub size: usize,
+    /// number of names of the node, 2 plus the number of subdirectories for a directory
+    pub nlink: u32,
+    /// permission bits, like 0o644
+    pub mode: u32,
+    /// unix timestamps in seconds, 0 for nodes that do not keep track of them
+    pub created: u64,
+    pub modified: u64,
+}
 
-    pub fn special(
-        read: impl Fn(&File, usize, &mut dyn crate::io::Write) -> Result<usize, crate::io::WriterError>
-            + 'static,
-    ) -> Self {
-        File::Special {
-            read: alloc::boxed::Box::new(read),
-            write: (),
+impl Stat {
+    /// A node with the usual mode of its type, a single name and no timestamps
+    pub fn new(file_type: FileType, size: usize) -> Self {
+        let (nlink, mode) = match file_type {
+            FileType::Regular => (1, 0o644),
+            FileType::Directory => (2, 0o755),
+            FileType::Stream | FileType::Device => (1, 0o666),
+            FileType::Symlink => (1, 0o777),
+        };
+
+        Stat {
+            file_type,
+            size,
+            nlink,
+            mode,
+            created: 0,
+            modified: 0,
         }
     }
+}
+
+/// Returns a number for a node that is created, see [`Inode::inode_number`].
+/// Numbers are not reused, so a node that is dropped cannot be mistaken for a new one.
+pub fn next_inode_number() -> u64 {
+    static NEXT: core::sync::atomic::AtomicU64 = core::sync::atomic::AtomicU64::new(1);
+    NEXT.fetch_add(1, core::sync::atomic::Ordering::Relaxed)
+}
+
+/// The current time as a unix timestamp, for the timestamps of nodes
+pub fn now() -> u64 {
+    u64::try_from(crate::driver::rtc::get_time().and_utc().timestamp()).unwrap_or(0)
+}
+
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    pub name: String,
+    pub file_type: FileType,
+}
+
+/// A file or directory of a file system.
+/// The operations a node does not support return `Error::NotSupported` or `Error::NotADirectory`.
+pub trait Inode: Send + Sync {
+    fn stat(&self) -> Stat;
+
+    /// A number that tells the node apart from the other nodes of its file system.
+    /// It stays the same as long as the node exists, even for nodes that are generated on every lookup.
+    fn inode_number(&self) -> u64;
 
-    pub fn stream1(stream_type: OwnedStreamType) -> Self {
-        File::OwnedStream { stream_type }
+    /// Writes the contents starting at `offset` to `writer` and returns the number of bytes written
+    fn read(&self, _offset: usize, _writer: &mut dyn crate::io::Write) -> Result<usize, Error> {
+        match self.stat().file_type {
+            FileType::Directory => Err(Error::IsADirectory),
+            _ => Err(Error::NotSupported),
+        }
     }
 
-    pub fn stream2(stream_type: ForeignStreamType) -> Self {
-        File::ForeignStream { stream_type }
+    /// Writes `bytes` at `offset` and returns the number of bytes written
+    fn write(&self, _offset: usize, _bytes: &[u8]) -> Result<usize, Error> {
+        match self.stat().file_type {
+            FileType::Directory => Err(Error::IsADirectory),
+            _ => Err(Error::NotSupported),
+        }
     }
 
-    pub fn read(
-        &self,
-        offset: usize,
-        mut writer: impl crate::io::Write,
-    ) -> Result<usize, crate::io::WriterError> {
-        match self {
-            File::Regular { contents } => writer.write(&contents[offset..]),
-            File::Special { read, .. } => {
-                read(self, 0, &mut crate::io::Ignorer::ignoring(offset, writer))
-            }
-            File::OwnedStream { .. } => panic!("cannot read from an owned stream file"),
-            File::ForeignStream { stream_type } => match stream_type {
-                ForeignStreamType::Process {
-                    pid,
-                    file_descriptor,
-                } => {
-                    let mut process = crate::process::PROCESSES.process_mut(*pid);
-                    let fd = process
-                        .file_descriptor_mut(*file_descriptor)
-                        .expect("file descriptor not found");
-                    match fd {
-                        crate::process::FileDescriptor::OwnedStream { buffer, .. } => {
-                            let bytes = buffer.drain(..).collect::<alloc::vec::Vec<_>>();
-                            let written = writer.write(bytes.as_slice())?;
-
-                            Ok(written)
-                        }
-                        e => panic!("expected owned stream file descriptor, found {e:?}"),
-                    }
-                }
-            },
+    /// Changes the size of a regular file, it is filled with zeros when it grows
+    fn truncate(&self, _size: usize) -> Result<(), Error> {
+        match self.stat().file_type {
+            FileType::Directory => Err(Error::IsADirectory),
+            _ => Err(Error::NotSupported),
         }
     }
-}
 
-impl Directory {
-    pub fn new(directories: &[&str]) -> Self {
-        let mut root = Directory::empty();
-        for d in directories {
-            root.create_directories(&[d]);
+    /// Nodes that are not read and written at an offset, like streams, return their own file descriptor
+    fn open(&self) -> Option<crate::process::FileDescriptor> {
+        None
+    }
+
+    /// Returns the path a symbolic link points to, relative paths are relative to the directory of the link
+    fn readlink(&self) -> Result<String, Error> {
+        Err(Error::InvalidInput)
+    }
+
+    /// Returns the entry `name` of a directory
+    fn lookup(&self, _name: &str) -> Result<Arc<dyn Inode>, Error> {
+        Err(Error::NotADirectory)
+    }
+
+    fn readdir(&self) -> Result<Vec<DirEntry>, Error> {
+        Err(Error::NotADirectory)
+    }
+
+    /// Creates an empty regular file or directory `name` in a directory
+    fn create(&self, _name: &str, _file_type: FileType) -> Result<Arc<dyn Inode>, Error> {
+        match self.stat().file_type {
+            FileType::Directory => Err(Error::NotSupported),
+            _ => Err(Error::NotADirectory),
         }
-        root
     }
 
-    pub fn update(&mut self) {
-        if let Directory::Special {
-            update,
-            files,
-            directories,
-        } = self
-        {
-            update(files, directories);
+    /// Adds the existing `node` to a directory as `name`
+    fn link(&self, _name: &str, _node: Arc<dyn Inode>) -> Result<(), Error> {
+        match self.stat().file_type {
+            FileType::Directory => Err(Error::NotSupported),
+            _ => Err(Error::NotADirectory),
         }
     }
 
-    pub fn files(&mut self) -> &mut alloc::collections::BTreeMap<String, File> {
-        match self {
-            Directory::Regular { files, .. } => files,
-            Directory::Special {
-                files,
-                directories,
-                update,
-            } => {
-                update(files, directories);
-                files
-            }
+    /// Removes the entry `name` from a directory, open files keep the node alive
+    fn unlink(&self, _name: &str) -> Result<(), Error> {
+        match self.stat().file_type {
+            FileType::Directory => Err(Error::NotSupported),
+            _ => Err(Error::NotADirectory),
         }
     }
+}
 
-    pub fn directories(&mut self) -> &mut alloc::collections::BTreeMap<String, Directory> {
-        self.update();
+impl core::fmt::Debug for dyn Inode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Inode({:?})", self.stat())
+    }
+}
 
-        match self {
-            Directory::Regular { directories, .. } => directories,
-            Directory::Special {
-                files,
-                directories,
-                update,
-            } => {
-                update(files, directories);
-                directories
-            }
+/// Reads all of the contents of `node`
+pub fn read_all(node: &dyn Inode) -> Result<Vec<u8>, Error> {
+    struct VecWriter<'a>(&'a mut Vec<u8>);
+
+    impl crate::io::Write for VecWriter<'_> {
+        fn write(&mut self, bytes: &[u8]) -> Result<usize, crate::io::WriterError> {
+            self.0.extend_from_slice(bytes);
+            Ok(bytes.len())
         }
     }
 
-    pub fn directory(&mut self, path: &str) -> Option<&Directory> {
-        let path = path
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect::<alloc::vec::Vec<_>>();
-        self.directory_impl(path.as_slice())
+    let mut contents = Vec::with_capacity(node.stat().size);
+    node.read(0, &mut VecWriter(&mut contents))?;
+    Ok(contents)
+}
+
+/// A tree of nodes that can be mounted
+pub trait FileSystem: Send + Sync {
+    /// The type of the file system, as passed to the mount syscall and listed in `/proc/mounts`
+    fn name(&self) -> &'static str;
+
+    fn root(&self) -> Arc<dyn Inode>;
+}
+
+/// Creates a new instance of the file system type `name`
+pub fn new_file_system(name: &str) -> Option<Arc<dyn FileSystem>> {
+    match name {
+        "ramfs" => Some(Arc::new(ramfs::RamFs::new())),
+        "proc" => Some(Arc::new(procfs::ProcFs)),
+        "dev" => Some(Arc::new(devfs::DevFs::new())),
+        "sys" => Some(Arc::new(sysfs::SysFs::new())),
+        _ => None,
     }
+}
+
+/// How many symbolic links are followed while resolving a single path
+const MAX_SYMLINK_DEPTH: usize = 8;
 
-    pub fn directory_mut(&mut self, path: &str) -> Option<&mut Directory> {
-        let path = path
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect::<alloc::vec::Vec<_>>();
-        self.directory_mut_impl(path.as_slice())
+/// The file systems mounted at absolute paths, paths are resolved through the innermost mount
+pub struct Vfs {
+    mounts: spin::Mutex<BTreeMap<String, Arc<dyn FileSystem>>>,
+}
+
+impl Vfs {
+    pub const fn new() -> Self {
+        Vfs {
+            mounts: spin::Mutex::new(BTreeMap::new()),
+        }
     }
 
-    fn directory_impl(&mut self, path: &[&str]) -> Option<&Directory> {
-        match path {
-            [] => Some(self),
-            [name] => self.directories().get(*name),
-            [name, rest @ ..] => self
-                .directories()
-                .get_mut(*name)
-                .and_then(|dir| dir.directory_impl(rest)),
+    /// Mounts `fs` at the absolute `path`, which has to be an existing directory unless it is the root
+    pub fn mount(&self, path: &str, fs: Arc<dyn FileSystem>) -> Result<(), Error> {
+        let path = absolute_path("/", path);
+
+        if self.mounts().contains_key(&path) {
+            return Err(Error::Busy);
+        }
+        if path != "/" && self.lookup(&path)?.stat().file_type != FileType::Directory {
+            return Err(Error::NotADirectory);
         }
+
+        log::info!("mounting {} at {path}", fs.name());
+        self.mounts().insert(path, fs);
+        Ok(())
     }
 
-    fn directory_mut_impl(&mut self, path: &[&str]) -> Option<&mut Directory> {
-        match path {
-            [] => Some(self),
-            [name] => self.directories().get_mut(*name),
-            [name, rest @ ..] => self
-                .directories()
-                .get_mut(*name)
-                .and_then(|dir| dir.directory_mut_impl(rest)),
+    /// Unmounts the file system at `path`, the root and file systems with mounts below them stay mounted
+    pub fn umount(&self, path: &str) -> Result<(), Error> {
+        let path = absolute_path("/", path);
+        let mut mounts = self.mounts();
+
+        if !mounts.contains_key(&path) {
+            return Err(Error::NotFound);
+        }
+        let prefix = alloc::format!("{path}/");
+        if path == "/" || mounts.keys().any(|other| other.starts_with(&prefix)) {
+            return Err(Error::Busy);
         }
+
+        log::info!("unmounting {path}");
+        mounts.remove(&path);
+        Ok(())
     }
 
-    pub fn file(&mut self, path: &str) -> Option<&File> {
-        let path = path
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect::<alloc::vec::Vec<_>>();
-        self.file_impl(path.as_slice())
+    /// The mount points and the types of the file systems mounted on them
+    pub fn mount_points(&self) -> Vec<(String, &'static str)> {
+        self.mounts()
+            .iter()
+            .map(|(path, fs)| (path.clone(), fs.name()))
+            .collect()
     }
 
-    pub fn file_mut(&mut self, path: &str) -> Option<&mut File> {
-        let path = path
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect::<alloc::vec::Vec<_>>();
-        self.file_mut_impl(path.as_slice())
+    /// Returns the node at the absolute `path`, following symbolic links
+    pub fn lookup(&self, path: &str) -> Result<Arc<dyn Inode>, Error> {
+        self.resolve(path, true, 0)
     }
 
-    fn file_impl(&mut self, path: &[&str]) -> Option<&File> {
-        match path {
-            [] => None,
-            [name] => self.files().get(*name),
-            [name, rest @ ..] => self
-                .directories()
-                .get_mut(*name)
-                .and_then(|dir| dir.file_impl(rest)),
+    /// Like [`Vfs::lookup`], but returns a symbolic link at the end of `path` itself
+    pub fn lookup_link(&self, path: &str) -> Result<Arc<dyn Inode>, Error> {
+        self.resolve(path, false, 0)
+    }
+
+    fn resolve(&self, path: &str, follow: bool, depth: usize) -> Result<Arc<dyn Inode>, Error> {
+        // nodes of some file systems look at the mounts, so the lock is not held while walking the path
+        let mounts = self.mounts().clone();
+
+        let components = components(path);
+        let mut node = mounts.get("/").ok_or(Error::NotFound)?.root();
+        let mut current = String::new();
+        for (i, name) in components.iter().enumerate() {
+            node = node.lookup(name)?;
+
+            let parent_len = current.len();
+            current.push('/');
+            current.push_str(name);
+            if let Some(fs) = mounts.get(&current) {
+                node = fs.root();
+            }
+
+            let last = i + 1 == components.len();
+            if node.stat().file_type == FileType::Symlink && (follow || !last) {
+                if depth >= MAX_SYMLINK_DEPTH {
+                    return Err(Error::SymlinkLoop);
+                }
+
+                let parent = match &current[..parent_len] {
+                    "" => "/",
+                    parent => parent,
+                };
+                let mut target = absolute_path(parent, &node.readlink()?);
+                for rest in &components[i + 1..] {
+                    target.push('/');
+                    target.push_str(rest);
+                }
+                return self.resolve(&target, follow, depth + 1);
+            }
         }
+
+        Ok(node)
     }
 
-    fn file_mut_impl(&mut self, path: &[&str]) -> Option<&mut File> {
-        match path {
-            [] => None,
-            [name] => self.files().get_mut(*name),
-            [name, rest @ ..] => self
-                .directories()
-                .get_mut(*name)
-                .and_then(|dir| dir.file_mut_impl(rest)),
+    /// Returns the directory containing the absolute `path` and the name of the entry in it
+    pub fn lookup_parent<'a>(&self, path: &'a str) -> Result<(Arc<dyn Inode>, &'a str), Error> {
+        let mut components = components(path);
+        // the root has no parent
+        let name = components.pop().ok_or(Error::Busy)?;
+
+        let parent = self.lookup(&components.join("/"))?;
+        if parent.stat().file_type != FileType::Directory {
+            return Err(Error::NotADirectory);
         }
+        Ok((parent, name))
+    }
+
+    /// Creates an empty regular file or directory at the absolute `path`, its parent has to exist
+    pub fn create(&self, path: &str, file_type: FileType) -> Result<Arc<dyn Inode>, Error> {
+        let (parent, name) = self.lookup_parent(path)?;
+        parent.create(name, file_type)
     }
 
-    pub fn empty() -> Self {
-        Directory::Regular {
-            files: alloc::collections::BTreeMap::new(),
-            directories: alloc::collections::BTreeMap::new(),
+    /// Removes the file at the absolute `path`, directories are removed with [`Vfs::rmdir`]
+    pub fn unlink(&self, path: &str) -> Result<(), Error> {
+        let (parent, name) = self.lookup_parent(path)?;
+        if parent.lookup(name)?.stat().file_type == FileType::Directory {
+            return Err(Error::IsADirectory);
         }
+        parent.unlink(name)
     }
 
-    pub fn create_directories(&mut self, path: &[&str]) -> &mut Directory {
-        match path {
-            [] => self,
-            &[name] => self
-                .directories()
-                .entry(name.to_string())
-                .or_insert_with(Directory::empty),
-            [name, rest @ ..] => {
-                let dir = self
-                    .directories()
-                    .entry((*name).to_string())
-                    .or_insert_with(Directory::empty);
-                dir.create_directories(rest)
-            }
+    /// Removes the empty directory at the absolute `path`, mount points cannot be removed
+    pub fn rmdir(&self, path: &str) -> Result<(), Error> {
+        let path = absolute_path("/", path);
+        if self.mounts().contains_key(&path) {
+            return Err(Error::Busy);
+        }
+
+        let (parent, name) = self.lookup_parent(&path)?;
+        let node = parent.lookup(name)?;
+        if node.stat().file_type != FileType::Directory {
+            return Err(Error::NotADirectory);
         }
+        if !node.readdir()?.is_empty() {
+            return Err(Error::NotEmpty);
+        }
+        parent.unlink(name)
     }
 
-    pub fn create_file(&mut self, path: &str, file: File) -> &mut File {
-        let path = path
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect::<alloc::vec::Vec<_>>();
-        self.create_file_(path.as_slice(), file)
-    }
-
-    fn create_file_(&mut self, path: &[&str], file: File) -> &mut File {
-        match path {
-            [] => panic!("Cannot create file at root"),
-            &[name] => self.files().entry(name.to_string()).or_insert(file),
-            [name, rest @ ..] => {
-                let dir = self
-                    .directories()
-                    .entry((*name).to_string())
-                    .or_insert_with(Directory::empty);
-                dir.create_file_(rest, file)
+    /// Moves the node at the absolute path `old` to `new`, replacing a file or an empty directory there.
+    /// Both paths have to be on the same file system and neither can be a mount point or contain one.
+    pub fn rename(&self, old: &str, new: &str) -> Result<(), Error> {
+        let (old, new) = (absolute_path("/", old), absolute_path("/", new));
+        if old == new {
+            return self.lookup(&old).map(|_| ());
+        }
+        if new.starts_with(&alloc::format!("{old}/")) {
+            return Err(Error::InvalidInput);
+        }
+        {
+            let mounts = self.mounts();
+            let old_prefix = alloc::format!("{old}/");
+            if mounts
+                .keys()
+                .any(|mount| *mount == old || *mount == new || mount.starts_with(&old_prefix))
+            {
+                return Err(Error::Busy);
+            }
+            let mount_of = |path: &str| {
+                mounts
+                    .keys()
+                    .filter(|mount| *mount == "/" || path.starts_with(&alloc::format!("{mount}/")))
+                    .max_by_key(|mount| mount.len())
+                    .cloned()
+            };
+            if mount_of(&old) != mount_of(&new) {
+                return Err(Error::CrossDevice);
             }
         }
-    }
 
-    fn print_impl(&mut self, indent: usize) {
-        for (name, file) in self.files() {
-            match file {
-                File::Regular { contents } => {
-                    log::debug!("{:indent$}+ '{name}' ({} B)", "", contents.len());
-                }
-                File::Special { .. } => {
-                    log::debug!("{:indent$}+ '{name}' (special)", "");
-                }
-                File::OwnedStream { stream_type } => {
-                    log::debug!("{:indent$}+ '{name}' (stream {:?})", "", stream_type);
-                }
-                File::ForeignStream { stream_type } => {
-                    log::debug!(
-                        "{:indent$}+ '{name}' (foreign stream {:?})",
-                        "",
-                        stream_type
-                    );
+        let (old_parent, old_name) = self.lookup_parent(&old)?;
+        let (new_parent, new_name) = self.lookup_parent(&new)?;
+        let node = old_parent.lookup(old_name)?;
+
+        match new_parent.lookup(new_name) {
+            Ok(existing) => {
+                match (node.stat().file_type, existing.stat().file_type) {
+                    (FileType::Directory, FileType::Directory) => {
+                        if !existing.readdir()?.is_empty() {
+                            return Err(Error::NotEmpty);
+                        }
+                    }
+                    (_, FileType::Directory) => return Err(Error::IsADirectory),
+                    (FileType::Directory, _) => return Err(Error::NotADirectory),
+                    _ => {}
                 }
+                new_parent.unlink(new_name)?;
             }
+            Err(Error::NotFound) => {}
+            Err(e) => return Err(e),
         }
-        for (name, dir) in self.directories() {
-            log::debug!("{:indent$}+ {name}/", "");
-            dir.print_impl(indent + 2);
-        }
+
+        new_parent.link(new_name, node)?;
+        old_parent.unlink(old_name)
     }
 
-    pub fn print(&mut self) {
-        self.print_impl(0);
-    }
-
-    pub fn create_special_directory(
-        &mut self,
-        name: &str,
-        update: fn(
-            files: &mut alloc::collections::BTreeMap<String, File>,
-            directories: &mut alloc::collections::BTreeMap<String, Directory>,
-        ),
-    ) -> &mut Directory {
-        let path = name
-            .split('/')
-            .filter(|s| !s.is_empty())
-            .collect::<alloc::vec::Vec<_>>();
-        self.create_special_directory_(path.as_slice(), update)
-    }
-
-    fn create_special_directory_(
-        &mut self,
-        path: &[&str],
-        update: fn(
-            files: &mut alloc::collections::BTreeMap<String, File>,
-            directories: &mut alloc::collections::BTreeMap<String, Directory>,
-        ),
-    ) -> &mut Directory {
-        match path {
-            [] => panic!("Cannot create special directory at root"),
-            &[name] => self
-                .directories()
-                .entry(name.to_string())
-                .or_insert_with(|| Directory::Special {
-                    files: alloc::collections::BTreeMap::new(),
-                    directories: alloc::collections::BTreeMap::new(),
-                    update,
-                }),
-            [name, rest @ ..] => {
-                let dir = self
-                    .directories()
-                    .entry((*name).to_string())
-                    .or_insert_with(|| Directory::Special {
-                        files: alloc::collections::BTreeMap::new(),
-                        directories: alloc::collections::BTreeMap::new(),
-                        update,
-                    });
-                dir.create_special_directory_(rest, update)
-            }
+    /// Creates the directory at the absolute `path` and the directories leading to it if they do not exist
+    pub fn create_directories(&self, path: &str) -> Result<Arc<dyn Inode>, Error> {
+        let mut current = String::new();
+        let mut node = self.lookup("/")?;
+        for name in components(path) {
+            current.push('/');
+            current.push_str(name);
+            node = match self.lookup(&current) {
+                Ok(node) => node,
+                Err(Error::NotFound) => node.create(name, FileType::Directory)?,
+                Err(e) => return Err(e),
+            };
         }
+        Ok(node)
+    }
+
+    /// Creates the directories leading to the absolute `path` and links `node` there
+    pub fn create_node(&self, path: &str, node: Arc<dyn Inode>) -> Result<(), Error> {
+        let mut components = components(path);
+        let name = components.pop().ok_or(Error::AlreadyExists)?;
+
+        self.create_directories(&components.join("/"))?
+            .link(name, node)
+    }
+
+    fn mounts(&self) -> spin::MutexGuard<'_, BTreeMap<String, Arc<dyn FileSystem>>> {
+        self.mounts.try_lock().expect("Failed to lock mount table")
     }
 }
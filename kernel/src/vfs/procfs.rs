@@ -0,0 +1,593 @@
+//! Information about the processes and the kernel, generated when it is read
+
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+use core::fmt::Write as _;
+
+use super::{
+    special::{ForeignStream, SpecialFile},
+    DirEntry, Error, FileSystem, FileType, Inode, Stat,
+};
+use crate::process::{memory::Backing, FileDescriptor, OwnedStreamType, State, PROCESSES};
+
+pub struct ProcFs;
+
+impl FileSystem for ProcFs {
+    fn name(&self) -> &'static str {
+        "proc"
+    }
+
+    fn root(&self) -> Arc<dyn Inode> {
+        Arc::new(ProcRoot)
+    }
+}
+
+/// The nodes are generated on every lookup, so their number is made of the process and the entry.
+/// Nodes about the whole system use pid 0, which no process has, and entry 0 is the directory itself.
+fn inode_number(pid: u32, entry: u64) -> u64 {
+    u64::from(pid) << 32 | entry
+}
+
+/// The entry of `name` in `entries`, counted from 1
+fn entry_number(entries: &[(&str, FileType)], name: &str) -> u64 {
+    entries
+        .iter()
+        .position(|&(entry, _)| entry == name)
+        .map_or(0, |index| index as u64 + 1)
+}
+
+fn process_exists(pid: u32) -> bool {
+    PROCESSES.processes().iter().any(|p| p.pid() == pid)
+}
+
+/// Contains a directory for every process and the files about the whole system
+struct ProcRoot;
+
+impl ProcRoot {
+    const ENTRIES: [(&'static str, FileType); 6] = [
+        ("self", FileType::Symlink),
+        ("mounts", FileType::Regular),
+        ("meminfo", FileType::Regular),
+        ("uptime", FileType::Regular),
+        ("interrupts", FileType::Regular),
+        ("cpuinfo", FileType::Regular),
+    ];
+}
+
+impl Inode for ProcRoot {
+    fn inode_number(&self) -> u64 {
+        inode_number(0, 0)
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Directory, 0)
+    }
+
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>, Error> {
+        let number = inode_number(0, entry_number(&Self::ENTRIES, name));
+        match name {
+            "self" => Ok(Arc::new(ProcSelf)),
+            "mounts" => Ok(Arc::new(mounts().with_inode_number(number))),
+            "meminfo" => Ok(Arc::new(meminfo().with_inode_number(number))),
+            "uptime" => Ok(Arc::new(uptime().with_inode_number(number))),
+            "interrupts" => Ok(Arc::new(interrupts().with_inode_number(number))),
+            "cpuinfo" => Ok(Arc::new(cpuinfo().with_inode_number(number))),
+            name => match name.parse::<u32>() {
+                Ok(pid) if process_exists(pid) => Ok(Arc::new(ProcessDirectory { pid })),
+                _ => Err(Error::NotFound),
+            },
+        }
+    }
+
+    fn readdir(&self) -> Result<Vec<DirEntry>, Error> {
+        let mut entries = Self::ENTRIES
+            .iter()
+            .map(|&(name, file_type)| DirEntry {
+                name: String::from(name),
+                file_type,
+            })
+            .collect::<Vec<_>>();
+        entries.extend(PROCESSES.processes().iter().map(|p| DirEntry {
+            name: format!("{}", p.pid()),
+            file_type: FileType::Directory,
+        }));
+        Ok(entries)
+    }
+}
+
+/// A link to the directory of the process that follows it
+struct ProcSelf;
+
+impl Inode for ProcSelf {
+    fn inode_number(&self) -> u64 {
+        inode_number(0, entry_number(&ProcRoot::ENTRIES, "self"))
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Symlink, 0)
+    }
+
+    fn readlink(&self) -> Result<String, Error> {
+        // kernel threads have no directory
+        let pid = PROCESSES
+            .current()
+            .and_then(|thread| thread.pid())
+            .ok_or(Error::NotFound)?;
+        Ok(format!("{pid}"))
+    }
+}
+
+fn mounts() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        let mut written = 0;
+        for (path, name) in crate::FILE_SYSTEM.mount_points() {
+            written += writer.write(format!("{name} {path}\n").as_bytes())?;
+        }
+        Ok(written)
+    })
+}
+
+/// The files about one process, they are empty once the process is reaped
+struct ProcessDirectory {
+    pid: u32,
+}
+
+impl ProcessDirectory {
+    const ENTRIES: [(&'static str, FileType); 6] = [
+        ("status", FileType::Regular),
+        ("cmdline", FileType::Regular),
+        ("fd", FileType::Directory),
+        ("stdin", FileType::Stream),
+        ("stdout", FileType::Stream),
+        ("memmap", FileType::Regular),
+    ];
+}
+
+impl Inode for ProcessDirectory {
+    fn inode_number(&self) -> u64 {
+        inode_number(self.pid, 0)
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Directory, Self::ENTRIES.len())
+    }
+
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>, Error> {
+        let pid = self.pid;
+        if !process_exists(pid) {
+            return Err(Error::NotFound);
+        }
+
+        let number = inode_number(pid, entry_number(&Self::ENTRIES, name));
+        match name {
+            "status" => Ok(Arc::new(status(pid).with_inode_number(number))),
+            "cmdline" => Ok(Arc::new(cmdline(pid).with_inode_number(number))),
+            "fd" => Ok(Arc::new(FdDirectory { pid })),
+            "stdin" => Ok(Arc::new(ForeignStream::new(
+                crate::process::ForeignStreamType::Process {
+                    pid,
+                    file_descriptor: 0,
+                },
+                number,
+            ))),
+            "stdout" => Ok(Arc::new(ForeignStream::new(
+                crate::process::ForeignStreamType::Process {
+                    pid,
+                    file_descriptor: 1,
+                },
+                number,
+            ))),
+            "memmap" => Ok(Arc::new(memmap(pid).with_inode_number(number))),
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn readdir(&self) -> Result<Vec<DirEntry>, Error> {
+        if !process_exists(self.pid) {
+            return Err(Error::NotFound);
+        }
+
+        Ok(Self::ENTRIES
+            .iter()
+            .map(|&(name, file_type)| DirEntry {
+                name: String::from(name),
+                file_type,
+            })
+            .collect())
+    }
+}
+
+/// The state of the process as a whole, derived from its threads
+fn process_state(pid: u32) -> &'static str {
+    if PROCESSES
+        .with_process(pid, crate::process::Process::exit_status)
+        .is_some()
+    {
+        return "zombie";
+    }
+
+    let states = PROCESSES
+        .threads()
+        .values()
+        .filter(|t| t.pid() == Some(pid))
+        .map(crate::process::thread::Thread::state)
+        .filter(|state| !matches!(state, State::Terminated(_)))
+        .collect::<Vec<_>>();
+
+    if states.is_empty() {
+        "dead"
+    } else if states.contains(&State::Ready) {
+        "running"
+    } else if states.iter().all(|&state| state == State::Stopped) {
+        "stopped"
+    } else {
+        "sleeping"
+    }
+}
+
+fn status(pid: u32) -> SpecialFile {
+    SpecialFile::new(move |writer| {
+        if !process_exists(pid) {
+            return Ok(0);
+        }
+
+        let (parent, exit_status, name, memory) = PROCESSES.with_process(pid, |p| {
+            let name = p
+                .cmdline()
+                .first()
+                .map(|arg| String::from(arg.rsplit('/').next().unwrap_or(arg)))
+                .unwrap_or_default();
+            (p.parent(), p.exit_status(), name, p.memory.clone())
+        });
+
+        // writing may fault on a page of this address space
+        let (virtual_size, resident_pages) = {
+            let memory = memory.try_lock().expect("Failed to lock address space");
+            let virtual_size = memory
+                .regions()
+                .map(|region| region.end - region.start)
+                .sum::<u64>();
+            (virtual_size, memory.populated_pages().len())
+        };
+
+        let threads = PROCESSES
+            .threads()
+            .values()
+            .filter(|t| t.pid() == Some(pid))
+            .map(|t| (t.tid(), t.state(), t.nice(), t.priority(), t.stats))
+            .collect::<Vec<_>>();
+        let cpu_ticks = threads.iter().map(|t| t.4.cpu_ticks).sum::<u64>();
+        let cpu_time = crate::i8253::timer0().ticks_to_ms(cpu_ticks);
+
+        let mut lines = format!(
+            "name: {name}\npid: {pid}\nppid: {}\nstate: {}\n",
+            parent.unwrap_or(0),
+            process_state(pid),
+        );
+        if let Some(status) = exit_status {
+            writeln!(lines, "exit status: {status}").expect("Failed to format process status");
+        }
+        write!(
+            lines,
+            "threads: {}\ncpu time: {cpu_time} ms\nvirtual memory: {} KiB\nresident memory: {} KiB\n",
+            threads.len(),
+            virtual_size / 1024,
+            resident_pages * 4,
+        )
+        .expect("Failed to format process status");
+
+        for (tid, state, nice, priority, stats) in threads {
+            let cpu_time = crate::i8253::timer0().ticks_to_ms(stats.cpu_ticks);
+
+            write!(
+                lines,
+                "\ntid: {tid}\nstate: {state:?}\nnice: {nice}\npriority: {priority}\ncpu time: {cpu_time} ms\nscheduled: {}\npreempted: {}\nblocked: {}\n",
+                stats.scheduled,
+                stats.preempted,
+                stats.blocked,
+            )
+            .expect("Failed to format thread status");
+        }
+
+        writer.write(lines.as_bytes())
+    })
+}
+
+/// The arguments of the program, each one is followed by a NUL byte
+fn cmdline(pid: u32) -> SpecialFile {
+    SpecialFile::new(move |writer| {
+        if !process_exists(pid) {
+            return Ok(0);
+        }
+
+        let mut cmdline = Vec::new();
+        PROCESSES.with_process(pid, |p| {
+            for arg in p.cmdline() {
+                cmdline.extend_from_slice(arg.as_bytes());
+                cmdline.push(0);
+            }
+        });
+
+        writer.write(&cmdline)
+    })
+}
+
+/// Contains a link for every open file descriptor of a process
+struct FdDirectory {
+    pid: u32,
+}
+
+impl Inode for FdDirectory {
+    fn inode_number(&self) -> u64 {
+        inode_number(self.pid, entry_number(&ProcessDirectory::ENTRIES, "fd"))
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Directory, 0)
+    }
+
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>, Error> {
+        let pid = self.pid;
+        let fd = name.parse::<i32>().map_err(|_| Error::NotFound)?;
+        if !process_exists(pid) || PROCESSES.process(pid).file_descriptor(fd).is_none() {
+            return Err(Error::NotFound);
+        }
+
+        Ok(Arc::new(FdLink { pid, fd }))
+    }
+
+    fn readdir(&self) -> Result<Vec<DirEntry>, Error> {
+        if !process_exists(self.pid) {
+            return Err(Error::NotFound);
+        }
+
+        Ok(PROCESSES
+            .process(self.pid)
+            .file_descriptors()
+            .map(|fd| DirEntry {
+                name: format!("{fd}"),
+                file_type: FileType::Symlink,
+            })
+            .collect())
+    }
+}
+
+/// A link to the file a file descriptor refers to.
+/// Pipes have no path, their link is only a description like Linux' `pipe:[read]`.
+struct FdLink {
+    pid: u32,
+    fd: i32,
+}
+
+impl Inode for FdLink {
+    /// The links come after the entries of the process directory
+    fn inode_number(&self) -> u64 {
+        let first = ProcessDirectory::ENTRIES.len() as u64 + 1;
+        inode_number(self.pid, first + u64::from(self.fd.cast_unsigned()))
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Symlink, 0)
+    }
+
+    fn readlink(&self) -> Result<String, Error> {
+        if !process_exists(self.pid) {
+            return Err(Error::NotFound);
+        }
+
+        let file = PROCESSES
+            .process(self.pid)
+            .file_descriptor(self.fd)
+            .ok_or(Error::NotFound)?;
+        let file = file.try_lock().expect("Failed to lock file descriptor");
+
+        Ok(match &*file {
+            FileDescriptor::Regular { path, .. } => path.clone(),
+            FileDescriptor::ForeignStream {
+                stream_type:
+                    crate::process::ForeignStreamType::Process {
+                        pid,
+                        file_descriptor,
+                    },
+            } => match file_descriptor {
+                0 => format!("/proc/{pid}/stdin"),
+                _ => format!("/proc/{pid}/stdout"),
+            },
+            FileDescriptor::OwnedStream { stream_type, .. } => match stream_type {
+                OwnedStreamType::Keyboard => String::from("/dev/keyboard"),
+                OwnedStreamType::Mouse => String::from("/dev/mouse"),
+            },
+            FileDescriptor::Pipe { end } => match end.side() {
+                crate::process::pipe::PipeSide::Read => String::from("pipe:[read]"),
+                crate::process::pipe::PipeSide::Write => String::from("pipe:[write]"),
+            },
+            FileDescriptor::Device { device, .. } => format!("/dev/{}", device.name()),
+        })
+    }
+}
+
+fn memmap(pid: u32) -> SpecialFile {
+    SpecialFile::new(move |writer| {
+        if !process_exists(pid) {
+            return Ok(0);
+        }
+
+        let mut written = 0;
+
+        // writing may fault on a page of this address space
+        let regions = PROCESSES
+            .process(pid)
+            .memory
+            .try_lock()
+            .expect("Failed to lock address space")
+            .regions()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        written += writer.write(
+            format!(
+                "{:<16}{:<16}{:<8}{:<8}{}\n",
+                "start", "end", "name", "backing", "flags"
+            )
+            .as_bytes(),
+        )?;
+
+        for region in regions {
+            let backing = match region.backing {
+                Backing::Anonymous => "anon",
+                Backing::File { .. } => "file",
+            };
+
+            written += writer.write(
+                format!(
+                    "{:<#16x}{:<#16x}{:<8}{:<8}{:?}\n",
+                    region.start, region.end, region.name, backing, region.flags
+                )
+                .as_bytes(),
+            )?;
+        }
+
+        Ok(written)
+    })
+}
+
+fn meminfo() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        const FRAME_KIB: usize = 4;
+
+        let (used, total, shared) = {
+            let kernel_paging = crate::kernel_paging();
+            let frame_allocator = kernel_paging.frame_allocator();
+            let (_, used, total) = frame_allocator.stats();
+            (used, total, frame_allocator.shared_frames())
+        };
+        let (heap_size, heap_used) = crate::kernel::allocator::heap_usage();
+
+        writer.write(
+            format!(
+                "memory total: {} KiB\nmemory used: {} KiB\nmemory free: {} KiB\nmemory shared: {} KiB\nheap total: {} KiB\nheap used: {} KiB\nheap free: {} KiB\n",
+                total * FRAME_KIB,
+                used * FRAME_KIB,
+                (total - used) * FRAME_KIB,
+                shared * FRAME_KIB,
+                heap_size / 1024,
+                heap_used / 1024,
+                (heap_size - heap_used) / 1024,
+            )
+            .as_bytes(),
+        )
+    })
+}
+
+/// Seconds since boot with two decimal places
+fn uptime() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        let uptime = crate::i8253::timer0().time();
+        writer
+            .write(format!("{}.{:02}\n", uptime.as_secs(), uptime.subsec_millis() / 10).as_bytes())
+    })
+}
+
+fn interrupts() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        const IRQ_NAMES: [&str; 16] = [
+            "timer",
+            "keyboard",
+            "cascade",
+            "com2",
+            "com1",
+            "lpt2",
+            "floppy",
+            "lpt1",
+            "rtc",
+            "",
+            "",
+            "",
+            "mouse",
+            "fpu",
+            "ata primary",
+            "ata secondary",
+        ];
+
+        let (irq_counts, syscalls) = crate::idt::interrupt_counts();
+
+        let mut lines = String::new();
+        for (irq, (count, name)) in irq_counts.iter().zip(IRQ_NAMES).enumerate() {
+            writeln!(lines, "{irq:>3}: {count:>12}  {name}").expect("Failed to format interrupts");
+        }
+        writeln!(lines, "syscalls: {syscalls}").expect("Failed to format interrupts");
+
+        writer.write(lines.as_bytes())
+    })
+}
+
+fn cpuinfo() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        let cpuid = raw_cpuid::CpuId::new();
+
+        let mut lines = String::new();
+        if let Some(vendor) = cpuid.get_vendor_info() {
+            writeln!(lines, "vendor: {}", vendor.as_str()).expect("Failed to format cpu info");
+        }
+        if let Some(brand) = cpuid.get_processor_brand_string() {
+            writeln!(lines, "model name: {}", brand.as_str().trim())
+                .expect("Failed to format cpu info");
+        }
+
+        let mut features = Vec::new();
+        if let Some(info) = cpuid.get_feature_info() {
+            write!(
+                lines,
+                "family: {}\nmodel: {}\nstepping: {}\nlogical processors: {}\n",
+                info.family_id(),
+                info.model_id(),
+                info.stepping_id(),
+                info.max_logical_processor_ids(),
+            )
+            .expect("Failed to format cpu info");
+
+            features.extend(
+                [
+                    ("fpu", info.has_fpu()),
+                    ("tsc", info.has_tsc()),
+                    ("msr", info.has_msr()),
+                    ("pae", info.has_pae()),
+                    ("apic", info.has_apic()),
+                    ("mmx", info.has_mmx()),
+                    ("sse", info.has_sse()),
+                    ("sse2", info.has_sse2()),
+                    ("sse3", info.has_sse3()),
+                    ("ssse3", info.has_ssse3()),
+                    ("sse4_1", info.has_sse41()),
+                    ("sse4_2", info.has_sse42()),
+                    ("popcnt", info.has_popcnt()),
+                    ("aes", info.has_aesni()),
+                    ("fma", info.has_fma()),
+                    ("f16c", info.has_f16c()),
+                    ("avx", info.has_avx()),
+                    ("xsave", info.has_xsave()),
+                    ("x2apic", info.has_x2apic()),
+                    ("rdrand", info.has_rdrand()),
+                    ("hypervisor", info.has_hypervisor()),
+                ]
+                .into_iter()
+                .filter_map(|(name, present)| present.then_some(name)),
+            );
+        }
+        if let Some(info) = cpuid.get_extended_feature_info() {
+            features.extend(
+                [
+                    ("avx2", info.has_avx2()),
+                    ("bmi1", info.has_bmi1()),
+                    ("bmi2", info.has_bmi2()),
+                    ("sha", info.has_sha()),
+                    ("avx512f", info.has_avx512f()),
+                ]
+                .into_iter()
+                .filter_map(|(name, present)| present.then_some(name)),
+            );
+        }
+        writeln!(lines, "features: {}", features.join(" ")).expect("Failed to format cpu info");
+
+        writer.write(lines.as_bytes())
+    })
+}
@@ -0,0 +1,262 @@
+//! A file system that keeps everything in memory, used for the root file system
+
+use alloc::{collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use super::{DirEntry, Error, FileSystem, FileType, Inode, Stat};
+
+pub struct RamFs {
+    root: Arc<RamDirectory>,
+}
+
+impl RamFs {
+    pub fn new() -> Self {
+        RamFs {
+            root: Arc::new(RamDirectory::new()),
+        }
+    }
+}
+
+impl FileSystem for RamFs {
+    fn name(&self) -> &'static str {
+        "ramfs"
+    }
+
+    fn root(&self) -> Arc<dyn Inode> {
+        self.root.clone()
+    }
+}
+
+/// Largest size a file can grow to by writes, the contents are kept on the kernel heap
+pub const MAX_FILE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Grows `contents` to `size` bytes of zeros, failing instead of running the kernel heap out
+fn grow(contents: &mut Vec<u8>, size: usize) -> Result<(), Error> {
+    if size > MAX_FILE_SIZE {
+        return Err(Error::FileTooLarge);
+    }
+    contents
+        .try_reserve(size.saturating_sub(contents.len()))
+        .map_err(|_| Error::NoSpace)?;
+    contents.resize(size, 0);
+    Ok(())
+}
+
+pub struct RamFile {
+    inode_number: u64,
+    contents: spin::Mutex<Vec<u8>>,
+    mode: u32,
+    created: u64,
+    modified: AtomicU64,
+}
+
+impl RamFile {
+    pub fn new(contents: impl Into<Vec<u8>>) -> Self {
+        let now = super::now();
+        RamFile {
+            inode_number: super::next_inode_number(),
+            contents: spin::Mutex::new(contents.into()),
+            mode: 0o644,
+            created: now,
+            modified: AtomicU64::new(now),
+        }
+    }
+
+    /// Sets the permission bits, like 0o755 for a program
+    pub fn with_mode(mut self, mode: u32) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn contents(&self) -> spin::MutexGuard<'_, Vec<u8>> {
+        self.contents
+            .try_lock()
+            .expect("Failed to lock file contents")
+    }
+}
+
+impl Inode for RamFile {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat {
+            mode: self.mode,
+            created: self.created,
+            modified: self.modified.load(Ordering::Relaxed),
+            ..Stat::new(FileType::Regular, self.contents().len())
+        }
+    }
+
+    fn read(&self, offset: usize, writer: &mut dyn crate::io::Write) -> Result<usize, Error> {
+        let contents = self.contents();
+        Ok(writer.write(contents.get(offset..).unwrap_or_default())?)
+    }
+
+    /// Writing past the end fills the gap with zeros
+    fn write(&self, offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        let mut contents = self.contents();
+        let end = offset
+            .checked_add(bytes.len())
+            .ok_or(Error::InvalidOffset)?;
+        if contents.len() < end {
+            grow(&mut contents, end)?;
+        }
+        contents[offset..end].copy_from_slice(bytes);
+        self.modified.store(super::now(), Ordering::Relaxed);
+        Ok(bytes.len())
+    }
+
+    fn truncate(&self, size: usize) -> Result<(), Error> {
+        let mut contents = self.contents();
+        if size > contents.len() {
+            grow(&mut contents, size)?;
+        } else {
+            contents.truncate(size);
+        }
+        drop(contents);
+        self.modified.store(super::now(), Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// A symbolic link to a path, which is not checked until the link is followed
+pub struct RamSymlink {
+    inode_number: u64,
+    target: String,
+    created: u64,
+}
+
+impl RamSymlink {
+    pub fn new(target: impl Into<String>) -> Self {
+        RamSymlink {
+            inode_number: super::next_inode_number(),
+            target: target.into(),
+            created: super::now(),
+        }
+    }
+}
+
+impl Inode for RamSymlink {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat {
+            created: self.created,
+            modified: self.created,
+            ..Stat::new(FileType::Symlink, self.target.len())
+        }
+    }
+
+    fn readlink(&self) -> Result<String, Error> {
+        Ok(self.target.clone())
+    }
+}
+
+pub struct RamDirectory {
+    inode_number: u64,
+    entries: spin::Mutex<BTreeMap<String, Arc<dyn Inode>>>,
+    /// counted when entries are linked, so that the stat of a directory does not look at its whole subtree
+    subdirectories: AtomicU32,
+    mode: u32,
+    created: u64,
+    modified: AtomicU64,
+}
+
+impl RamDirectory {
+    pub fn new() -> Self {
+        let now = super::now();
+        RamDirectory {
+            inode_number: super::next_inode_number(),
+            entries: spin::Mutex::new(BTreeMap::new()),
+            subdirectories: AtomicU32::new(0),
+            mode: 0o755,
+            created: now,
+            modified: AtomicU64::new(now),
+        }
+    }
+
+    /// Sets the permission bits
+    pub fn with_mode(mut self, mode: u32) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    fn entries(&self) -> spin::MutexGuard<'_, BTreeMap<String, Arc<dyn Inode>>> {
+        self.entries
+            .try_lock()
+            .expect("Failed to lock directory entries")
+    }
+}
+
+impl Inode for RamDirectory {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat {
+            nlink: 2 + self.subdirectories.load(Ordering::Relaxed),
+            mode: self.mode,
+            created: self.created,
+            modified: self.modified.load(Ordering::Relaxed),
+            ..Stat::new(FileType::Directory, self.entries().len())
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>, Error> {
+        self.entries().get(name).cloned().ok_or(Error::NotFound)
+    }
+
+    fn readdir(&self) -> Result<Vec<DirEntry>, Error> {
+        Ok(self
+            .entries()
+            .iter()
+            .map(|(name, node)| DirEntry {
+                name: name.clone(),
+                file_type: node.stat().file_type,
+            })
+            .collect())
+    }
+
+    fn create(&self, name: &str, file_type: FileType) -> Result<Arc<dyn Inode>, Error> {
+        let node: Arc<dyn Inode> = match file_type {
+            FileType::Regular => Arc::new(RamFile::new(Vec::new())),
+            FileType::Directory => Arc::new(RamDirectory::new()),
+            FileType::Stream | FileType::Symlink | FileType::Device => {
+                return Err(Error::NotSupported)
+            }
+        };
+        self.link(name, node.clone())?;
+        Ok(node)
+    }
+
+    fn link(&self, name: &str, node: Arc<dyn Inode>) -> Result<(), Error> {
+        let is_directory = node.stat().file_type == FileType::Directory;
+
+        let mut entries = self.entries();
+        if entries.contains_key(name) {
+            return Err(Error::AlreadyExists);
+        }
+        entries.insert(String::from(name), node);
+
+        if is_directory {
+            self.subdirectories.fetch_add(1, Ordering::Relaxed);
+        }
+        self.modified.store(super::now(), Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn unlink(&self, name: &str) -> Result<(), Error> {
+        let node = self.entries().remove(name).ok_or(Error::NotFound)?;
+
+        if node.stat().file_type == FileType::Directory {
+            self.subdirectories.fetch_sub(1, Ordering::Relaxed);
+        }
+        self.modified.store(super::now(), Ordering::Relaxed);
+        Ok(())
+    }
+}
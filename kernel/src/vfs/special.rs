@@ -0,0 +1,238 @@
+//! Nodes that are generated by the kernel instead of storing data, the building blocks of procfs, devfs and sysfs
+
+use alloc::{boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+
+use super::{DirEntry, Error, FileType, Inode, Stat};
+use crate::{
+    driver::device::Device,
+    process::{FileDescriptor, ForeignStreamType, OwnedStreamType, WaitQueue},
+};
+
+type ReadFn =
+    dyn Fn(&mut dyn crate::io::Write) -> Result<usize, crate::io::WriterError> + Send + Sync;
+type WriteFn = dyn Fn(&[u8]) -> Result<usize, Error> + Send + Sync;
+
+/// A file whose contents are generated on every read
+pub struct SpecialFile {
+    inode_number: u64,
+    read: Box<ReadFn>,
+    write: Option<Box<WriteFn>>,
+}
+
+impl SpecialFile {
+    /// `read` writes all of the contents, the part before the offset of the read is skipped
+    pub fn new(
+        read: impl Fn(&mut dyn crate::io::Write) -> Result<usize, crate::io::WriterError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        SpecialFile {
+            inode_number: super::next_inode_number(),
+            read: Box::new(read),
+            write: None,
+        }
+    }
+
+    /// Makes the file writable, every write is passed to `write` as a whole regardless of the offset
+    pub fn with_write(
+        mut self,
+        write: impl Fn(&[u8]) -> Result<usize, Error> + Send + Sync + 'static,
+    ) -> Self {
+        self.write = Some(Box::new(write));
+        self
+    }
+
+    /// Replaces the number of a file that is generated on every lookup, so it keeps the same number
+    pub fn with_inode_number(mut self, inode_number: u64) -> Self {
+        self.inode_number = inode_number;
+        self
+    }
+}
+
+impl Inode for SpecialFile {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat {
+            mode: if self.write.is_some() { 0o644 } else { 0o444 },
+            ..Stat::new(FileType::Regular, 0)
+        }
+    }
+
+    fn read(&self, offset: usize, writer: &mut dyn crate::io::Write) -> Result<usize, Error> {
+        Ok((self.read)(&mut crate::io::Ignorer::ignoring(
+            offset, writer,
+        ))?)
+    }
+
+    fn write(&self, _offset: usize, bytes: &[u8]) -> Result<usize, Error> {
+        match &self.write {
+            Some(write) => write(bytes),
+            None => Err(Error::NotSupported),
+        }
+    }
+
+    /// There are no contents to discard, so writable files can be opened with truncation
+    fn truncate(&self, _size: usize) -> Result<(), Error> {
+        match self.write {
+            Some(_) => Ok(()),
+            None => Err(Error::NotSupported),
+        }
+    }
+}
+
+/// A directory with a fixed set of entries
+pub struct SpecialDirectory {
+    inode_number: u64,
+    entries: BTreeMap<String, Arc<dyn Inode>>,
+}
+
+impl SpecialDirectory {
+    pub fn new() -> Self {
+        SpecialDirectory {
+            inode_number: super::next_inode_number(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Adds `node` as `name`
+    pub fn with(mut self, name: &str, node: impl Inode + 'static) -> Self {
+        self.entries.insert(String::from(name), Arc::new(node));
+        self
+    }
+}
+
+impl Inode for SpecialDirectory {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        let subdirectories = self
+            .entries
+            .values()
+            .filter(|node| node.stat().file_type == FileType::Directory)
+            .count();
+
+        Stat {
+            nlink: 2 + subdirectories as u32,
+            ..Stat::new(FileType::Directory, self.entries.len())
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Result<Arc<dyn Inode>, Error> {
+        self.entries.get(name).cloned().ok_or(Error::NotFound)
+    }
+
+    fn readdir(&self) -> Result<Vec<DirEntry>, Error> {
+        Ok(self
+            .entries
+            .iter()
+            .map(|(name, node)| DirEntry {
+                name: name.clone(),
+                file_type: node.stat().file_type,
+            })
+            .collect())
+    }
+}
+
+/// Every open file gets its own buffer that is fed with the data of the stream
+pub struct OwnedStream {
+    inode_number: u64,
+    stream_type: OwnedStreamType,
+}
+
+impl OwnedStream {
+    pub fn new(stream_type: OwnedStreamType) -> Self {
+        OwnedStream {
+            inode_number: super::next_inode_number(),
+            stream_type,
+        }
+    }
+}
+
+impl Inode for OwnedStream {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Stream, 0)
+    }
+
+    fn open(&self) -> Option<FileDescriptor> {
+        Some(FileDescriptor::OwnedStream {
+            buffer: alloc::collections::VecDeque::with_capacity(1024),
+            max_size: 1024,
+            stream_type: self.stream_type,
+            readers: WaitQueue::new(),
+        })
+    }
+}
+
+/// Reads from the owned stream of another process
+pub struct ForeignStream {
+    inode_number: u64,
+    stream_type: ForeignStreamType,
+}
+
+impl ForeignStream {
+    /// The stream is generated on every lookup, so it gets the number of its entry instead of a new one
+    pub fn new(stream_type: ForeignStreamType, inode_number: u64) -> Self {
+        ForeignStream {
+            inode_number,
+            stream_type,
+        }
+    }
+}
+
+impl Inode for ForeignStream {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Stream, 0)
+    }
+
+    fn open(&self) -> Option<FileDescriptor> {
+        Some(FileDescriptor::ForeignStream {
+            stream_type: self.stream_type,
+        })
+    }
+}
+
+/// The node of a device, opening it gives a file descriptor that is read and written through the driver
+pub struct DeviceNode {
+    inode_number: u64,
+    device: &'static dyn Device,
+}
+
+impl DeviceNode {
+    pub fn new(device: &'static dyn Device) -> Self {
+        DeviceNode {
+            inode_number: super::next_inode_number(),
+            device,
+        }
+    }
+}
+
+impl Inode for DeviceNode {
+    fn inode_number(&self) -> u64 {
+        self.inode_number
+    }
+
+    fn stat(&self) -> Stat {
+        Stat::new(FileType::Device, self.device.size())
+    }
+
+    fn open(&self) -> Option<FileDescriptor> {
+        Some(FileDescriptor::Device {
+            device: self.device,
+            offset: 0,
+        })
+    }
+}
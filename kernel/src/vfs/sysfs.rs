@@ -0,0 +1,210 @@
+//! Information about the hardware, generated when it is read, and files to configure the kernel
+
+use alloc::{format, string::String, sync::Arc};
+use core::sync::atomic::Ordering;
+
+use crate::driver::pci::{Bar, PCIDevice, CONFIG_SPACE_SIZE};
+
+use super::{
+    special::{SpecialDirectory, SpecialFile},
+    Error, FileSystem, Inode,
+};
+
+pub struct SysFs {
+    root: Arc<SpecialDirectory>,
+}
+
+impl SysFs {
+    pub fn new() -> Self {
+        let root = SpecialDirectory::new()
+            .with(
+                "bus",
+                SpecialDirectory::new().with(
+                    "pci",
+                    SpecialDirectory::new().with("devices", pci_devices()),
+                ),
+            )
+            .with("memory", memory())
+            .with(
+                "kernel",
+                SpecialDirectory::new()
+                    .with("log_level", log_level())
+                    .with("kill_on_syscall_error", kill_on_syscall_error()),
+            );
+
+        SysFs {
+            root: Arc::new(root),
+        }
+    }
+}
+
+impl FileSystem for SysFs {
+    fn name(&self) -> &'static str {
+        "sys"
+    }
+
+    fn root(&self) -> Arc<dyn Inode> {
+        self.root.clone()
+    }
+}
+
+/// A directory named bus:device.function for every PCI device found at boot
+fn pci_devices() -> SpecialDirectory {
+    crate::driver::pci::devices()
+        .iter()
+        .fold(SpecialDirectory::new(), |directory, dev| {
+            directory.with(
+                &format!("{:02x}:{:02x}.{}", dev.bus, dev.device, dev.function),
+                pci_device(dev),
+            )
+        })
+}
+
+fn pci_device(dev: &'static PCIDevice) -> SpecialDirectory {
+    SpecialDirectory::new()
+        .with(
+            "vendor",
+            SpecialFile::new(|writer| {
+                writer.write(format!("{:#06x}\n", dev.header.vendor_id).as_bytes())
+            }),
+        )
+        .with(
+            "device",
+            SpecialFile::new(|writer| {
+                writer.write(format!("{:#06x}\n", dev.header.device_id).as_bytes())
+            }),
+        )
+        .with(
+            "class",
+            SpecialFile::new(|writer| {
+                writer.write(
+                    format!(
+                        "{:?} (prog if {:#04x})\n",
+                        dev.header.class, dev.header.prog_if
+                    )
+                    .as_bytes(),
+                )
+            }),
+        )
+        .with(
+            "resources",
+            SpecialFile::new(|writer| {
+                let mut written = 0;
+                for (index, bar) in &dev.bars {
+                    written +=
+                        writer.write(format!("BAR{index} {}\n", bar_line(bar)).as_bytes())?;
+                }
+                Ok(written)
+            }),
+        )
+        .with(
+            "irq",
+            SpecialFile::new(|writer| match dev.header.interrupt_line() {
+                Some(line) => writer.write(format!("{line}\n").as_bytes()),
+                None => writer.write(b"none\n"),
+            }),
+        )
+        .with(
+            "config",
+            SpecialFile::new(|writer| {
+                let mut config = [0; CONFIG_SPACE_SIZE];
+                dev.read_config(0, &mut config);
+                writer.write(&config)
+            }),
+        )
+}
+
+/// Like "memory 0xfebf0000 size 0x1000 32-bit" or "io 0xc000 size 0x40"
+fn bar_line(bar: &Bar) -> String {
+    match *bar {
+        Bar::Memory {
+            address,
+            size,
+            prefetchable,
+            is_64bit,
+        } => format!(
+            "memory {address:#x} size {size:#x} {}{}",
+            if is_64bit { "64-bit" } else { "32-bit" },
+            if prefetchable { " prefetchable" } else { "" }
+        ),
+        Bar::Io { port, size } => format!("io {port:#x} size {size:#x}"),
+    }
+}
+
+fn memory() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        let mut written = 0;
+
+        let kernel_paging = crate::kernel_paging();
+
+        written += writer.write(
+            alloc::format!("{:<#16} {:<#16} {}\n", "Base Address", "Length", "Type").as_bytes(),
+        )?;
+
+        for entry in kernel_paging.frame_allocator().memmap.iter() {
+            let line = alloc::format!(
+                "{:<#16x} {:<#16x} {:?}\n",
+                entry.base,
+                entry.len,
+                entry.type_,
+            );
+            written += writer.write(line.as_bytes())?;
+        }
+
+        let (allocated, used, total) = kernel_paging.frame_allocator().stats();
+        let shared = kernel_paging.frame_allocator().shared_frames();
+        written += writer.write(
+            alloc::format!(
+                "\nused frames: {used}/{total} ({allocated} allocated, {shared} shared)\n"
+            )
+            .as_bytes(),
+        )?;
+
+        Ok(written)
+    })
+}
+
+/// Parses the value written to a configuration file, surrounding whitespace like a trailing newline is ignored
+fn parse_value(bytes: &[u8]) -> Result<&str, Error> {
+    core::str::from_utf8(bytes)
+        .map(str::trim)
+        .map_err(|_| Error::InvalidInput)
+}
+
+/// The maximum level of the kernel log, one of off, error, warn, info, debug and trace
+fn log_level() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        writer.write(
+            alloc::format!("{}\n", log::max_level())
+                .to_lowercase()
+                .as_bytes(),
+        )
+    })
+    .with_write(|bytes| {
+        let level = parse_value(bytes)?
+            .parse::<log::LevelFilter>()
+            .map_err(|_| Error::InvalidInput)?;
+
+        crate::kernel::logger::KERNEL_LOGGER.set_level_filter(level);
+        log::info!("log level set to {level}");
+        Ok(bytes.len())
+    })
+}
+
+/// 1 if processes are terminated when a syscall fails instead of getting an error, 0 otherwise
+fn kill_on_syscall_error() -> SpecialFile {
+    SpecialFile::new(|writer| {
+        let enabled = crate::syscall::KILL_ON_SYSCALL_ERROR.load(Ordering::Relaxed);
+        writer.write(if enabled { b"1\n" } else { b"0\n" })
+    })
+    .with_write(|bytes| {
+        let enabled = match parse_value(bytes)? {
+            "0" => false,
+            "1" => true,
+            _ => return Err(Error::InvalidInput),
+        };
+
+        crate::syscall::KILL_ON_SYSCALL_ERROR.store(enabled, Ordering::Relaxed);
+        Ok(bytes.len())
+    })
+}
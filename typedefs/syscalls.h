@@ -17,8 +17,54 @@ enum syscall_id_t {
     SYSCALL_MAP_FRAMEBUFFER = 11,
     SYSCALL_WRITE = 12,
     SYSCALL_WAITPID = 13,
+    SYSCALL_MPROTECT = 14,
+    SYSCALL_BRK = 15,
+    SYSCALL_NICE = 16,
+    SYSCALL_THREAD_CREATE = 17,
+    SYSCALL_THREAD_EXIT = 18,
+    SYSCALL_THREAD_JOIN = 19,
+    SYSCALL_PIPE = 20,
+    SYSCALL_DUP = 21,
+    SYSCALL_DUP2 = 22,
+    SYSCALL_KILL = 23,
+    SYSCALL_SIGACTION = 24,
+    SYSCALL_SIGPROCMASK = 25,
+    SYSCALL_SIGRETURN = 26,
+    SYSCALL_GETPID = 27,
+    SYSCALL_GETPPID = 28,
+    SYSCALL_CHDIR = 29,
+    SYSCALL_GETCWD = 30,
+    SYSCALL_MOUNT = 31,
+    SYSCALL_UMOUNT = 32,
+    SYSCALL_UNLINK = 33,
+    SYSCALL_MKDIR = 34,
+    SYSCALL_RMDIR = 35,
+    SYSCALL_RENAME = 36,
+    SYSCALL_STAT = 37,
+    SYSCALL_FSTAT = 38,
+    SYSCALL_LSEEK = 39,
+    SYSCALL_PREAD = 40,
+    SYSCALL_PWRITE = 41,
+    SYSCALL_IOCTL = 42,
+    SYSCALL_GETDENTS = 43,
 };
 
+// returned in rax by every syscall, errors specific to a syscall are in its return value
+typedef uint32_t syscall_error_t;
+static const syscall_error_t SYSCALL_ERROR_NONE = 0;
+// a pointer passed to the syscall is not accessible by the process
+static const syscall_error_t SYSCALL_ERROR_FAULT = 1;
+// there is no syscall with the number in rax
+static const syscall_error_t SYSCALL_ERROR_UNKNOWN_SYSCALL = 2;
+// the syscall is not supported for the given file descriptor or arguments
+static const syscall_error_t SYSCALL_ERROR_NOT_SUPPORTED = 3;
+// the blocked syscall was interrupted to run a signal handler
+static const syscall_error_t SYSCALL_ERROR_INTERRUPTED = 4;
+// a buffer or string passed to the syscall is longer than the kernel copies at once
+static const syscall_error_t SYSCALL_ERROR_TOO_LARGE = 5;
+// the kernel has no memory left to copy the arguments of the syscall
+static const syscall_error_t SYSCALL_ERROR_NO_MEMORY = 6;
+
 struct syscall_print_t {
     struct string_const_t message;
 };
@@ -75,6 +121,9 @@ struct syscall_read_t {
 
 typedef uint32_t pid_t;
 
+// the file descriptor is closed by execve
+static const uint32_t SYSCALL_FD_FLAG_CLOSE_ON_EXEC = 1;
+
 struct syscall_fork_return_t {
     pid_t child_pid;
 };
@@ -82,15 +131,37 @@ struct syscall_fork_t {
     struct syscall_fork_return_t return_value;
 };
 
+// the read and write modes only restrict regular files, streams and devices can always be read and written
+typedef uint32_t syscall_open_mode_t;
+static const syscall_open_mode_t SYSCALL_OPEN_MODE_READ = 1;
+static const syscall_open_mode_t SYSCALL_OPEN_MODE_WRITE = 2;
+static const syscall_open_mode_t SYSCALL_OPEN_MODE_READ_WRITE = 3;
+// an empty regular file is created if nothing exists at the path
+static const syscall_open_mode_t SYSCALL_OPEN_MODE_CREATE = 4;
+// the contents of the file are discarded, needs SYSCALL_OPEN_MODE_WRITE
+static const syscall_open_mode_t SYSCALL_OPEN_MODE_TRUNCATE = 8;
+// every write goes to the end of the file
+static const syscall_open_mode_t SYSCALL_OPEN_MODE_APPEND = 16;
+
 typedef uint32_t syscall_open_error_t;
 static const syscall_open_error_t SYSCALL_OPEN_ERROR_NONE = 0;
 static const syscall_open_error_t SYSCALL_OPEN_ERROR_NOT_FOUND = 1;
+// a directory was opened for writing
+static const syscall_open_error_t SYSCALL_OPEN_ERROR_IS_A_DIRECTORY = 2;
+// a component of the path is not a directory
+static const syscall_open_error_t SYSCALL_OPEN_ERROR_NOT_A_DIRECTORY = 3;
+// the file system cannot create or truncate the file
+static const syscall_open_error_t SYSCALL_OPEN_ERROR_NOT_SUPPORTED = 4;
 struct syscall_open_return_t {
     fd_t fd;
     syscall_open_error_t error;
 };
 struct syscall_open_t {
     struct string_const_t path;
+    // SYSCALL_FD_FLAG_*
+    uint32_t flags;
+    // SYSCALL_OPEN_MODE_*
+    syscall_open_mode_t mode;
     struct syscall_open_return_t return_value;
 };
 
@@ -105,32 +176,138 @@ struct syscall_close_t {
     struct syscall_close_return_t return_value;
 };
 
+static const uint32_t SYSCALL_MMAP_PROT_NONE = 0;
+static const uint32_t SYSCALL_MMAP_PROT_READ = 1;
+static const uint32_t SYSCALL_MMAP_PROT_WRITE = 2;
+static const uint32_t SYSCALL_MMAP_PROT_EXEC = 4;
+
+// the mapping is placed exactly at addr, replacing existing mappings
+static const uint32_t SYSCALL_MMAP_FLAG_FIXED = 1;
+// the mapping is zero-filled and not backed by a file
+static const uint32_t SYSCALL_MMAP_FLAG_ANONYMOUS = 2;
+// the mapping is shared with forked children instead of being copied
+static const uint32_t SYSCALL_MMAP_FLAG_SHARED = 4;
+
 typedef uint32_t syscall_mmap_error_t;
 static const syscall_mmap_error_t SYSCALL_MMAP_ERROR_NONE = 0;
+static const syscall_mmap_error_t SYSCALL_MMAP_ERROR_INVALID_SIZE = 1;
+static const syscall_mmap_error_t SYSCALL_MMAP_ERROR_INVALID_ADDR = 2;
+static const syscall_mmap_error_t SYSCALL_MMAP_ERROR_INVALID_FLAGS = 3;
+static const syscall_mmap_error_t SYSCALL_MMAP_ERROR_NO_MEMORY = 4;
 struct syscall_mmap_return_t {
     void *addr;
     syscall_mmap_error_t error;
 };
 struct syscall_mmap_t {
-    uint32_t size;
+    // hint, or the exact address with SYSCALL_MMAP_FLAG_FIXED
+    void *addr;
+    uint64_t size;
+    uint32_t prot;
+    uint32_t flags;
     struct syscall_mmap_return_t return_value;
 };
 
 typedef uint32_t syscall_munmap_error_t;
 static const syscall_munmap_error_t SYSCALL_MUNMAP_ERROR_NONE = 0;
 static const syscall_munmap_error_t SYSCALL_MUNMAP_ERROR_INVALID_ADDR = 1;
+static const syscall_munmap_error_t SYSCALL_MUNMAP_ERROR_INVALID_SIZE = 2;
 struct syscall_munmap_return_t {
     syscall_munmap_error_t error;
 };
 struct syscall_munmap_t {
     void *addr;
-    uint32_t size;
+    uint64_t size;
     struct syscall_munmap_return_t return_value;
 };
 
+typedef uint32_t syscall_mprotect_error_t;
+static const syscall_mprotect_error_t SYSCALL_MPROTECT_ERROR_NONE = 0;
+static const syscall_mprotect_error_t SYSCALL_MPROTECT_ERROR_INVALID_ADDR = 1;
+static const syscall_mprotect_error_t SYSCALL_MPROTECT_ERROR_INVALID_SIZE = 2;
+static const syscall_mprotect_error_t SYSCALL_MPROTECT_ERROR_NOT_MAPPED = 3;
+struct syscall_mprotect_return_t {
+    syscall_mprotect_error_t error;
+};
+struct syscall_mprotect_t {
+    void *addr;
+    uint64_t size;
+    uint32_t prot;
+    struct syscall_mprotect_return_t return_value;
+};
+
+typedef uint32_t syscall_brk_error_t;
+static const syscall_brk_error_t SYSCALL_BRK_ERROR_NONE = 0;
+static const syscall_brk_error_t SYSCALL_BRK_ERROR_INVALID_ADDR = 1;
+static const syscall_brk_error_t SYSCALL_BRK_ERROR_NO_MEMORY = 2;
+struct syscall_brk_return_t {
+    // the new program break, or the current one on error
+    void *addr;
+    syscall_brk_error_t error;
+};
+struct syscall_brk_t {
+    // the new program break, or null to query the current one
+    void *addr;
+    struct syscall_brk_return_t return_value;
+};
+
+typedef uint32_t syscall_nice_error_t;
+static const syscall_nice_error_t SYSCALL_NICE_ERROR_NONE = 0;
+// only init may lower the nice value, a higher priority would starve the other threads
+static const syscall_nice_error_t SYSCALL_NICE_ERROR_PERMISSION_DENIED = 1;
+struct syscall_nice_return_t {
+    // the new nice value, between -20 (highest priority) and 19 (lowest priority)
+    int32_t nice;
+    syscall_nice_error_t error;
+};
+struct syscall_nice_t {
+    // added to the nice value of the thread, the result is clamped
+    int32_t increment;
+    struct syscall_nice_return_t return_value;
+};
+
+typedef uint32_t tid_t;
+
+typedef uint32_t syscall_thread_create_error_t;
+static const syscall_thread_create_error_t SYSCALL_THREAD_CREATE_ERROR_NONE = 0;
+static const syscall_thread_create_error_t SYSCALL_THREAD_CREATE_ERROR_INVALID_ADDR = 1;
+struct syscall_thread_create_return_t {
+    tid_t tid;
+    syscall_thread_create_error_t error;
+};
+struct syscall_thread_create_t {
+    // called with `arg` as its only argument, it must not return
+    void *entry;
+    // top of the stack of the new thread
+    void *stack;
+    void *arg;
+    struct syscall_thread_create_return_t return_value;
+};
+
+// exits the calling thread, the process exits with `status` if it is the last thread
+struct syscall_thread_exit_t {
+    uint32_t status;
+};
+
+typedef uint32_t syscall_thread_join_error_t;
+static const syscall_thread_join_error_t SYSCALL_THREAD_JOIN_ERROR_NONE = 0;
+// there is no other thread with the id in this process, or it was joined already
+static const syscall_thread_join_error_t SYSCALL_THREAD_JOIN_ERROR_NOT_FOUND = 1;
+struct syscall_thread_join_return_t {
+    uint32_t status;
+    syscall_thread_join_error_t error;
+};
+struct syscall_thread_join_t {
+    tid_t tid;
+    struct syscall_thread_join_return_t return_value;
+};
+
 typedef uint32_t syscall_execve_error_t;
 static const syscall_execve_error_t SYSCALL_EXECVE_ERROR_NONE = 0;
 static const syscall_execve_error_t SYSCALL_EXECVE_ERROR_NOT_FOUND = 1;
+// the file is not an x86_64 executable that can be loaded, the calling process keeps running
+static const syscall_execve_error_t SYSCALL_EXECVE_ERROR_INVALID_EXECUTABLE = 2;
+// the arguments and environment together are larger than 128 KiB, the calling process keeps running
+static const syscall_execve_error_t SYSCALL_EXECVE_ERROR_ARGUMENTS_TOO_LONG = 3;
 struct syscall_execve_return_t {
     syscall_execve_error_t error;
 };
@@ -155,6 +332,14 @@ struct syscall_map_framebuffer_t {
 typedef uint32_t syscall_write_error_t;
 static const syscall_write_error_t SYSCALL_WRITE_ERROR_NONE = 0;
 static const syscall_write_error_t SYSCALL_WRITE_ERROR_INVALID_FD = 1;
+// all read ends of the pipe are closed
+static const syscall_write_error_t SYSCALL_WRITE_ERROR_BROKEN_PIPE = 2;
+// the file does not accept the written value, like an unknown setting written to a file in /sys
+static const syscall_write_error_t SYSCALL_WRITE_ERROR_INVALID_VALUE = 3;
+// the write would make the file larger than the file system allows
+static const syscall_write_error_t SYSCALL_WRITE_ERROR_FILE_TOO_LARGE = 4;
+// there is no memory left to store the file
+static const syscall_write_error_t SYSCALL_WRITE_ERROR_NO_SPACE = 5;
 struct syscall_write_return_t {
     uint32_t bytes_written;
     syscall_write_error_t error;
@@ -168,12 +353,501 @@ struct syscall_write_t {
 
 typedef uint32_t syscall_waitpid_error_t;
 static const syscall_waitpid_error_t SYSCALL_WAITPID_ERROR_NONE = 0;
+// the process has no child with the pid
 static const syscall_waitpid_error_t SYSCALL_WAITPID_ERROR_INVALID_PID = 1;
+// waits for whichever child exits first
+static const pid_t SYSCALL_WAITPID_ANY_CHILD = 0;
+// returns pid 0 instead of waiting if no matching child has exited yet
+static const uint32_t SYSCALL_WAITPID_OPTION_NON_BLOCKING = 1;
 struct syscall_waitpid_return_t {
+    // the child that exited, it is gone once its status was returned
+    pid_t pid;
     uint32_t status;
     syscall_waitpid_error_t error;
 };
 struct syscall_waitpid_t {
+    // a child of the calling process or SYSCALL_WAITPID_ANY_CHILD
     pid_t pid;
+    // SYSCALL_WAITPID_OPTION_*
+    uint32_t options;
     struct syscall_waitpid_return_t return_value;
 };
+
+struct syscall_pipe_return_t {
+    fd_t read_fd;
+    fd_t write_fd;
+};
+// reads from the read end wait until data is written, or return nothing once all write ends are closed
+struct syscall_pipe_t {
+    // SYSCALL_FD_FLAG_*, applied to both ends
+    uint32_t flags;
+    struct syscall_pipe_return_t return_value;
+};
+
+typedef uint32_t syscall_dup_error_t;
+static const syscall_dup_error_t SYSCALL_DUP_ERROR_NONE = 0;
+static const syscall_dup_error_t SYSCALL_DUP_ERROR_INVALID_FD = 1;
+struct syscall_dup_return_t {
+    fd_t fd;
+    syscall_dup_error_t error;
+};
+// the new file descriptor is the lowest free one and shares the open file, including its offset
+struct syscall_dup_t {
+    fd_t fd;
+    // SYSCALL_FD_FLAG_*
+    uint32_t flags;
+    struct syscall_dup_return_t return_value;
+};
+
+typedef uint32_t syscall_dup2_error_t;
+static const syscall_dup2_error_t SYSCALL_DUP2_ERROR_NONE = 0;
+static const syscall_dup2_error_t SYSCALL_DUP2_ERROR_INVALID_FD = 1;
+struct syscall_dup2_return_t {
+    fd_t fd;
+    syscall_dup2_error_t error;
+};
+// new_fd is closed first if it is open, nothing happens if it is the same as fd
+struct syscall_dup2_t {
+    fd_t fd;
+    fd_t new_fd;
+    // SYSCALL_FD_FLAG_*
+    uint32_t flags;
+    struct syscall_dup2_return_t return_value;
+};
+
+// signals are numbered from 1 to 31, bit n of a signal_mask_t stands for signal n
+typedef uint32_t signal_t;
+typedef uint32_t signal_mask_t;
+static const signal_t SIGHUP = 1;
+static const signal_t SIGINT = 2;
+static const signal_t SIGQUIT = 3;
+static const signal_t SIGILL = 4;
+static const signal_t SIGABRT = 6;
+static const signal_t SIGFPE = 8;
+// cannot be handled, ignored or blocked
+static const signal_t SIGKILL = 9;
+static const signal_t SIGUSR1 = 10;
+static const signal_t SIGSEGV = 11;
+static const signal_t SIGUSR2 = 12;
+static const signal_t SIGPIPE = 13;
+static const signal_t SIGALRM = 14;
+static const signal_t SIGTERM = 15;
+static const signal_t SIGCHLD = 17;
+static const signal_t SIGCONT = 18;
+// cannot be handled, ignored or blocked
+static const signal_t SIGSTOP = 19;
+static const signal_t SIGTSTP = 20;
+static const signal_t SIGNAL_MAX = 31;
+
+typedef uint32_t syscall_kill_error_t;
+static const syscall_kill_error_t SYSCALL_KILL_ERROR_NONE = 0;
+static const syscall_kill_error_t SYSCALL_KILL_ERROR_INVALID_PID = 1;
+static const syscall_kill_error_t SYSCALL_KILL_ERROR_INVALID_SIGNAL = 2;
+// the signal would terminate or stop the init process, which has no handler for it
+static const syscall_kill_error_t SYSCALL_KILL_ERROR_PERMISSION_DENIED = 3;
+struct syscall_kill_return_t {
+    syscall_kill_error_t error;
+};
+struct syscall_kill_t {
+    pid_t pid;
+    signal_t signal;
+    struct syscall_kill_return_t return_value;
+};
+
+static const uint64_t SIGNAL_HANDLER_DEFAULT = 0;
+static const uint64_t SIGNAL_HANDLER_IGNORE = 1;
+
+typedef uint32_t syscall_sigaction_error_t;
+static const syscall_sigaction_error_t SYSCALL_SIGACTION_ERROR_NONE = 0;
+static const syscall_sigaction_error_t SYSCALL_SIGACTION_ERROR_INVALID_SIGNAL = 1;
+struct syscall_sigaction_return_t {
+    uint64_t old_handler;
+    syscall_sigaction_error_t error;
+};
+struct syscall_sigaction_t {
+    signal_t signal;
+    // SIGNAL_HANDLER_* or the address of a function that is called with the signal as its only argument
+    uint64_t handler;
+    // blocked in addition to the signal itself while the handler runs
+    signal_mask_t mask;
+    // the handler returns to this address, which has to make the sigreturn syscall
+    uint64_t restorer;
+    struct syscall_sigaction_return_t return_value;
+};
+
+typedef uint32_t syscall_sigprocmask_how_t;
+static const syscall_sigprocmask_how_t SYSCALL_SIGPROCMASK_BLOCK = 0;
+static const syscall_sigprocmask_how_t SYSCALL_SIGPROCMASK_UNBLOCK = 1;
+static const syscall_sigprocmask_how_t SYSCALL_SIGPROCMASK_SET = 2;
+
+typedef uint32_t syscall_sigprocmask_error_t;
+static const syscall_sigprocmask_error_t SYSCALL_SIGPROCMASK_ERROR_NONE = 0;
+static const syscall_sigprocmask_error_t SYSCALL_SIGPROCMASK_ERROR_INVALID_HOW = 1;
+struct syscall_sigprocmask_return_t {
+    signal_mask_t old_mask;
+    syscall_sigprocmask_error_t error;
+};
+// changes the signals blocked by the process, SIGKILL and SIGSTOP cannot be blocked
+struct syscall_sigprocmask_t {
+    syscall_sigprocmask_how_t how;
+    signal_mask_t mask;
+    struct syscall_sigprocmask_return_t return_value;
+};
+
+// pushed onto the user stack before a signal handler is called, above the address of the restorer.
+// sigreturn takes no argument, it restores the state from the frame at the stack pointer.
+struct signal_frame_t {
+    uint64_t rax;
+    uint64_t rbx;
+    uint64_t rcx;
+    uint64_t rdx;
+    uint64_t rsi;
+    uint64_t rdi;
+    uint64_t rsp;
+    uint64_t rbp;
+    uint64_t r8;
+    uint64_t r9;
+    uint64_t r10;
+    uint64_t r11;
+    uint64_t r12;
+    uint64_t r13;
+    uint64_t r14;
+    uint64_t r15;
+    uint64_t rip;
+    uint64_t flags;
+    // blocked signals before the handler was called
+    signal_mask_t blocked;
+    signal_t signal;
+};
+
+struct syscall_getpid_return_t {
+    pid_t pid;
+};
+struct syscall_getpid_t {
+    struct syscall_getpid_return_t return_value;
+};
+
+struct syscall_getppid_return_t {
+    // 0 if the process has no parent
+    pid_t pid;
+};
+struct syscall_getppid_t {
+    struct syscall_getppid_return_t return_value;
+};
+
+typedef uint32_t syscall_chdir_error_t;
+static const syscall_chdir_error_t SYSCALL_CHDIR_ERROR_NONE = 0;
+static const syscall_chdir_error_t SYSCALL_CHDIR_ERROR_NOT_FOUND = 1;
+struct syscall_chdir_return_t {
+    syscall_chdir_error_t error;
+};
+// relative paths are resolved against the current working directory
+struct syscall_chdir_t {
+    struct string_const_t path;
+    struct syscall_chdir_return_t return_value;
+};
+
+typedef uint32_t syscall_getcwd_error_t;
+static const syscall_getcwd_error_t SYSCALL_GETCWD_ERROR_NONE = 0;
+static const syscall_getcwd_error_t SYSCALL_GETCWD_ERROR_BUFFER_TOO_SMALL = 1;
+struct syscall_getcwd_return_t {
+    // length of the absolute path of the working directory, also set if the buffer is too small
+    uint32_t len;
+    syscall_getcwd_error_t error;
+};
+struct syscall_getcwd_t {
+    char *buf;
+    uint32_t len;
+    struct syscall_getcwd_return_t return_value;
+};
+
+typedef uint32_t syscall_mount_error_t;
+static const syscall_mount_error_t SYSCALL_MOUNT_ERROR_NONE = 0;
+static const syscall_mount_error_t SYSCALL_MOUNT_ERROR_NOT_FOUND = 1;
+static const syscall_mount_error_t SYSCALL_MOUNT_ERROR_NOT_A_DIRECTORY = 2;
+static const syscall_mount_error_t SYSCALL_MOUNT_ERROR_UNKNOWN_FILE_SYSTEM = 3;
+// something is already mounted at the target
+static const syscall_mount_error_t SYSCALL_MOUNT_ERROR_BUSY = 4;
+struct syscall_mount_return_t {
+    syscall_mount_error_t error;
+};
+// mounts a new instance of the file system type fs_type (ramfs, proc, dev or sys) on the directory target
+struct syscall_mount_t {
+    struct string_const_t fs_type;
+    struct string_const_t target;
+    struct syscall_mount_return_t return_value;
+};
+
+typedef uint32_t syscall_umount_error_t;
+static const syscall_umount_error_t SYSCALL_UMOUNT_ERROR_NONE = 0;
+static const syscall_umount_error_t SYSCALL_UMOUNT_ERROR_NOT_MOUNTED = 1;
+// the target is the root or other file systems are mounted below it
+static const syscall_umount_error_t SYSCALL_UMOUNT_ERROR_BUSY = 2;
+struct syscall_umount_return_t {
+    syscall_umount_error_t error;
+};
+struct syscall_umount_t {
+    struct string_const_t target;
+    struct syscall_umount_return_t return_value;
+};
+
+typedef uint32_t syscall_unlink_error_t;
+static const syscall_unlink_error_t SYSCALL_UNLINK_ERROR_NONE = 0;
+static const syscall_unlink_error_t SYSCALL_UNLINK_ERROR_NOT_FOUND = 1;
+// directories are removed with rmdir
+static const syscall_unlink_error_t SYSCALL_UNLINK_ERROR_IS_A_DIRECTORY = 2;
+static const syscall_unlink_error_t SYSCALL_UNLINK_ERROR_NOT_SUPPORTED = 3;
+struct syscall_unlink_return_t {
+    syscall_unlink_error_t error;
+};
+// removes a file, it stays usable through the file descriptors that are open on it
+struct syscall_unlink_t {
+    struct string_const_t path;
+    struct syscall_unlink_return_t return_value;
+};
+
+typedef uint32_t syscall_mkdir_error_t;
+static const syscall_mkdir_error_t SYSCALL_MKDIR_ERROR_NONE = 0;
+// the parent directory does not exist
+static const syscall_mkdir_error_t SYSCALL_MKDIR_ERROR_NOT_FOUND = 1;
+static const syscall_mkdir_error_t SYSCALL_MKDIR_ERROR_ALREADY_EXISTS = 2;
+static const syscall_mkdir_error_t SYSCALL_MKDIR_ERROR_NOT_SUPPORTED = 3;
+struct syscall_mkdir_return_t {
+    syscall_mkdir_error_t error;
+};
+struct syscall_mkdir_t {
+    struct string_const_t path;
+    struct syscall_mkdir_return_t return_value;
+};
+
+typedef uint32_t syscall_rmdir_error_t;
+static const syscall_rmdir_error_t SYSCALL_RMDIR_ERROR_NONE = 0;
+static const syscall_rmdir_error_t SYSCALL_RMDIR_ERROR_NOT_FOUND = 1;
+static const syscall_rmdir_error_t SYSCALL_RMDIR_ERROR_NOT_A_DIRECTORY = 2;
+static const syscall_rmdir_error_t SYSCALL_RMDIR_ERROR_NOT_EMPTY = 3;
+// the directory is the root or a mount point
+static const syscall_rmdir_error_t SYSCALL_RMDIR_ERROR_BUSY = 4;
+static const syscall_rmdir_error_t SYSCALL_RMDIR_ERROR_NOT_SUPPORTED = 5;
+struct syscall_rmdir_return_t {
+    syscall_rmdir_error_t error;
+};
+// removes an empty directory
+struct syscall_rmdir_t {
+    struct string_const_t path;
+    struct syscall_rmdir_return_t return_value;
+};
+
+typedef uint32_t syscall_rename_error_t;
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_NONE = 0;
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_NOT_FOUND = 1;
+// a directory would replace a file
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_NOT_A_DIRECTORY = 2;
+// a file would replace a directory
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_IS_A_DIRECTORY = 3;
+// the directory that would be replaced is not empty
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_NOT_EMPTY = 4;
+// a mount point would be moved or replaced
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_BUSY = 5;
+// the paths are on different file systems
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_CROSS_DEVICE = 6;
+// a directory would be moved into itself
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_INVALID_PATH = 7;
+static const syscall_rename_error_t SYSCALL_RENAME_ERROR_NOT_SUPPORTED = 8;
+struct syscall_rename_return_t {
+    syscall_rename_error_t error;
+};
+// moves old_path to new_path, replacing a file or an empty directory that is there
+struct syscall_rename_t {
+    struct string_const_t old_path;
+    struct string_const_t new_path;
+    struct syscall_rename_return_t return_value;
+};
+
+typedef uint32_t stat_type_t;
+static const stat_type_t STAT_TYPE_REGULAR = 0;
+static const stat_type_t STAT_TYPE_DIRECTORY = 1;
+// streams and pipes, they are not read at an offset
+static const stat_type_t STAT_TYPE_STREAM = 2;
+// only seen for links that are not followed, stat and fstat report the file a link points to
+static const stat_type_t STAT_TYPE_SYMLINK = 3;
+// the devices in /dev, like /dev/null or /dev/tty
+static const stat_type_t STAT_TYPE_DEVICE = 4;
+
+struct stat_t {
+    stat_type_t type;
+    // permission bits, like 0644
+    uint32_t mode;
+    // number of names of the file, 2 plus the number of subdirectories for a directory
+    uint32_t nlink;
+    // size of a regular file in bytes, number of entries of a directory, 0 if it is not known in advance
+    uint64_t size;
+    // unix timestamps in seconds, 0 if the file system does not keep track of them
+    uint64_t created;
+    uint64_t modified;
+};
+
+typedef uint32_t syscall_stat_error_t;
+static const syscall_stat_error_t SYSCALL_STAT_ERROR_NONE = 0;
+static const syscall_stat_error_t SYSCALL_STAT_ERROR_NOT_FOUND = 1;
+struct syscall_stat_return_t {
+    syscall_stat_error_t error;
+};
+struct syscall_stat_t {
+    struct string_const_t path;
+    struct stat_t *stat;
+    struct syscall_stat_return_t return_value;
+};
+
+typedef uint32_t syscall_fstat_error_t;
+static const syscall_fstat_error_t SYSCALL_FSTAT_ERROR_NONE = 0;
+static const syscall_fstat_error_t SYSCALL_FSTAT_ERROR_INVALID_FD = 1;
+struct syscall_fstat_return_t {
+    syscall_fstat_error_t error;
+};
+struct syscall_fstat_t {
+    fd_t fd;
+    struct stat_t *stat;
+    struct syscall_fstat_return_t return_value;
+};
+
+typedef uint32_t syscall_lseek_whence_t;
+// the offset is relative to the start of the file
+static const syscall_lseek_whence_t SYSCALL_LSEEK_WHENCE_SET = 0;
+// the offset is relative to the current offset
+static const syscall_lseek_whence_t SYSCALL_LSEEK_WHENCE_CURRENT = 1;
+// the offset is relative to the end of the file
+static const syscall_lseek_whence_t SYSCALL_LSEEK_WHENCE_END = 2;
+
+typedef uint32_t syscall_lseek_error_t;
+static const syscall_lseek_error_t SYSCALL_LSEEK_ERROR_NONE = 0;
+static const syscall_lseek_error_t SYSCALL_LSEEK_ERROR_INVALID_FD = 1;
+// the new offset would be negative, or the whence is unknown
+static const syscall_lseek_error_t SYSCALL_LSEEK_ERROR_INVALID_OFFSET = 2;
+// the file descriptor is neither a regular file nor a device without input, like /dev/fb0
+static const syscall_lseek_error_t SYSCALL_LSEEK_ERROR_NOT_SEEKABLE = 3;
+struct syscall_lseek_return_t {
+    // the new offset from the start of the file
+    uint64_t offset;
+    syscall_lseek_error_t error;
+};
+// the offset can be moved past the end, writing there fills the gap with zeros
+struct syscall_lseek_t {
+    fd_t fd;
+    int64_t offset;
+    syscall_lseek_whence_t whence;
+    struct syscall_lseek_return_t return_value;
+};
+
+typedef uint32_t syscall_pread_error_t;
+static const syscall_pread_error_t SYSCALL_PREAD_ERROR_NONE = 0;
+// the file descriptor is not open for reading
+static const syscall_pread_error_t SYSCALL_PREAD_ERROR_INVALID_FD = 1;
+static const syscall_pread_error_t SYSCALL_PREAD_ERROR_NOT_SEEKABLE = 2;
+struct syscall_pread_return_t {
+    uint32_t bytes_read;
+    syscall_pread_error_t error;
+};
+// reads at the offset without changing the offset of the file descriptor
+struct syscall_pread_t {
+    fd_t fd;
+    void *buf;
+    uint32_t len;
+    uint64_t offset;
+    struct syscall_pread_return_t return_value;
+};
+
+typedef uint32_t syscall_pwrite_error_t;
+static const syscall_pwrite_error_t SYSCALL_PWRITE_ERROR_NONE = 0;
+// the file descriptor is not open for writing
+static const syscall_pwrite_error_t SYSCALL_PWRITE_ERROR_INVALID_FD = 1;
+static const syscall_pwrite_error_t SYSCALL_PWRITE_ERROR_NOT_SEEKABLE = 2;
+static const syscall_pwrite_error_t SYSCALL_PWRITE_ERROR_INVALID_VALUE = 3;
+static const syscall_pwrite_error_t SYSCALL_PWRITE_ERROR_FILE_TOO_LARGE = 4;
+static const syscall_pwrite_error_t SYSCALL_PWRITE_ERROR_NO_SPACE = 5;
+struct syscall_pwrite_return_t {
+    uint32_t bytes_written;
+    syscall_pwrite_error_t error;
+};
+// writes at the offset without changing the offset of the file descriptor, even if it was opened for appending
+struct syscall_pwrite_t {
+    fd_t fd;
+    const void *buf;
+    uint32_t len;
+    uint64_t offset;
+    struct syscall_pwrite_return_t return_value;
+};
+
+typedef uint32_t ioctl_request_t;
+// writes the size of the terminal to a struct tty_window_size_t, for /dev/tty and /dev/console
+static const ioctl_request_t IOCTL_TTY_GET_WINDOW_SIZE = 1;
+// writes the layout of the framebuffer to a struct framebuffer_info_t, for /dev/fb0
+static const ioctl_request_t IOCTL_FRAMEBUFFER_GET_INFO = 2;
+
+struct tty_window_size_t {
+    uint32_t rows;
+    uint32_t columns;
+};
+
+struct framebuffer_info_t {
+    uint32_t width;
+    uint32_t height;
+    // bytes from the start of one row of pixels to the next
+    uint32_t pitch;
+    uint32_t bits_per_pixel;
+};
+
+typedef uint32_t syscall_ioctl_error_t;
+static const syscall_ioctl_error_t SYSCALL_IOCTL_ERROR_NONE = 0;
+// the file descriptor is not open or not a device
+static const syscall_ioctl_error_t SYSCALL_IOCTL_ERROR_INVALID_FD = 1;
+// the device does not know the request
+static const syscall_ioctl_error_t SYSCALL_IOCTL_ERROR_NOT_SUPPORTED = 2;
+// the argument does not have the size of the struct the request expects
+static const syscall_ioctl_error_t SYSCALL_IOCTL_ERROR_INVALID_ARGUMENT = 3;
+struct syscall_ioctl_return_t {
+    syscall_ioctl_error_t error;
+};
+// sends a device specific request, the device reads and writes the argument
+struct syscall_ioctl_t {
+    fd_t fd;
+    ioctl_request_t request;
+    void *arg;
+    uint32_t arg_len;
+    struct syscall_ioctl_return_t return_value;
+};
+
+// a record written by getdents, the name follows the struct and the record is padded to a multiple of 8 bytes
+struct dirent_t {
+    // number of the node, unique among the nodes that exist at the same time
+    uint64_t inode;
+    // like the size of stat_t, 0 if the file is gone by the time its size is read
+    uint64_t size;
+    stat_type_t type;
+    // bytes from the start of this record to the next one
+    uint16_t record_len;
+    // the name is not terminated
+    uint16_t name_len;
+};
+
+typedef uint32_t syscall_getdents_error_t;
+static const syscall_getdents_error_t SYSCALL_GETDENTS_ERROR_NONE = 0;
+// the file descriptor is not open or not a file of the VFS
+static const syscall_getdents_error_t SYSCALL_GETDENTS_ERROR_INVALID_FD = 1;
+static const syscall_getdents_error_t SYSCALL_GETDENTS_ERROR_NOT_A_DIRECTORY = 2;
+// the next record does not fit into the buffer
+static const syscall_getdents_error_t SYSCALL_GETDENTS_ERROR_BUFFER_TOO_SMALL = 3;
+struct syscall_getdents_return_t {
+    // 0 once all entries were returned
+    uint32_t bytes_written;
+    syscall_getdents_error_t error;
+    // passed as the cookie of the next call to continue after the records that were written
+    uint64_t cookie;
+};
+// writes as many records of the entries of the directory fd as fit into buf, starting at cookie, which is 0 for the first call
+struct syscall_getdents_t {
+    fd_t fd;
+    uint64_t cookie;
+    void *buf;
+    uint32_t len;
+    struct syscall_getdents_return_t return_value;
+};
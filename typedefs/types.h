@@ -13,8 +13,37 @@ struct string_const_t {
 typedef int32_t fd_t;
 static const fd_t FD_STDIN = 0;
 
+// keys of the auxiliary vector, the values match Linux where there is an equivalent
+typedef uint64_t auxv_key_t;
+// marks the end of the auxiliary vector
+static const auxv_key_t AT_NULL = 0;
+// address of the program headers in memory
+static const auxv_key_t AT_PHDR = 3;
+// size of a program header
+static const auxv_key_t AT_PHENT = 4;
+// number of program headers
+static const auxv_key_t AT_PHNUM = 5;
+static const auxv_key_t AT_PAGESZ = 6;
+// entry point of the program
+static const auxv_key_t AT_ENTRY = 9;
+// address of 16 random bytes
+static const auxv_key_t AT_RANDOM = 25;
+// size of the framebuffer returned by the map_framebuffer syscall, in pixels
+static const auxv_key_t AT_FRAMEBUFFER_WIDTH = 0x1000;
+static const auxv_key_t AT_FRAMEBUFFER_HEIGHT = 0x1001;
+
+struct auxv_t {
+    auxv_key_t key;
+    uint64_t value;
+};
+
 // a pointer to this struct is on the stack at the entry point of the program
 struct entry_t {
     uint32_t argc;
     struct string_const_t *argv;
+    // environment variables in the form NAME=value
+    uint32_t envc;
+    struct string_const_t *envp;
+    // terminated by an entry with the key AT_NULL
+    struct auxv_t *auxv;
 };
@@ -11,16 +11,8 @@ pub const types = @cImport({
 
 const PageAllocator = struct {
     fn alloc(_: *anyopaque, len: usize, _: std.mem.Alignment, _: usize) ?[*]u8 {
-        const firstPage: []u8 = @ptrCast(mmap() catch @panic("Failed to allocate memory"));
-        // allocate additional pages if the requested length exceeds one page
-        for (0..len / 4096) |i| {
-            const page = mmap() catch @panic("Failed to allocate additional memory");
-            if (page.ptr != firstPage.ptr + 4096 * (i + 1)) {
-                // if the page is not contiguous, we return null
-                @panic("pages are not contiguous");
-            }
-        }
-        return @as([*]u8, @ptrCast(firstPage.ptr));
+        const memory = mmap(null, len, PROT_READ | PROT_WRITE, MAP_ANONYMOUS) catch return null;
+        return memory.ptr;
     }
 
     fn resize(_: *anyopaque, _: []u8, _: std.mem.Alignment, _: usize, _: usize) bool {
@@ -36,16 +28,7 @@ const PageAllocator = struct {
             @panic("memory is not page aligned");
         }
 
-        munmap(memory.ptr) catch @panic("Failed to unmap memory");
-
-        if (memory.len > 4096) {
-            for (0..memory.len / 4096) |i| {
-                munmap(@alignCast(@ptrCast(memory.ptr + 4096 * (i + 1)))) catch |err| {
-                    std.log.err("Failed to unmap additional memory: {}", .{err});
-                    @panic("Failed to unmap additional memory");
-                };
-            }
-        }
+        munmap(memory) catch @panic("Failed to unmap memory");
     }
 };
 
@@ -61,6 +44,19 @@ pub fn pageAllocator() std.mem.Allocator {
     };
 }
 
+/// Returns an allocator that grows the heap with `sbrk`, for small allocations
+pub fn heapAllocator() std.mem.Allocator {
+    return std.mem.Allocator{
+        .ptr = undefined,
+        .vtable = &std.heap.SbrkAllocator(sbrkOrZero).vtable,
+    };
+}
+
+fn sbrkOrZero(increment: usize) usize {
+    const memory = sbrk(@intCast(increment)) catch return 0;
+    return @intFromPtr(memory);
+}
+
 pub fn print(comptime fmt: []const u8, args: anytype) void {
     var buffer: [8192]u8 = undefined;
     const str = std.fmt.bufPrint(&buffer, fmt, args) catch |err| {
@@ -75,25 +71,75 @@ pub fn print(comptime fmt: []const u8, args: anytype) void {
         },
     };
 
-    syscalls.print(&arg);
+    syscalls.print(&arg) catch @panic("print failed");
 }
 
 pub fn sleep(milliseconds: u32) void {
     var arg = syscalls.types.syscall_sleep_t{
         .milliseconds = milliseconds,
     };
-    syscalls.sleep(&arg);
+    syscalls.sleep(&arg) catch |err| switch (err) {
+        // a signal handler ran, the sleep ends early
+        error.Interrupted => {},
+        else => @panic("sleep failed"),
+    };
 }
 
 pub fn exit(status: u32) noreturn {
     var arg = syscalls.types.syscall_exit_t{
         .status = status,
     };
-    syscalls.exit(&arg);
+    syscalls.exit(&arg) catch @panic("exit failed");
 
     @panic("exit syscall returned, which should not happen");
 }
 
+/// The entry structure the kernel passed to the program, set by `start`
+var entry: ?*const types.entry_t = null;
+
+/// Startup routine for programs, called from `_start` with the pointer the kernel put on the stack.
+/// Runs `main` with the arguments of the program and exits with its result.
+pub fn start(entry_ptr: *const types.entry_t, comptime main: fn ([]const []const u8) anyerror!void) noreturn {
+    entry = entry_ptr;
+
+    var argBuffer: [512][]const u8 = undefined;
+    const argc = @min(entry_ptr.argc, argBuffer.len);
+    for (0..argc) |i| {
+        const arg = entry_ptr.argv[i];
+        argBuffer[i] = arg.ptr[0..arg.len];
+    }
+
+    main(argBuffer[0..argc]) catch |err| {
+        std.log.err("error: {}\n", .{err});
+        exit(1);
+    };
+    exit(0);
+}
+
+/// Returns the value of the environment variable `name`
+pub fn getenv(name: []const u8) ?[]const u8 {
+    const e = entry orelse return null;
+    for (0..e.envc) |i| {
+        const variable = e.envp[i].ptr[0..e.envp[i].len];
+        if (variable.len > name.len and variable[name.len] == '=' and std.mem.eql(u8, variable[0..name.len], name)) {
+            return variable[name.len + 1 ..];
+        }
+    }
+    return null;
+}
+
+/// Returns the value of the auxiliary vector entry with `key`, one of the `AT_*` constants
+pub fn getauxval(key: types.auxv_key_t) ?u64 {
+    const e = entry orelse return null;
+    var i: usize = 0;
+    while (e.auxv[i].key != types.AT_NULL) : (i += 1) {
+        if (e.auxv[i].key == key) {
+            return e.auxv[i].value;
+        }
+    }
+    return null;
+}
+
 const ListDir = struct {
     _buffer: [64][64]u8,
     entries: [64]syscalls.types.syscall_listdir_entry_t,
@@ -149,7 +195,7 @@ pub fn listdir(path: []const u8) !ListDir {
         },
     };
 
-    const ret = syscalls.listdir(&arg);
+    const ret = try syscalls.listdir(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_LISTDIR_ERROR_NONE) {
         return switch (ret.@"error") {
@@ -163,6 +209,80 @@ pub fn listdir(path: []const u8) !ListDir {
     return listDir;
 }
 
+/// Writes the records of the entries of the directory `fd` from `cookie` on into `buffer`.
+/// Returns the number of bytes written, 0 at the end of the directory, and the cookie to continue with.
+pub fn getdents(fd: i32, cookie: u64, buffer: []align(8) u8) !struct { len: usize, cookie: u64 } {
+    var arg = syscalls.types.syscall_getdents_t{
+        .fd = fd,
+        .cookie = cookie,
+        .buf = buffer.ptr,
+        .len = @intCast(buffer.len),
+    };
+
+    const ret = try syscalls.getdents(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_GETDENTS_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_GETDENTS_ERROR_INVALID_FD => error.InvalidFd,
+            syscalls.types.SYSCALL_GETDENTS_ERROR_NOT_A_DIRECTORY => error.NotADirectory,
+            syscalls.types.SYSCALL_GETDENTS_ERROR_BUFFER_TOO_SMALL => error.BufferTooSmall,
+            else => @panic("getdents unexpected error"),
+        };
+    }
+
+    return .{ .len = @intCast(ret.bytes_written), .cookie = ret.cookie };
+}
+
+pub const DirEntry = struct {
+    /// only valid until the next call to `Dir.next`
+    name: []const u8,
+    type: FileType,
+    inode: u64,
+    size: u64,
+};
+
+/// An open directory that is read a batch of entries at a time, so its size does not matter
+pub const Dir = struct {
+    fd: i32,
+    cookie: u64 = 0,
+    buffer: [1024]u8 align(8) = undefined,
+    len: usize = 0,
+    offset: usize = 0,
+
+    pub fn next(self: *Dir) !?DirEntry {
+        if (self.offset >= self.len) {
+            const batch = try getdents(self.fd, self.cookie, &self.buffer);
+            if (batch.len == 0) {
+                return null;
+            }
+            self.len = batch.len;
+            self.cookie = batch.cookie;
+            self.offset = 0;
+        }
+
+        const record: *const syscalls.types.dirent_t = @ptrCast(@alignCast(&self.buffer[self.offset]));
+        const name = self.buffer[self.offset + @sizeOf(syscalls.types.dirent_t) ..][0..record.name_len];
+        self.offset += record.record_len;
+
+        return DirEntry{
+            .name = name,
+            .type = @enumFromInt(record.type),
+            .inode = record.inode,
+            .size = record.size,
+        };
+    }
+
+    /// Closes the directory
+    pub fn deinit(self: *Dir) void {
+        close(self.fd) catch {};
+    }
+};
+
+/// Opens the directory at `path` for reading its entries with `Dir.next`
+pub fn opendir(path: []const u8) !Dir {
+    return Dir{ .fd = try open(path) };
+}
+
 pub fn read(fd: i32, buffer: []u8, blocking: bool) !usize {
     var arg = syscalls.types.syscall_read_t{
         .fd = fd,
@@ -171,7 +291,7 @@ pub fn read(fd: i32, buffer: []u8, blocking: bool) !usize {
         .len = @intCast(buffer.len),
     };
 
-    const ret = syscalls.read(&arg);
+    const ret = try syscalls.read(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_READ_ERROR_NONE) {
         return switch (ret.@"error") {
@@ -183,27 +303,224 @@ pub fn read(fd: i32, buffer: []u8, blocking: bool) !usize {
     return @intCast(ret.bytes_read);
 }
 
+pub const FileType = enum(u32) {
+    regular = syscalls.types.STAT_TYPE_REGULAR,
+    directory = syscalls.types.STAT_TYPE_DIRECTORY,
+    /// streams and pipes
+    stream = syscalls.types.STAT_TYPE_STREAM,
+    symlink = syscalls.types.STAT_TYPE_SYMLINK,
+    /// devices like /dev/tty and /dev/fb0, see `ioctl`
+    device = syscalls.types.STAT_TYPE_DEVICE,
+};
+
+pub const Stat = struct {
+    type: FileType,
+    /// permission bits, like 0o644
+    mode: u32,
+    nlink: u32,
+    /// in bytes for regular files, the number of entries for directories
+    size: u64,
+    /// unix timestamps in seconds, 0 if the file system does not keep track of them
+    created: u64,
+    modified: u64,
+
+    fn from(stat_: syscalls.types.stat_t) Stat {
+        return .{
+            .type = @enumFromInt(stat_.type),
+            .mode = stat_.mode,
+            .nlink = stat_.nlink,
+            .size = stat_.size,
+            .created = stat_.created,
+            .modified = stat_.modified,
+        };
+    }
+};
+
+/// Returns the type, size, link count, mode and timestamps of the file at `path`
+pub fn stat(path: []const u8) !Stat {
+    var result: syscalls.types.stat_t = undefined;
+    var arg = syscalls.types.syscall_stat_t{
+        .path = syscalls.types.string_const_t{
+            .ptr = path.ptr,
+            .len = @intCast(path.len),
+        },
+        .stat = &result,
+    };
+
+    const ret = try syscalls.stat(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_STAT_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_STAT_ERROR_NOT_FOUND => error.NotFound,
+            else => @panic("stat unexpected error"),
+        };
+    }
+
+    return Stat.from(result);
+}
+
+/// Like `stat`, for the file open as `fd`
+pub fn fstat(fd: i32) !Stat {
+    var result: syscalls.types.stat_t = undefined;
+    var arg = syscalls.types.syscall_fstat_t{
+        .fd = fd,
+        .stat = &result,
+    };
+
+    const ret = try syscalls.fstat(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_FSTAT_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_FSTAT_ERROR_INVALID_FD => error.InvalidFd,
+            else => @panic("fstat unexpected error"),
+        };
+    }
+
+    return Stat.from(result);
+}
+
+pub const Whence = enum(u32) {
+    set = syscalls.types.SYSCALL_LSEEK_WHENCE_SET,
+    current = syscalls.types.SYSCALL_LSEEK_WHENCE_CURRENT,
+    end = syscalls.types.SYSCALL_LSEEK_WHENCE_END,
+};
+
+/// Moves the offset of the regular file `fd` relative to `whence` and returns the new offset
+pub fn lseek(fd: i32, offset: i64, whence: Whence) !u64 {
+    var arg = syscalls.types.syscall_lseek_t{
+        .fd = fd,
+        .offset = offset,
+        .whence = @intFromEnum(whence),
+    };
+
+    const ret = try syscalls.lseek(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_LSEEK_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_LSEEK_ERROR_INVALID_FD => error.InvalidFd,
+            syscalls.types.SYSCALL_LSEEK_ERROR_INVALID_OFFSET => error.InvalidOffset,
+            syscalls.types.SYSCALL_LSEEK_ERROR_NOT_SEEKABLE => error.NotSeekable,
+            else => @panic("lseek unexpected error"),
+        };
+    }
+
+    return ret.offset;
+}
+
+/// Reads from the regular file `fd` at `offset`, without moving the offset of `fd`
+pub fn pread(fd: i32, buffer: []u8, offset: u64) !usize {
+    var arg = syscalls.types.syscall_pread_t{
+        .fd = fd,
+        .buf = buffer.ptr,
+        .len = @intCast(buffer.len),
+        .offset = offset,
+    };
+
+    const ret = try syscalls.pread(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_PREAD_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_PREAD_ERROR_INVALID_FD => error.InvalidFd,
+            syscalls.types.SYSCALL_PREAD_ERROR_NOT_SEEKABLE => error.NotSeekable,
+            else => @panic("pread unexpected error"),
+        };
+    }
+
+    return @intCast(ret.bytes_read);
+}
+
+/// Writes to the regular file `fd` at `offset`, without moving the offset of `fd`
+pub fn pwrite(fd: i32, buffer: []const u8, offset: u64) !usize {
+    var arg = syscalls.types.syscall_pwrite_t{
+        .fd = fd,
+        .buf = buffer.ptr,
+        .len = @intCast(buffer.len),
+        .offset = offset,
+    };
+
+    const ret = try syscalls.pwrite(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_PWRITE_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_PWRITE_ERROR_INVALID_FD => error.InvalidFd,
+            syscalls.types.SYSCALL_PWRITE_ERROR_NOT_SEEKABLE => error.NotSeekable,
+            syscalls.types.SYSCALL_PWRITE_ERROR_INVALID_VALUE => error.InvalidValue,
+            syscalls.types.SYSCALL_PWRITE_ERROR_FILE_TOO_LARGE => error.FileTooLarge,
+            syscalls.types.SYSCALL_PWRITE_ERROR_NO_SPACE => error.NoSpace,
+            else => @panic("pwrite unexpected error"),
+        };
+    }
+
+    return @intCast(ret.bytes_written);
+}
+
+pub const TtyWindowSize = syscalls.types.tty_window_size_t;
+pub const FramebufferInfo = syscalls.types.framebuffer_info_t;
+
+/// Sends `request` to the device `fd`, which reads and writes `arg`.
+/// `arg` is a pointer to the struct the request expects, like `TtyWindowSize` for `IOCTL_TTY_GET_WINDOW_SIZE`
+pub fn ioctl(fd: i32, request: syscalls.types.ioctl_request_t, arg: anytype) !void {
+    const bytes = std.mem.asBytes(arg);
+    var arg_ = syscalls.types.syscall_ioctl_t{
+        .fd = fd,
+        .request = request,
+        .arg = @ptrCast(bytes.ptr),
+        .arg_len = @intCast(bytes.len),
+    };
+
+    const ret = try syscalls.ioctl(&arg_);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_IOCTL_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_IOCTL_ERROR_INVALID_FD => error.InvalidFd,
+            syscalls.types.SYSCALL_IOCTL_ERROR_NOT_SUPPORTED => error.NotSupported,
+            syscalls.types.SYSCALL_IOCTL_ERROR_INVALID_ARGUMENT => error.InvalidArgument,
+            else => @panic("ioctl unexpected error"),
+        };
+    }
+}
+
+pub const IOCTL_TTY_GET_WINDOW_SIZE = syscalls.types.IOCTL_TTY_GET_WINDOW_SIZE;
+pub const IOCTL_FRAMEBUFFER_GET_INFO = syscalls.types.IOCTL_FRAMEBUFFER_GET_INFO;
+
 pub fn fork() u32 {
     var arg = syscalls.types.syscall_fork_t{ .return_value = .{ .child_pid = 0 } };
 
-    const ret = syscalls.fork(&arg);
+    const ret = syscalls.fork(&arg) catch @panic("fork failed");
 
     return @intCast(ret.child_pid);
 }
 
+pub const OPEN_READ = syscalls.types.SYSCALL_OPEN_MODE_READ;
+pub const OPEN_WRITE = syscalls.types.SYSCALL_OPEN_MODE_WRITE;
+pub const OPEN_READ_WRITE = syscalls.types.SYSCALL_OPEN_MODE_READ_WRITE;
+pub const OPEN_CREATE = syscalls.types.SYSCALL_OPEN_MODE_CREATE;
+pub const OPEN_TRUNCATE = syscalls.types.SYSCALL_OPEN_MODE_TRUNCATE;
+pub const OPEN_APPEND = syscalls.types.SYSCALL_OPEN_MODE_APPEND;
+
+/// Opens the file at `path` for reading
 pub fn open(path: []const u8) !i32 {
+    return openWithMode(path, OPEN_READ);
+}
+
+/// Opens the file at `path` with a combination of the `OPEN_*` modes
+pub fn openWithMode(path: []const u8, mode: u32) !i32 {
     var arg = syscalls.types.syscall_open_t{
         .path = syscalls.types.string_const_t{
             .ptr = path.ptr,
             .len = @intCast(path.len),
         },
+        .mode = mode,
     };
 
-    const ret = syscalls.open(&arg);
+    const ret = try syscalls.open(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_OPEN_ERROR_NONE) {
         return switch (ret.@"error") {
             syscalls.types.SYSCALL_OPEN_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_OPEN_ERROR_IS_A_DIRECTORY => error.IsADirectory,
+            syscalls.types.SYSCALL_OPEN_ERROR_NOT_A_DIRECTORY => error.NotADirectory,
+            syscalls.types.SYSCALL_OPEN_ERROR_NOT_SUPPORTED => error.NotSupported,
             else => @panic("open unexpected error"),
         };
     }
@@ -211,12 +528,106 @@ pub fn open(path: []const u8) !i32 {
     return ret.fd;
 }
 
+/// Removes the file at `path`
+pub fn unlink(path: []const u8) !void {
+    var arg = syscalls.types.syscall_unlink_t{
+        .path = syscalls.types.string_const_t{
+            .ptr = path.ptr,
+            .len = @intCast(path.len),
+        },
+    };
+
+    const ret = try syscalls.unlink(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_UNLINK_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_UNLINK_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_UNLINK_ERROR_IS_A_DIRECTORY => error.IsADirectory,
+            syscalls.types.SYSCALL_UNLINK_ERROR_NOT_SUPPORTED => error.NotSupported,
+            else => @panic("unlink unexpected error"),
+        };
+    }
+}
+
+pub fn mkdir(path: []const u8) !void {
+    var arg = syscalls.types.syscall_mkdir_t{
+        .path = syscalls.types.string_const_t{
+            .ptr = path.ptr,
+            .len = @intCast(path.len),
+        },
+    };
+
+    const ret = try syscalls.mkdir(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_MKDIR_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_MKDIR_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_MKDIR_ERROR_ALREADY_EXISTS => error.AlreadyExists,
+            syscalls.types.SYSCALL_MKDIR_ERROR_NOT_SUPPORTED => error.NotSupported,
+            else => @panic("mkdir unexpected error"),
+        };
+    }
+}
+
+/// Removes the empty directory at `path`
+pub fn rmdir(path: []const u8) !void {
+    var arg = syscalls.types.syscall_rmdir_t{
+        .path = syscalls.types.string_const_t{
+            .ptr = path.ptr,
+            .len = @intCast(path.len),
+        },
+    };
+
+    const ret = try syscalls.rmdir(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_RMDIR_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_RMDIR_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_RMDIR_ERROR_NOT_A_DIRECTORY => error.NotADirectory,
+            syscalls.types.SYSCALL_RMDIR_ERROR_NOT_EMPTY => error.NotEmpty,
+            syscalls.types.SYSCALL_RMDIR_ERROR_BUSY => error.Busy,
+            syscalls.types.SYSCALL_RMDIR_ERROR_NOT_SUPPORTED => error.NotSupported,
+            else => @panic("rmdir unexpected error"),
+        };
+    }
+}
+
+/// Moves `oldPath` to `newPath`, replacing a file or an empty directory there
+pub fn rename(oldPath: []const u8, newPath: []const u8) !void {
+    var arg = syscalls.types.syscall_rename_t{
+        .old_path = syscalls.types.string_const_t{
+            .ptr = oldPath.ptr,
+            .len = @intCast(oldPath.len),
+        },
+        .new_path = syscalls.types.string_const_t{
+            .ptr = newPath.ptr,
+            .len = @intCast(newPath.len),
+        },
+    };
+
+    const ret = try syscalls.rename(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_RENAME_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_RENAME_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_RENAME_ERROR_NOT_A_DIRECTORY => error.NotADirectory,
+            syscalls.types.SYSCALL_RENAME_ERROR_IS_A_DIRECTORY => error.IsADirectory,
+            syscalls.types.SYSCALL_RENAME_ERROR_NOT_EMPTY => error.NotEmpty,
+            syscalls.types.SYSCALL_RENAME_ERROR_BUSY => error.Busy,
+            syscalls.types.SYSCALL_RENAME_ERROR_CROSS_DEVICE => error.CrossDevice,
+            syscalls.types.SYSCALL_RENAME_ERROR_INVALID_PATH => error.InvalidPath,
+            syscalls.types.SYSCALL_RENAME_ERROR_NOT_SUPPORTED => error.NotSupported,
+            else => @panic("rename unexpected error"),
+        };
+    }
+}
+
 pub fn close(fd: i32) !void {
     var arg = syscalls.types.syscall_close_t{
         .fd = fd,
     };
 
-    const ret = syscalls.close(&arg);
+    const ret = try syscalls.close(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_CLOSE_ERROR_NONE) {
         return switch (ret.@"error") {
@@ -226,39 +637,138 @@ pub fn close(fd: i32) !void {
     }
 }
 
-pub fn mmap() ![]u8 {
+pub const PROT_NONE = syscalls.types.SYSCALL_MMAP_PROT_NONE;
+pub const PROT_READ = syscalls.types.SYSCALL_MMAP_PROT_READ;
+pub const PROT_WRITE = syscalls.types.SYSCALL_MMAP_PROT_WRITE;
+pub const PROT_EXEC = syscalls.types.SYSCALL_MMAP_PROT_EXEC;
+
+pub const MAP_FIXED = syscalls.types.SYSCALL_MMAP_FLAG_FIXED;
+pub const MAP_ANONYMOUS = syscalls.types.SYSCALL_MMAP_FLAG_ANONYMOUS;
+pub const MAP_SHARED = syscalls.types.SYSCALL_MMAP_FLAG_SHARED;
+
+pub fn mmap(addr: ?*anyopaque, size: usize, prot: u32, flags: u32) ![]u8 {
     var arg = syscalls.types.syscall_mmap_t{
-        .size = 4096, // Allocate one page (4KB)
+        .addr = addr,
+        .size = size,
+        .prot = prot,
+        .flags = flags,
     };
 
-    const ret = syscalls.mmap(&arg);
+    const ret = try syscalls.mmap(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_MMAP_ERROR_NONE) {
         return switch (ret.@"error") {
+            syscalls.types.SYSCALL_MMAP_ERROR_INVALID_SIZE => error.InvalidSize,
+            syscalls.types.SYSCALL_MMAP_ERROR_INVALID_ADDR => error.InvalidAddress,
+            syscalls.types.SYSCALL_MMAP_ERROR_INVALID_FLAGS => error.InvalidFlags,
+            syscalls.types.SYSCALL_MMAP_ERROR_NO_MEMORY => error.OutOfMemory,
             else => @panic("mmap unexpected error"),
         };
     }
 
-    return @as([*]u8, @ptrCast(ret.addr))[0..4096];
+    return @as([*]u8, @ptrCast(ret.addr))[0..size];
 }
 
-pub fn munmap(ptr: *anyopaque) !void {
+pub fn munmap(memory: []u8) !void {
     var arg = syscalls.types.syscall_munmap_t{
-        .addr = @ptrCast(ptr),
-        .size = 4096,
+        .addr = @ptrCast(memory.ptr),
+        .size = memory.len,
     };
 
-    const ret = syscalls.munmap(&arg);
+    const ret = try syscalls.munmap(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_MUNMAP_ERROR_NONE) {
         return switch (ret.@"error") {
             syscalls.types.SYSCALL_MUNMAP_ERROR_INVALID_ADDR => error.InvalidAddress,
+            syscalls.types.SYSCALL_MUNMAP_ERROR_INVALID_SIZE => error.InvalidSize,
             else => @panic("munmap unexpected error"),
         };
     }
 }
 
+pub fn mprotect(memory: []u8, prot: u32) !void {
+    var arg = syscalls.types.syscall_mprotect_t{
+        .addr = @ptrCast(memory.ptr),
+        .size = memory.len,
+        .prot = prot,
+    };
+
+    const ret = try syscalls.mprotect(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_MPROTECT_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_MPROTECT_ERROR_INVALID_ADDR => error.InvalidAddress,
+            syscalls.types.SYSCALL_MPROTECT_ERROR_INVALID_SIZE => error.InvalidSize,
+            syscalls.types.SYSCALL_MPROTECT_ERROR_NOT_MAPPED => error.NotMapped,
+            else => @panic("mprotect unexpected error"),
+        };
+    }
+}
+
+/// Sets the end of the heap to `addr`, or returns the current end if `addr` is null
+pub fn brk(addr: ?*anyopaque) !*anyopaque {
+    var arg = syscalls.types.syscall_brk_t{
+        .addr = addr,
+    };
+
+    const ret = try syscalls.brk(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_BRK_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_BRK_ERROR_INVALID_ADDR => error.InvalidAddress,
+            syscalls.types.SYSCALL_BRK_ERROR_NO_MEMORY => error.OutOfMemory,
+            else => @panic("brk unexpected error"),
+        };
+    }
+
+    return ret.addr.?;
+}
+
+/// Moves the end of the heap by `increment` bytes and returns the previous end
+pub fn sbrk(increment: isize) ![*]u8 {
+    const current: [*]u8 = @ptrCast(try brk(null));
+    if (increment != 0) {
+        const new = if (increment > 0) current + @as(usize, @intCast(increment)) else current - @abs(increment);
+        _ = try brk(@ptrCast(new));
+    }
+    return current;
+}
+
+/// Adds `increment` to the nice value of the process and returns the new value,
+/// lower values give the process a higher priority
+pub fn nice(increment: i32) !i32 {
+    var arg = syscalls.types.syscall_nice_t{
+        .increment = increment,
+    };
+
+    const ret = try syscalls.nice(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_NICE_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_NICE_ERROR_PERMISSION_DENIED => error.PermissionDenied,
+            else => @panic("nice unexpected error"),
+        };
+    }
+
+    return ret.nice;
+}
+
+/// Replaces the program of the process, it keeps the environment of the current program
 pub fn execve(program: []const u8, args: []const []const u8) !noreturn {
+    var envBuffer: [64][]const u8 = undefined;
+    var envc: usize = 0;
+    if (entry) |e| {
+        envc = @min(e.envc, envBuffer.len);
+        for (0..envc) |i| {
+            envBuffer[i] = e.envp[i].ptr[0..e.envp[i].len];
+        }
+    }
+
+    return execveWithEnv(program, args, envBuffer[0..envc]);
+}
+
+/// Replaces the program of the process, `env` contains the environment variables in the form NAME=value
+pub fn execveWithEnv(program: []const u8, args: []const []const u8, env: []const []const u8) !noreturn {
     var argv: [64]syscalls.types.string_const_t = undefined;
     for (0..args.len) |i| {
         argv[i] = syscalls.types.string_const_t{
@@ -267,6 +777,14 @@ pub fn execve(program: []const u8, args: []const []const u8) !noreturn {
         };
     }
 
+    var envp: [64]syscalls.types.string_const_t = undefined;
+    for (0..env.len) |i| {
+        envp[i] = syscalls.types.string_const_t{
+            .ptr = env[i].ptr,
+            .len = @intCast(env[i].len),
+        };
+    }
+
     var arg = syscalls.types.syscall_execve_t{
         .path = syscalls.types.string_const_t{
             .ptr = program.ptr,
@@ -274,15 +792,17 @@ pub fn execve(program: []const u8, args: []const []const u8) !noreturn {
         },
         .argv = &argv,
         .argv_len = @intCast(args.len),
-        .envp = null,
-        .envp_len = 0,
+        .envp = &envp,
+        .envp_len = @intCast(env.len),
     };
 
-    const ret = syscalls.execve(&arg);
+    const ret = try syscalls.execve(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_EXECVE_ERROR_NONE) {
         return switch (ret.@"error") {
             syscalls.types.SYSCALL_EXECVE_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_EXECVE_ERROR_INVALID_EXECUTABLE => error.InvalidExecutable,
+            syscalls.types.SYSCALL_EXECVE_ERROR_ARGUMENTS_TOO_LONG => error.ArgumentsTooLong,
             else => @panic("execve unexpected error"),
         };
     } else {
@@ -406,7 +926,7 @@ pub const Framebuffer = struct {
 pub fn mapFramebuffer() Framebuffer {
     var arg = syscalls.types.syscall_map_framebuffer_t{};
 
-    const ret = syscalls.mapFramebuffer(&arg);
+    const ret = syscalls.mapFramebuffer(&arg) catch @panic("mapFramebuffer failed");
 
     return .{
         .ptr = @as([*]u32, @alignCast(@ptrCast(ret.addr)))[0 .. ret.width * ret.height],
@@ -422,11 +942,15 @@ pub fn write(fd: i32, buffer: []const u8) !usize {
         .len = @intCast(buffer.len),
     };
 
-    const ret = syscalls.write(&arg);
+    const ret = try syscalls.write(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_WRITE_ERROR_NONE) {
         return switch (ret.@"error") {
             syscalls.types.SYSCALL_WRITE_ERROR_INVALID_FD => error.InvalidFd,
+            syscalls.types.SYSCALL_WRITE_ERROR_BROKEN_PIPE => error.BrokenPipe,
+            syscalls.types.SYSCALL_WRITE_ERROR_INVALID_VALUE => error.InvalidValue,
+            syscalls.types.SYSCALL_WRITE_ERROR_FILE_TOO_LARGE => error.FileTooLarge,
+            syscalls.types.SYSCALL_WRITE_ERROR_NO_SPACE => error.NoSpace,
             else => @panic("write unexpected error"),
         };
     }
@@ -434,12 +958,169 @@ pub fn write(fd: i32, buffer: []const u8) !usize {
     return @intCast(ret.bytes_written);
 }
 
+pub const Pipe = struct {
+    read: i32,
+    write: i32,
+};
+
+/// Creates a pipe, reading from it returns 0 bytes once all write ends are closed
+pub fn pipe() !Pipe {
+    var arg = syscalls.types.syscall_pipe_t{};
+
+    const ret = try syscalls.pipe(&arg);
+
+    return Pipe{
+        .read = ret.read_fd,
+        .write = ret.write_fd,
+    };
+}
+
+/// Duplicates `fd` to the lowest free file descriptor, both share the open file
+pub fn dup(fd: i32) !i32 {
+    var arg = syscalls.types.syscall_dup_t{
+        .fd = fd,
+    };
+
+    const ret = try syscalls.dup(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_DUP_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_DUP_ERROR_INVALID_FD => error.InvalidFd,
+            else => @panic("dup unexpected error"),
+        };
+    }
+
+    return ret.fd;
+}
+
+/// Makes `new_fd` refer to the open file of `fd`, closing `new_fd` first if it is open
+pub fn dup2(fd: i32, new_fd: i32) !i32 {
+    var arg = syscalls.types.syscall_dup2_t{
+        .fd = fd,
+        .new_fd = new_fd,
+    };
+
+    const ret = try syscalls.dup2(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_DUP2_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_DUP2_ERROR_INVALID_FD => error.InvalidFd,
+            else => @panic("dup2 unexpected error"),
+        };
+    }
+
+    return ret.fd;
+}
+
+pub const SIGHUP = syscalls.types.SIGHUP;
+pub const SIGINT = syscalls.types.SIGINT;
+pub const SIGQUIT = syscalls.types.SIGQUIT;
+pub const SIGILL = syscalls.types.SIGILL;
+pub const SIGABRT = syscalls.types.SIGABRT;
+pub const SIGFPE = syscalls.types.SIGFPE;
+pub const SIGKILL = syscalls.types.SIGKILL;
+pub const SIGUSR1 = syscalls.types.SIGUSR1;
+pub const SIGSEGV = syscalls.types.SIGSEGV;
+pub const SIGUSR2 = syscalls.types.SIGUSR2;
+pub const SIGPIPE = syscalls.types.SIGPIPE;
+pub const SIGALRM = syscalls.types.SIGALRM;
+pub const SIGTERM = syscalls.types.SIGTERM;
+pub const SIGCHLD = syscalls.types.SIGCHLD;
+pub const SIGCONT = syscalls.types.SIGCONT;
+pub const SIGSTOP = syscalls.types.SIGSTOP;
+pub const SIGTSTP = syscalls.types.SIGTSTP;
+
+/// Sends `signal` to the process `pid`
+pub fn kill(pid: u32, signal: u32) !void {
+    var arg = syscalls.types.syscall_kill_t{
+        .pid = pid,
+        .signal = signal,
+    };
+
+    const ret = try syscalls.kill(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_KILL_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_KILL_ERROR_INVALID_PID => error.InvalidPid,
+            syscalls.types.SYSCALL_KILL_ERROR_INVALID_SIGNAL => error.InvalidSignal,
+            syscalls.types.SYSCALL_KILL_ERROR_PERMISSION_DENIED => error.PermissionDenied,
+            else => @panic("kill unexpected error"),
+        };
+    }
+}
+
+pub const SignalHandler = union(enum) {
+    default,
+    ignore,
+    /// called with the signal, which is blocked until the function returns
+    function: *const fn (signal: u32) callconv(.C) void,
+};
+
+/// Sets what happens when the process receives `signal`, SIGKILL and SIGSTOP cannot be changed
+pub fn signal(sig: u32, handler: SignalHandler) !void {
+    var arg = syscalls.types.syscall_sigaction_t{
+        .signal = sig,
+        .handler = switch (handler) {
+            .default => syscalls.types.SIGNAL_HANDLER_DEFAULT,
+            .ignore => syscalls.types.SIGNAL_HANDLER_IGNORE,
+            .function => |function| @intFromPtr(function),
+        },
+        .mask = 0,
+        .restorer = @intFromPtr(&syscalls.sigreturn),
+    };
+
+    const ret = try syscalls.sigaction(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_SIGACTION_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_SIGACTION_ERROR_INVALID_SIGNAL => error.InvalidSignal,
+            else => @panic("sigaction unexpected error"),
+        };
+    }
+}
+
+pub const SignalMaskHow = enum(u32) {
+    block = syscalls.types.SYSCALL_SIGPROCMASK_BLOCK,
+    unblock = syscalls.types.SYSCALL_SIGPROCMASK_UNBLOCK,
+    set = syscalls.types.SYSCALL_SIGPROCMASK_SET,
+};
+
+/// Changes the blocked signals, bit n of `mask` stands for signal n. Returns the previously blocked signals.
+pub fn sigprocmask(how: SignalMaskHow, mask: u32) !u32 {
+    var arg = syscalls.types.syscall_sigprocmask_t{
+        .how = @intFromEnum(how),
+        .mask = mask,
+    };
+
+    const ret = try syscalls.sigprocmask(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_SIGPROCMASK_ERROR_NONE) {
+        @panic("sigprocmask unexpected error");
+    }
+
+    return ret.old_mask;
+}
+
+/// Waits for the child `pid` to exit and returns its exit status
 pub fn waitpid(pid: u32) !u32 {
+    const result = try wait(pid, true);
+    return result.?.status;
+}
+
+pub const WaitResult = struct {
+    pid: u32,
+    status: u32,
+};
+
+/// Waits for the child `pid` to exit, or for any child if `pid` is `ANY_CHILD`.
+/// Returns null instead of waiting if `blocking` is false and no matching child has exited yet.
+pub fn wait(pid: u32, blocking: bool) !?WaitResult {
     var arg = syscalls.types.syscall_waitpid_t{
         .pid = pid,
+        .options = if (blocking) 0 else syscalls.types.SYSCALL_WAITPID_OPTION_NON_BLOCKING,
     };
 
-    const ret = syscalls.waitpid(&arg);
+    const ret = try syscalls.waitpid(&arg);
 
     if (ret.@"error" != syscalls.types.SYSCALL_WAITPID_ERROR_NONE) {
         return switch (ret.@"error") {
@@ -448,5 +1129,174 @@ pub fn waitpid(pid: u32) !u32 {
         };
     }
 
-    return ret.status;
+    if (ret.pid == 0) {
+        return null;
+    }
+
+    return WaitResult{ .pid = ret.pid, .status = ret.status };
+}
+
+pub const ANY_CHILD = syscalls.types.SYSCALL_WAITPID_ANY_CHILD;
+
+pub fn getpid() u32 {
+    var arg = syscalls.types.syscall_getpid_t{};
+    const ret = syscalls.getpid(&arg) catch @panic("getpid failed");
+    return ret.pid;
+}
+
+/// Returns the pid of the parent process, 0 if it has none
+pub fn getppid() u32 {
+    var arg = syscalls.types.syscall_getppid_t{};
+    const ret = syscalls.getppid(&arg) catch @panic("getppid failed");
+    return ret.pid;
+}
+
+/// Changes the working directory, relative paths are resolved against the current one
+pub fn chdir(path: []const u8) !void {
+    var arg = syscalls.types.syscall_chdir_t{
+        .path = syscalls.types.string_const_t{
+            .ptr = path.ptr,
+            .len = @intCast(path.len),
+        },
+    };
+
+    const ret = try syscalls.chdir(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_CHDIR_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_CHDIR_ERROR_NOT_FOUND => error.NotFound,
+            else => @panic("chdir unexpected error"),
+        };
+    }
+}
+
+/// Mounts a new file system of type `fsType` (ramfs, proc, dev or sys) on the directory `target`
+pub fn mount(fsType: []const u8, target: []const u8) !void {
+    var arg = syscalls.types.syscall_mount_t{
+        .fs_type = syscalls.types.string_const_t{
+            .ptr = fsType.ptr,
+            .len = @intCast(fsType.len),
+        },
+        .target = syscalls.types.string_const_t{
+            .ptr = target.ptr,
+            .len = @intCast(target.len),
+        },
+    };
+
+    const ret = try syscalls.mount(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_MOUNT_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_MOUNT_ERROR_NOT_FOUND => error.NotFound,
+            syscalls.types.SYSCALL_MOUNT_ERROR_NOT_A_DIRECTORY => error.NotADirectory,
+            syscalls.types.SYSCALL_MOUNT_ERROR_UNKNOWN_FILE_SYSTEM => error.UnknownFileSystem,
+            syscalls.types.SYSCALL_MOUNT_ERROR_BUSY => error.Busy,
+            else => @panic("mount unexpected error"),
+        };
+    }
+}
+
+pub fn umount(target: []const u8) !void {
+    var arg = syscalls.types.syscall_umount_t{
+        .target = syscalls.types.string_const_t{
+            .ptr = target.ptr,
+            .len = @intCast(target.len),
+        },
+    };
+
+    const ret = try syscalls.umount(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_UMOUNT_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_UMOUNT_ERROR_NOT_MOUNTED => error.NotMounted,
+            syscalls.types.SYSCALL_UMOUNT_ERROR_BUSY => error.Busy,
+            else => @panic("umount unexpected error"),
+        };
+    }
+}
+
+/// Writes the absolute path of the working directory to `buffer` and returns it
+pub fn getcwd(buffer: []u8) ![]u8 {
+    var arg = syscalls.types.syscall_getcwd_t{
+        .buf = buffer.ptr,
+        .len = @intCast(buffer.len),
+    };
+
+    const ret = try syscalls.getcwd(&arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_GETCWD_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_GETCWD_ERROR_BUFFER_TOO_SMALL => error.BufferTooSmall,
+            else => @panic("getcwd unexpected error"),
+        };
+    }
+
+    return buffer[0..ret.len];
+}
+
+const THREAD_STACK_SIZE = 64 * 1024;
+
+pub const Thread = struct {
+    tid: u32,
+    stack: []u8,
+
+    /// Waits for the thread to exit, frees its stack and returns its exit status
+    pub fn join(self: Thread) !u32 {
+        var arg = syscalls.types.syscall_thread_join_t{
+            .tid = self.tid,
+        };
+
+        const ret = try syscalls.thread_join(&arg);
+
+        if (ret.@"error" != syscalls.types.SYSCALL_THREAD_JOIN_ERROR_NONE) {
+            return switch (ret.@"error") {
+                syscalls.types.SYSCALL_THREAD_JOIN_ERROR_NOT_FOUND => error.NotFound,
+                else => @panic("thread_join unexpected error"),
+            };
+        }
+
+        try munmap(self.stack);
+
+        return ret.status;
+    }
+};
+
+/// Runs `func` with `arg` in a new thread of this process, the thread exits with status 0 when `func` returns
+pub fn spawnThread(comptime func: fn (?*anyopaque) void, arg: ?*anyopaque) !Thread {
+    const entry = struct {
+        fn entry(context: ?*anyopaque) callconv(.C) noreturn {
+            func(context);
+            threadExit(0);
+        }
+    }.entry;
+
+    const stack = try mmap(null, THREAD_STACK_SIZE, PROT_READ | PROT_WRITE, MAP_ANONYMOUS);
+    errdefer munmap(stack) catch {};
+
+    var sys_arg = syscalls.types.syscall_thread_create_t{
+        .entry = @constCast(@ptrCast(&entry)),
+        .stack = @ptrCast(stack.ptr + stack.len),
+        .arg = arg,
+    };
+
+    const ret = try syscalls.thread_create(&sys_arg);
+
+    if (ret.@"error" != syscalls.types.SYSCALL_THREAD_CREATE_ERROR_NONE) {
+        return switch (ret.@"error") {
+            syscalls.types.SYSCALL_THREAD_CREATE_ERROR_INVALID_ADDR => error.InvalidAddress,
+            else => @panic("thread_create unexpected error"),
+        };
+    }
+
+    return Thread{ .tid = ret.tid, .stack = stack };
+}
+
+/// Exits the calling thread, the process exits with `status` if it is the last thread
+pub fn threadExit(status: u32) noreturn {
+    var arg = syscalls.types.syscall_thread_exit_t{
+        .status = status,
+    };
+    syscalls.thread_exit(&arg) catch @panic("thread_exit failed");
+
+    @panic("thread_exit syscall returned, which should not happen");
 }
@@ -26,17 +26,66 @@ const SYSCALLS: []const Syscall = &[_]Syscall{
     Syscall{ .name = "map_framebuffer", .number = types.SYSCALL_MAP_FRAMEBUFFER, .arg_type = types.syscall_map_framebuffer_t, .return_type = types.syscall_map_framebuffer_return_t },
     Syscall{ .name = "write", .number = types.SYSCALL_WRITE, .arg_type = types.syscall_write_t, .return_type = types.syscall_write_return_t },
     Syscall{ .name = "waitpid", .number = types.SYSCALL_WAITPID, .arg_type = types.syscall_waitpid_t, .return_type = types.syscall_waitpid_return_t },
+    Syscall{ .name = "mprotect", .number = types.SYSCALL_MPROTECT, .arg_type = types.syscall_mprotect_t, .return_type = types.syscall_mprotect_return_t },
+    Syscall{ .name = "brk", .number = types.SYSCALL_BRK, .arg_type = types.syscall_brk_t, .return_type = types.syscall_brk_return_t },
+    Syscall{ .name = "nice", .number = types.SYSCALL_NICE, .arg_type = types.syscall_nice_t, .return_type = types.syscall_nice_return_t },
+    Syscall{ .name = "thread_create", .number = types.SYSCALL_THREAD_CREATE, .arg_type = types.syscall_thread_create_t, .return_type = types.syscall_thread_create_return_t },
+    Syscall{ .name = "thread_exit", .number = types.SYSCALL_THREAD_EXIT, .arg_type = types.syscall_thread_exit_t, .return_type = void },
+    Syscall{ .name = "thread_join", .number = types.SYSCALL_THREAD_JOIN, .arg_type = types.syscall_thread_join_t, .return_type = types.syscall_thread_join_return_t },
+    Syscall{ .name = "pipe", .number = types.SYSCALL_PIPE, .arg_type = types.syscall_pipe_t, .return_type = types.syscall_pipe_return_t },
+    Syscall{ .name = "dup", .number = types.SYSCALL_DUP, .arg_type = types.syscall_dup_t, .return_type = types.syscall_dup_return_t },
+    Syscall{ .name = "dup2", .number = types.SYSCALL_DUP2, .arg_type = types.syscall_dup2_t, .return_type = types.syscall_dup2_return_t },
+    Syscall{ .name = "kill", .number = types.SYSCALL_KILL, .arg_type = types.syscall_kill_t, .return_type = types.syscall_kill_return_t },
+    Syscall{ .name = "sigaction", .number = types.SYSCALL_SIGACTION, .arg_type = types.syscall_sigaction_t, .return_type = types.syscall_sigaction_return_t },
+    Syscall{ .name = "sigprocmask", .number = types.SYSCALL_SIGPROCMASK, .arg_type = types.syscall_sigprocmask_t, .return_type = types.syscall_sigprocmask_return_t },
+    Syscall{ .name = "sigreturn", .number = types.SYSCALL_SIGRETURN, .arg_type = void, .return_type = void },
+    Syscall{ .name = "getpid", .number = types.SYSCALL_GETPID, .arg_type = types.syscall_getpid_t, .return_type = types.syscall_getpid_return_t },
+    Syscall{ .name = "getppid", .number = types.SYSCALL_GETPPID, .arg_type = types.syscall_getppid_t, .return_type = types.syscall_getppid_return_t },
+    Syscall{ .name = "chdir", .number = types.SYSCALL_CHDIR, .arg_type = types.syscall_chdir_t, .return_type = types.syscall_chdir_return_t },
+    Syscall{ .name = "getcwd", .number = types.SYSCALL_GETCWD, .arg_type = types.syscall_getcwd_t, .return_type = types.syscall_getcwd_return_t },
+    Syscall{ .name = "mount", .number = types.SYSCALL_MOUNT, .arg_type = types.syscall_mount_t, .return_type = types.syscall_mount_return_t },
+    Syscall{ .name = "umount", .number = types.SYSCALL_UMOUNT, .arg_type = types.syscall_umount_t, .return_type = types.syscall_umount_return_t },
+    Syscall{ .name = "unlink", .number = types.SYSCALL_UNLINK, .arg_type = types.syscall_unlink_t, .return_type = types.syscall_unlink_return_t },
+    Syscall{ .name = "mkdir", .number = types.SYSCALL_MKDIR, .arg_type = types.syscall_mkdir_t, .return_type = types.syscall_mkdir_return_t },
+    Syscall{ .name = "rmdir", .number = types.SYSCALL_RMDIR, .arg_type = types.syscall_rmdir_t, .return_type = types.syscall_rmdir_return_t },
+    Syscall{ .name = "rename", .number = types.SYSCALL_RENAME, .arg_type = types.syscall_rename_t, .return_type = types.syscall_rename_return_t },
+    Syscall{ .name = "stat", .number = types.SYSCALL_STAT, .arg_type = types.syscall_stat_t, .return_type = types.syscall_stat_return_t },
+    Syscall{ .name = "fstat", .number = types.SYSCALL_FSTAT, .arg_type = types.syscall_fstat_t, .return_type = types.syscall_fstat_return_t },
+    Syscall{ .name = "lseek", .number = types.SYSCALL_LSEEK, .arg_type = types.syscall_lseek_t, .return_type = types.syscall_lseek_return_t },
+    Syscall{ .name = "pread", .number = types.SYSCALL_PREAD, .arg_type = types.syscall_pread_t, .return_type = types.syscall_pread_return_t },
+    Syscall{ .name = "pwrite", .number = types.SYSCALL_PWRITE, .arg_type = types.syscall_pwrite_t, .return_type = types.syscall_pwrite_return_t },
+    Syscall{ .name = "ioctl", .number = types.SYSCALL_IOCTL, .arg_type = types.syscall_ioctl_t, .return_type = types.syscall_ioctl_return_t },
+    Syscall{ .name = "getdents", .number = types.SYSCALL_GETDENTS, .arg_type = types.syscall_getdents_t, .return_type = types.syscall_getdents_return_t },
 };
 
-fn call(comptime syscall: Syscall, arg: *syscall.arg_type) syscall.return_type {
-    asm volatile (
+/// Errors every syscall can fail with, see `syscall_error_t`
+pub const Error = error{
+    Fault,
+    UnknownSyscall,
+    NotSupported,
+    Interrupted,
+};
+
+fn call(comptime syscall: Syscall, arg: *syscall.arg_type) Error!syscall.return_type {
+    const err = asm volatile (
         \\int $0x80
-        :
+        : [err] "={rax}" (-> types.syscall_error_t),
         : [number] "{rax}" (syscall.number),
           [arg] "{rbx}" (arg),
-        : "rax", "rbx", "memory"
+        : "rbx", "memory"
     );
 
+    switch (err) {
+        types.SYSCALL_ERROR_NONE => {},
+        types.SYSCALL_ERROR_FAULT => return error.Fault,
+        types.SYSCALL_ERROR_UNKNOWN_SYSCALL => return error.UnknownSyscall,
+        types.SYSCALL_ERROR_NOT_SUPPORTED => return error.NotSupported,
+        types.SYSCALL_ERROR_INTERRUPTED => return error.Interrupted,
+        types.SYSCALL_ERROR_TOO_LARGE => return error.TooLarge,
+        types.SYSCALL_ERROR_NO_MEMORY => return error.NoMemory,
+        else => @panic("unexpected error from syscall " ++ syscall.name),
+    }
+
     if (syscall.return_type == void) {
         return;
     } else {
@@ -44,45 +93,140 @@ fn call(comptime syscall: Syscall, arg: *syscall.arg_type) syscall.return_type {
     }
 }
 
-pub fn print(arg: *types.syscall_print_t) void {
-    call(SYSCALLS[0], arg);
+pub fn print(arg: *types.syscall_print_t) Error!void {
+    return call(SYSCALLS[0], arg);
 }
-pub fn sleep(arg: *types.syscall_sleep_t) void {
-    call(SYSCALLS[1], arg);
+pub fn sleep(arg: *types.syscall_sleep_t) Error!void {
+    return call(SYSCALLS[1], arg);
 }
-pub fn exit(arg: *types.syscall_exit_t) void {
-    call(SYSCALLS[2], arg);
+pub fn exit(arg: *types.syscall_exit_t) Error!void {
+    return call(SYSCALLS[2], arg);
 }
-pub fn listdir(arg: *types.syscall_listdir_t) types.syscall_listdir_return_t {
+pub fn listdir(arg: *types.syscall_listdir_t) Error!types.syscall_listdir_return_t {
     return call(SYSCALLS[3], arg);
 }
-pub fn read(arg: *types.syscall_read_t) types.syscall_read_return_t {
+pub fn read(arg: *types.syscall_read_t) Error!types.syscall_read_return_t {
     return call(SYSCALLS[4], arg);
 }
-pub fn fork(arg: *types.syscall_fork_t) types.syscall_fork_return_t {
+pub fn fork(arg: *types.syscall_fork_t) Error!types.syscall_fork_return_t {
     return call(SYSCALLS[5], arg);
 }
-pub fn open(arg: *types.syscall_open_t) types.syscall_open_return_t {
+pub fn open(arg: *types.syscall_open_t) Error!types.syscall_open_return_t {
     return call(SYSCALLS[6], arg);
 }
-pub fn close(arg: *types.syscall_close_t) types.syscall_close_return_t {
+pub fn close(arg: *types.syscall_close_t) Error!types.syscall_close_return_t {
     return call(SYSCALLS[7], arg);
 }
-pub fn mmap(arg: *types.syscall_mmap_t) types.syscall_mmap_return_t {
+pub fn mmap(arg: *types.syscall_mmap_t) Error!types.syscall_mmap_return_t {
     return call(SYSCALLS[8], arg);
 }
-pub fn munmap(arg: *types.syscall_munmap_t) types.syscall_munmap_return_t {
+pub fn munmap(arg: *types.syscall_munmap_t) Error!types.syscall_munmap_return_t {
     return call(SYSCALLS[9], arg);
 }
-pub fn execve(arg: *types.syscall_execve_t) types.syscall_execve_return_t {
+pub fn execve(arg: *types.syscall_execve_t) Error!types.syscall_execve_return_t {
     return call(SYSCALLS[10], arg);
 }
-pub fn mapFramebuffer(arg: *types.syscall_map_framebuffer_t) types.syscall_map_framebuffer_return_t {
+pub fn mapFramebuffer(arg: *types.syscall_map_framebuffer_t) Error!types.syscall_map_framebuffer_return_t {
     return call(SYSCALLS[11], arg);
 }
-pub fn write(arg: *types.syscall_write_t) types.syscall_write_return_t {
+pub fn write(arg: *types.syscall_write_t) Error!types.syscall_write_return_t {
     return call(SYSCALLS[12], arg);
 }
-pub fn waitpid(arg: *types.syscall_waitpid_t) types.syscall_waitpid_return_t {
+pub fn waitpid(arg: *types.syscall_waitpid_t) Error!types.syscall_waitpid_return_t {
     return call(SYSCALLS[13], arg);
 }
+pub fn mprotect(arg: *types.syscall_mprotect_t) Error!types.syscall_mprotect_return_t {
+    return call(SYSCALLS[14], arg);
+}
+pub fn brk(arg: *types.syscall_brk_t) Error!types.syscall_brk_return_t {
+    return call(SYSCALLS[15], arg);
+}
+pub fn nice(arg: *types.syscall_nice_t) Error!types.syscall_nice_return_t {
+    return call(SYSCALLS[16], arg);
+}
+pub fn thread_create(arg: *types.syscall_thread_create_t) Error!types.syscall_thread_create_return_t {
+    return call(SYSCALLS[17], arg);
+}
+pub fn thread_exit(arg: *types.syscall_thread_exit_t) Error!void {
+    return call(SYSCALLS[18], arg);
+}
+pub fn thread_join(arg: *types.syscall_thread_join_t) Error!types.syscall_thread_join_return_t {
+    return call(SYSCALLS[19], arg);
+}
+pub fn pipe(arg: *types.syscall_pipe_t) Error!types.syscall_pipe_return_t {
+    return call(SYSCALLS[20], arg);
+}
+pub fn dup(arg: *types.syscall_dup_t) Error!types.syscall_dup_return_t {
+    return call(SYSCALLS[21], arg);
+}
+pub fn dup2(arg: *types.syscall_dup2_t) Error!types.syscall_dup2_return_t {
+    return call(SYSCALLS[22], arg);
+}
+pub fn kill(arg: *types.syscall_kill_t) Error!types.syscall_kill_return_t {
+    return call(SYSCALLS[23], arg);
+}
+pub fn sigaction(arg: *types.syscall_sigaction_t) Error!types.syscall_sigaction_return_t {
+    return call(SYSCALLS[24], arg);
+}
+pub fn sigprocmask(arg: *types.syscall_sigprocmask_t) Error!types.syscall_sigprocmask_return_t {
+    return call(SYSCALLS[25], arg);
+}
+pub fn getpid(arg: *types.syscall_getpid_t) Error!types.syscall_getpid_return_t {
+    return call(SYSCALLS[27], arg);
+}
+pub fn getppid(arg: *types.syscall_getppid_t) Error!types.syscall_getppid_return_t {
+    return call(SYSCALLS[28], arg);
+}
+pub fn chdir(arg: *types.syscall_chdir_t) Error!types.syscall_chdir_return_t {
+    return call(SYSCALLS[29], arg);
+}
+pub fn getcwd(arg: *types.syscall_getcwd_t) Error!types.syscall_getcwd_return_t {
+    return call(SYSCALLS[30], arg);
+}
+pub fn mount(arg: *types.syscall_mount_t) Error!types.syscall_mount_return_t {
+    return call(SYSCALLS[31], arg);
+}
+pub fn umount(arg: *types.syscall_umount_t) Error!types.syscall_umount_return_t {
+    return call(SYSCALLS[32], arg);
+}
+pub fn unlink(arg: *types.syscall_unlink_t) Error!types.syscall_unlink_return_t {
+    return call(SYSCALLS[33], arg);
+}
+pub fn mkdir(arg: *types.syscall_mkdir_t) Error!types.syscall_mkdir_return_t {
+    return call(SYSCALLS[34], arg);
+}
+pub fn rmdir(arg: *types.syscall_rmdir_t) Error!types.syscall_rmdir_return_t {
+    return call(SYSCALLS[35], arg);
+}
+pub fn rename(arg: *types.syscall_rename_t) Error!types.syscall_rename_return_t {
+    return call(SYSCALLS[36], arg);
+}
+pub fn stat(arg: *types.syscall_stat_t) Error!types.syscall_stat_return_t {
+    return call(SYSCALLS[37], arg);
+}
+pub fn fstat(arg: *types.syscall_fstat_t) Error!types.syscall_fstat_return_t {
+    return call(SYSCALLS[38], arg);
+}
+pub fn lseek(arg: *types.syscall_lseek_t) Error!types.syscall_lseek_return_t {
+    return call(SYSCALLS[39], arg);
+}
+pub fn pread(arg: *types.syscall_pread_t) Error!types.syscall_pread_return_t {
+    return call(SYSCALLS[40], arg);
+}
+pub fn pwrite(arg: *types.syscall_pwrite_t) Error!types.syscall_pwrite_return_t {
+    return call(SYSCALLS[41], arg);
+}
+pub fn ioctl(arg: *types.syscall_ioctl_t) Error!types.syscall_ioctl_return_t {
+    return call(SYSCALLS[42], arg);
+}
+pub fn getdents(arg: *types.syscall_getdents_t) Error!types.syscall_getdents_return_t {
+    return call(SYSCALLS[43], arg);
+}
+
+/// Signal handlers return here, sigreturn finds the interrupted state at the stack pointer
+pub fn sigreturn() callconv(.naked) noreturn {
+    asm volatile (std.fmt.comptimePrint(
+            \movq ${d}, %%rax
+            \int $0x80
+        , .{types.SYSCALL_SIGRETURN}));
+}
@@ -117,6 +117,9 @@ export fn _main() noreturn {
 }
 
 fn main() !void {
+    // Ctrl+C on the keyboard is meant for the programs in the terminals
+    try soos.signal(soos.SIGINT, .ignore);
+
     var heap = std.heap.ArenaAllocator.init(soos.pageAllocator());
     const allocator = heap.allocator();
 
@@ -30,20 +30,29 @@ pub const Terminal = struct {
         const str = "Welcome to SoOS!\n";
         @memcpy(contents[0..str.len], str);
 
+        // the shell reads its input from one pipe and writes its output to the other
+        const stdinPipe = try soos.pipe();
+        const stdoutPipe = try soos.pipe();
+
         const child = soos.fork();
         if (child == 0) {
+            _ = try soos.dup2(stdinPipe.read, 0);
+            _ = try soos.dup2(stdoutPipe.write, 1);
+            try soos.close(stdinPipe.read);
+            try soos.close(stdinPipe.write);
+            try soos.close(stdoutPipe.read);
+            try soos.close(stdoutPipe.write);
+
             soos.execve("/bin/sosh", &.{}) catch |err| {
                 std.log.err("failed to exec sosh: {}", .{err});
                 @panic("failed to exec sosh");
             };
         }
 
-        var buf: [64]u8 = undefined;
-        const childStdinPath = try std.fmt.bufPrint(&buf, "/proc/{d}/stdin", .{child});
-        const childStdin = try soos.open(childStdinPath);
-
-        const childStdoutPath = try std.fmt.bufPrint(&buf, "/proc/{d}/stdout", .{child});
-        const childStdout = try soos.open(childStdoutPath);
+        try soos.close(stdinPipe.read);
+        try soos.close(stdoutPipe.write);
+        const childStdin = stdinPipe.write;
+        const childStdout = stdoutPipe.read;
 
         const keyboardFd = try soos.open("/dev/keyboard");
 
@@ -62,17 +62,7 @@ export fn _start() callconv(.naked) void {
 }
 
 export fn _main() noreturn {
-    var argBuffer: [512][]const u8 = undefined;
-    for (0.._entry_ptr.argc) |i| {
-        const entry = _entry_ptr.argv[i];
-        argBuffer[i] = entry.ptr[0..entry.len];
-    }
-
-    main(argBuffer[0.._entry_ptr.argc]) catch |err| {
-        std.log.err("error: {}\n", .{err});
-        soos.exit(1);
-    };
-    soos.exit(0);
+    soos.start(_entry_ptr, main);
 }
 
 fn main(args: []const []const u8) !void {
@@ -82,11 +72,19 @@ fn main(args: []const []const u8) !void {
     }
 
     for (args[1..]) |arg| {
-        const fd = try soos.open(arg);
+        const fd = soos.open(arg) catch |err| {
+            std.log.err("cat: cannot open '{s}': {}", .{ arg, err });
+            continue;
+        };
         defer {
             soos.close(fd) catch @panic("failed to close file");
         }
 
+        if ((try soos.fstat(fd)).type == .directory) {
+            std.log.err("cat: '{s}' is a directory", .{arg});
+            continue;
+        }
+
         var buffer: [4096]u8 = undefined;
         while (true) {
             const bytes_read = try soos.read(fd, &buffer, true);
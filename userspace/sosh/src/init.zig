@@ -0,0 +1,127 @@
+const std = @import("std");
+
+const soos = @import("soos");
+
+const ANSI_RESET = "\x1b[0m";
+const ANSI_FG_RED = "\x1b[31m";
+const ANSI_FG_GREEN = "\x1b[32m";
+const ANSI_FG_YELLOW = "\x1b[33m";
+const ANSI_FG_CYAN = "\x1b[36m";
+
+pub const std_options = std.Options{
+    .page_size_max = 4096,
+    .log_level = .debug,
+    .logFn = struct {
+        fn f(
+            comptime message_level: std.log.Level,
+            comptime _: @TypeOf(.enum_literal),
+            comptime format: []const u8,
+            args: anytype,
+        ) void {
+            const colors = switch (message_level) {
+                .debug => ANSI_FG_CYAN,
+                .info => ANSI_FG_GREEN,
+                .warn => ANSI_FG_YELLOW,
+                .err => ANSI_FG_RED,
+            };
+            soos.print("{s}[{s}] ", .{ colors, @tagName(message_level) });
+            soos.print(format, args);
+            soos.print("{s}\n", .{ANSI_RESET});
+        }
+    }.f,
+};
+
+pub fn panic(message: []const u8, _: ?*std.builtin.StackTrace, _: ?usize) noreturn {
+    std.log.err("{s}init panic: {s}\n", .{ ANSI_FG_RED, message });
+    soos.exit(1);
+}
+
+const shell = "/bin/sosh";
+
+/// The environment the shells start with
+const environment = [_][]const u8{ "PATH=/bin", "HOME=/home" };
+
+/// A console a shell runs on
+const Console = struct {
+    name: []const u8,
+    /// the device the shell reads its input from and writes its output to
+    device: []const u8,
+    /// the shell currently running on the console, 0 if there is none
+    pid: u32 = 0,
+};
+
+var consoles = [_]Console{
+    .{ .name = "terminal", .device = "/dev/tty" },
+    .{ .name = "serial", .device = "/dev/ttyS0" },
+};
+
+/// Starts a shell with its standard input and output connected to `console`
+fn spawnShell(console: *Console) !void {
+    const input = soos.open(console.device) catch |err| switch (err) {
+        // the console is not available on this machine
+        error.NotFound => return,
+        else => return err,
+    };
+    const output = try soos.dup(input);
+
+    const pid = soos.fork();
+    if (pid == 0) {
+        _ = try soos.dup2(input, 0);
+        _ = try soos.dup2(output, 1);
+        try soos.close(input);
+        try soos.close(output);
+
+        soos.execveWithEnv(shell, &.{shell}, &environment) catch |err| {
+            std.log.err("failed to execute '{s}' on {s}: {}", .{ shell, console.name, err });
+        };
+        soos.exit(1);
+    }
+
+    try soos.close(input);
+    try soos.close(output);
+
+    console.pid = pid;
+    std.log.info("started '{s}' on {s} with pid {d}", .{ shell, console.name, pid });
+}
+
+var _entry_ptr: *const soos.types.entry_t = undefined;
+
+export fn _start() callconv(.naked) void {
+    // on entry there is a pointer on the stack to the arguments
+    asm volatile (
+        \\ pop %rdi
+        : [entry_pointer] "={rdi}" (_entry_ptr),
+        :
+        : "rdi", "memory"
+    );
+
+    asm volatile (
+        \\ call _main
+    );
+}
+
+export fn _main() noreturn {
+    soos.start(_entry_ptr, main);
+}
+
+fn main(_: []const []const u8) !void {
+    // Ctrl+C is meant for the programs running in the shells
+    try soos.signal(soos.SIGINT, .ignore);
+
+    for (&consoles) |*console| {
+        try spawnShell(console);
+    }
+
+    // orphaned processes become children of init, so this also reaps them
+    while (true) {
+        const child = (try soos.wait(soos.ANY_CHILD, true)).?;
+
+        for (&consoles) |*console| {
+            if (console.pid == child.pid) {
+                std.log.info("shell on {s} exited with status {d}, restarting it", .{ console.name, child.status });
+                console.pid = 0;
+                try spawnShell(console);
+            }
+        }
+    }
+}
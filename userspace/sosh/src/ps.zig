@@ -0,0 +1,141 @@
+const std = @import("std");
+
+const soos = @import("soos");
+
+const ANSI_RESET = "\x1b[0m";
+const ANSI_FG_RED = "\x1b[31m";
+const ANSI_FG_GREEN = "\x1b[32m";
+const ANSI_FG_YELLOW = "\x1b[33m";
+const ANSI_FG_BLUE = "\x1b[34m";
+const ANSI_FG_MAGENTA = "\x1b[35m";
+const ANSI_FG_CYAN = "\x1b[36m";
+const ANSI_FG_WHITE = "\x1b[37m";
+
+pub const std_options = std.Options{
+    .page_size_max = 4096,
+    .log_level = .debug,
+    .logFn = struct {
+        fn f(
+            comptime message_level: std.log.Level,
+            comptime _: @TypeOf(.enum_literal),
+            comptime format: []const u8,
+            args: anytype,
+        ) void {
+            const colors = switch (message_level) {
+                .debug => ANSI_FG_CYAN,
+                .info => ANSI_FG_GREEN,
+                .warn => ANSI_FG_YELLOW,
+                .err => ANSI_FG_RED,
+            };
+
+            soos.print("{s}[{s}] ", .{ colors, @tagName(message_level) });
+            soos.print(format, args);
+            soos.print("{s}\n", .{ANSI_RESET});
+        }
+    }.f,
+};
+
+const DummyMutex = struct {
+    pub fn lock(_: *@This()) void {}
+    pub fn unlock(_: *@This()) void {}
+};
+
+pub fn panic(message: []const u8, _: ?*std.builtin.StackTrace, _: ?usize) noreturn {
+    std.log.err("{s}ps panic: {s}\n", .{ ANSI_FG_RED, message });
+    soos.exit(1);
+}
+
+var _entry_ptr: *const soos.types.entry_t = undefined;
+
+export fn _start() callconv(.naked) void {
+    // on entry there is a pointer on the stack to the arguments
+    asm volatile (
+        \\ pop %rdi
+        : [entry_pointer] "={rdi}" (_entry_ptr),
+        :
+        : "rdi", "memory"
+    );
+
+    asm volatile (
+        \\ call _main
+    );
+}
+
+export fn _main() noreturn {
+    soos.start(_entry_ptr, main);
+}
+
+/// Reads the start of the file at `path`, as much as fits into `buffer`
+fn readFile(path: []const u8, buffer: []u8) ![]u8 {
+    const fd = try soos.open(path);
+    defer {
+        soos.close(fd) catch @panic("failed to close file");
+    }
+
+    var len: usize = 0;
+    while (len < buffer.len) {
+        const bytes_read = try soos.read(fd, buffer[len..], true);
+        if (bytes_read == 0) break; // EOF
+        len += bytes_read;
+    }
+    return buffer[0..len];
+}
+
+/// Returns the value of a "name: value" line of the process part of a status file
+fn field(status: []const u8, name: []const u8) []const u8 {
+    var lines = std.mem.splitScalar(u8, status, '\n');
+    while (lines.next()) |line| {
+        // the sections about the threads follow after an empty line
+        if (line.len == 0) break;
+
+        if (std.mem.startsWith(u8, line, name) and line.len > name.len and line[name.len] == ':') {
+            return std.mem.trim(u8, line[name.len + 1 ..], " ");
+        }
+    }
+    return "?";
+}
+
+/// Strips the unit from a value like "120 ms"
+fn number(value: []const u8) []const u8 {
+    return value[0 .. std.mem.indexOfScalar(u8, value, ' ') orelse value.len];
+}
+
+fn main(args: []const []const u8) !void {
+    if (args.len > 1) {
+        std.log.err("Usage: ps", .{});
+        return error.InvalidArguments;
+    }
+
+    var proc = try soos.opendir("/proc");
+    defer proc.deinit();
+
+    soos.print("{s:>5} {s:>5} {s:<9} {s:>10} {s:>10} {s}\n", .{ "PID", "PPID", "STATE", "TIME(ms)", "RSS(KiB)", "COMMAND" });
+
+    while (try proc.next()) |entry| {
+        _ = std.fmt.parseInt(u32, entry.name, 10) catch continue;
+
+        var pathBuffer: [64]u8 = undefined;
+        var statusBuffer: [1024]u8 = undefined;
+        var cmdlineBuffer: [256]u8 = undefined;
+
+        // processes can be reaped while they are listed
+        const statusPath = try std.fmt.bufPrint(&pathBuffer, "/proc/{s}/status", .{entry.name});
+        const status = readFile(statusPath, &statusBuffer) catch continue;
+        if (status.len == 0) continue;
+
+        const cmdlinePath = try std.fmt.bufPrint(&pathBuffer, "/proc/{s}/cmdline", .{entry.name});
+        const arguments = readFile(cmdlinePath, &cmdlineBuffer) catch continue;
+        // every argument is followed by a NUL byte
+        const cmdline = arguments[0..std.mem.trimRight(u8, arguments, "\x00").len];
+        std.mem.replaceScalar(u8, cmdline, 0, ' ');
+
+        soos.print("{s:>5} {s:>5} {s:<9} {s:>10} {s:>10} {s}\n", .{
+            field(status, "pid"),
+            field(status, "ppid"),
+            field(status, "state"),
+            number(field(status, "cpu time")),
+            number(field(status, "resident memory")),
+            if (cmdline.len > 0) cmdline else field(status, "name"),
+        });
+    }
+}
@@ -62,17 +62,7 @@ export fn _start() callconv(.naked) void {
 }
 
 export fn _main() noreturn {
-    var argBuffer: [512][]const u8 = undefined;
-    for (0.._entry_ptr.argc) |i| {
-        const entry = _entry_ptr.argv[i];
-        argBuffer[i] = entry.ptr[0..entry.len];
-    }
-
-    main(argBuffer[0.._entry_ptr.argc]) catch |err| {
-        std.log.err("error: {}\n", .{err});
-        soos.exit(1);
-    };
-    soos.exit(0);
+    soos.start(_entry_ptr, main);
 }
 
 const Snake = struct {
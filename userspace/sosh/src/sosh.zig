@@ -66,23 +66,179 @@ const commands: []const Command = &[_]Command{
         .name = "ls",
         .run = struct {
             fn ls(argv: []const []const u8) !void {
-                if (argv.len != 2) {
-                    print("usage: ls <directory>\n", .{});
+                if (argv.len > 2) {
+                    print("usage: ls [directory]\n", .{});
                     return;
                 }
-                var listDir = soos.listdir(argv[1]) catch |err| {
-                    print("Error: Failed to list directory '{s}': {}\n", .{ argv[1], err });
+                const path = if (argv.len == 2) argv[1] else ".";
+                var dir = soos.opendir(path) catch |err| {
+                    print("Error: Failed to list directory '{s}': {}\n", .{ path, err });
                     return;
                 };
-                while (listDir.next()) |entry| {
+                defer dir.deinit();
+
+                while (dir.next() catch |err| {
+                    print("Error: Failed to list directory '{s}': {}\n", .{ path, err });
+                    return;
+                }) |entry| {
                     switch (entry.type) {
-                        .file => print("{s}{s}\n", .{ ANSI_FG_BLUE, entry.name }),
-                        .directory => print("{s}{s}/\n", .{ ANSI_FG_CYAN, entry.name }),
+                        .directory => print("{s}{d:>8} {s:>10} {s}{s}/\n", .{ ANSI_RESET, entry.inode, "-", ANSI_FG_CYAN, entry.name }),
+                        .symlink => print("{s}{d:>8} {s:>10} {s}{s}@\n", .{ ANSI_RESET, entry.inode, "-", ANSI_FG_MAGENTA, entry.name }),
+                        .device, .stream => print("{s}{d:>8} {d:>10} {s}{s}\n", .{ ANSI_RESET, entry.inode, entry.size, ANSI_FG_YELLOW, entry.name }),
+                        .regular => print("{s}{d:>8} {d:>10} {s}{s}\n", .{ ANSI_RESET, entry.inode, entry.size, ANSI_FG_BLUE, entry.name }),
                     }
                 }
             }
         }.ls,
     },
+    .{
+        .name = "cd",
+        .run = struct {
+            fn cd(argv: []const []const u8) !void {
+                if (argv.len > 2) {
+                    print("usage: cd [directory]\n", .{});
+                    return;
+                }
+                const path = if (argv.len == 2) argv[1] else soos.getenv("HOME") orelse "/";
+                soos.chdir(path) catch |err| {
+                    print("{s}error: cannot change directory to '{s}': {}{s}\n", .{ ANSI_FG_RED, path, err, ANSI_RESET });
+                };
+            }
+        }.cd,
+    },
+    .{
+        .name = "pwd",
+        .run = struct {
+            fn pwd(_: []const []const u8) !void {
+                var buffer: [256]u8 = undefined;
+                print("{s}\n", .{try soos.getcwd(&buffer)});
+            }
+        }.pwd,
+    },
+    .{
+        .name = "echo",
+        .run = struct {
+            fn echo(argv: []const []const u8) !void {
+                // written at once, so that a file in /sys gets the whole value
+                var buffer: [1024]u8 = undefined;
+                var stream = std.io.fixedBufferStream(&buffer);
+                for (argv[1..], 0..) |arg, i| {
+                    if (i > 0) try stream.writer().writeByte(' ');
+                    try stream.writer().writeAll(arg);
+                }
+                try stream.writer().writeByte('\n');
+                _ = try soos.write(1, stream.getWritten());
+            }
+        }.echo,
+    },
+    .{
+        .name = "touch",
+        .run = struct {
+            fn touch(argv: []const []const u8) !void {
+                if (argv.len < 2) {
+                    print("usage: touch <file>...\n", .{});
+                    return;
+                }
+                for (argv[1..]) |path| {
+                    const fd = soos.openWithMode(path, soos.OPEN_WRITE | soos.OPEN_CREATE) catch |err| {
+                        print("{s}error: cannot create '{s}': {}{s}\n", .{ ANSI_FG_RED, path, err, ANSI_RESET });
+                        continue;
+                    };
+                    try soos.close(fd);
+                }
+            }
+        }.touch,
+    },
+    .{
+        .name = "mkdir",
+        .run = struct {
+            fn mkdir(argv: []const []const u8) !void {
+                if (argv.len < 2) {
+                    print("usage: mkdir <directory>...\n", .{});
+                    return;
+                }
+                for (argv[1..]) |path| {
+                    soos.mkdir(path) catch |err| {
+                        print("{s}error: cannot create directory '{s}': {}{s}\n", .{ ANSI_FG_RED, path, err, ANSI_RESET });
+                    };
+                }
+            }
+        }.mkdir,
+    },
+    .{
+        .name = "rmdir",
+        .run = struct {
+            fn rmdir(argv: []const []const u8) !void {
+                if (argv.len < 2) {
+                    print("usage: rmdir <directory>...\n", .{});
+                    return;
+                }
+                for (argv[1..]) |path| {
+                    soos.rmdir(path) catch |err| {
+                        print("{s}error: cannot remove directory '{s}': {}{s}\n", .{ ANSI_FG_RED, path, err, ANSI_RESET });
+                    };
+                }
+            }
+        }.rmdir,
+    },
+    .{
+        .name = "rm",
+        .run = struct {
+            fn rm(argv: []const []const u8) !void {
+                if (argv.len < 2) {
+                    print("usage: rm <file>...\n", .{});
+                    return;
+                }
+                for (argv[1..]) |path| {
+                    soos.unlink(path) catch |err| {
+                        print("{s}error: cannot remove '{s}': {}{s}\n", .{ ANSI_FG_RED, path, err, ANSI_RESET });
+                    };
+                }
+            }
+        }.rm,
+    },
+    .{
+        .name = "mv",
+        .run = struct {
+            fn mv(argv: []const []const u8) !void {
+                if (argv.len != 3) {
+                    print("usage: mv <source> <destination>\n", .{});
+                    return;
+                }
+                soos.rename(argv[1], argv[2]) catch |err| {
+                    print("{s}error: cannot move '{s}' to '{s}': {}{s}\n", .{ ANSI_FG_RED, argv[1], argv[2], err, ANSI_RESET });
+                };
+            }
+        }.mv,
+    },
+    .{
+        .name = "mount",
+        .run = struct {
+            fn mount(argv: []const []const u8) !void {
+                if (argv.len != 3) {
+                    print("usage: mount <ramfs|proc|dev|sys> <directory>\n", .{});
+                    return;
+                }
+                soos.mount(argv[1], argv[2]) catch |err| {
+                    print("{s}error: cannot mount {s} at '{s}': {}{s}\n", .{ ANSI_FG_RED, argv[1], argv[2], err, ANSI_RESET });
+                };
+            }
+        }.mount,
+    },
+    .{
+        .name = "umount",
+        .run = struct {
+            fn umount(argv: []const []const u8) !void {
+                if (argv.len != 2) {
+                    print("usage: umount <directory>\n", .{});
+                    return;
+                }
+                soos.umount(argv[1]) catch |err| {
+                    print("{s}error: cannot unmount '{s}': {}{s}\n", .{ ANSI_FG_RED, argv[1], err, ANSI_RESET });
+                };
+            }
+        }.umount,
+    },
     .{
         .name = "fork",
         .run = struct {
@@ -97,6 +253,28 @@ const commands: []const Command = &[_]Command{
             }
         }.fork,
     },
+    .{
+        .name = "kill",
+        .run = struct {
+            fn kill(argv: []const []const u8) !void {
+                if (argv.len < 2 or argv.len > 3) {
+                    print("usage: kill <pid> [signal]\n", .{});
+                    return;
+                }
+                const pid = std.fmt.parseInt(u32, argv[1], 10) catch {
+                    print("{s}error: invalid pid '{s}'{s}\n", .{ ANSI_FG_RED, argv[1], ANSI_RESET });
+                    return;
+                };
+                const signal = if (argv.len == 3) std.fmt.parseInt(u32, argv[2], 10) catch {
+                    print("{s}error: invalid signal '{s}'{s}\n", .{ ANSI_FG_RED, argv[2], ANSI_RESET });
+                    return;
+                } else soos.SIGTERM;
+                soos.kill(pid, signal) catch |err| {
+                    print("{s}error: failed to send signal {d} to {d}: {}{s}\n", .{ ANSI_FG_RED, signal, pid, err, ANSI_RESET });
+                };
+            }
+        }.kill,
+    },
     .{
         .name = "test",
         .run = struct {
@@ -292,25 +470,33 @@ pub fn panic(message: []const u8, _: ?*std.builtin.StackTrace, _: ?usize) noretu
     soos.exit(1);
 }
 
+var _entry_ptr: *const soos.types.entry_t = undefined;
+
 export fn _start() callconv(.naked) void {
+    // on entry there is a pointer on the stack to the arguments
+    asm volatile (
+        \\ pop %rdi
+        : [entry_pointer] "={rdi}" (_entry_ptr),
+        :
+        : "rdi", "memory"
+    );
+
     asm volatile (
-        \\
         \\ call _main
     );
 }
 
 export fn _main() noreturn {
-    main() catch |err| {
-        std.log.err("error: {}\n", .{err});
-        soos.exit(1);
-    };
-    soos.exit(0);
+    soos.start(_entry_ptr, main);
 }
 
-fn main() !void {
+fn main(_: []const []const u8) !void {
     var commandBuffer: [1024]u8 = undefined;
     var commandLength: u64 = 0;
 
+    // Ctrl+C interrupts the running command, not the shell
+    try soos.signal(soos.SIGINT, .ignore);
+
     reset();
     print("{s}_", .{prompt});
 
@@ -348,7 +534,7 @@ fn main() !void {
                         continue;
                     }
 
-                    command(argv[0..argc]) catch |err| {
+                    redirectedCommand(argv[0..argc]) catch |err| {
                         print("error: command failed: {}\n", .{err});
                     };
 
@@ -370,6 +556,35 @@ fn main() !void {
     }
 }
 
+/// Runs the command with its output written to the file after a trailing `> file`, or appended to it with `>> file`
+fn redirectedCommand(argv: []const []const u8) !void {
+    if (argv.len < 3) return command(argv);
+
+    const operator = argv[argv.len - 2];
+    const mode = if (std.mem.eql(u8, operator, ">"))
+        soos.OPEN_WRITE | soos.OPEN_CREATE | soos.OPEN_TRUNCATE
+    else if (std.mem.eql(u8, operator, ">>"))
+        soos.OPEN_WRITE | soos.OPEN_CREATE | soos.OPEN_APPEND
+    else
+        return command(argv);
+
+    const path = argv[argv.len - 1];
+    const fd = soos.openWithMode(path, mode) catch |err| {
+        print("{s}error: cannot open '{s}': {}{s}\n", .{ ANSI_FG_RED, path, err, ANSI_RESET });
+        return;
+    };
+    defer soos.close(fd) catch {};
+
+    const stdout = try soos.dup(1);
+    defer {
+        _ = soos.dup2(stdout, 1) catch @panic("Failed to restore stdout");
+        soos.close(stdout) catch {};
+    }
+    _ = try soos.dup2(fd, 1);
+
+    try command(argv[0 .. argv.len - 2]);
+}
+
 fn command(argv: []const []const u8) !void {
     // check for built-in commands
     for (commands) |cmd| {
@@ -383,6 +598,7 @@ fn command(argv: []const []const u8) !void {
     if (soos.open(argv[0]) catch null) |_| {
         const pid = soos.fork();
         if (pid == 0) {
+            try soos.signal(soos.SIGINT, .default);
             try soos.execve(argv[0], argv);
         } else {
             _ = try soos.waitpid(pid);
@@ -397,6 +613,7 @@ fn command(argv: []const []const u8) !void {
         if (std.mem.eql(u8, entry.name, argv[0])) {
             const pid = soos.fork();
             if (pid == 0) {
+                try soos.signal(soos.SIGINT, .default);
                 var filename: [256]u8 = undefined;
                 const str = try std.fmt.bufPrint(&filename, "/bin/{s}", .{entry.name});
                 soos.execve(str, argv) catch |err| {